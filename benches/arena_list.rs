@@ -0,0 +1,98 @@
+//! Compares the slab backed `ArenaList` with the pointer based `LinkedList`:
+//! time and heap allocations per operation for building, walking and
+//! churning a list.
+//!
+//! Run with `cargo bench --bench arena_list`.
+
+use data_structures::{ArenaList, LinkedList};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const SIZE: usize = 100_000;
+
+fn measure<F: FnMut(usize)>(name: &str, operations: usize, mut operation: F) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for i in 0..operations {
+        operation(i);
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    println!(
+        "{:<30} {:>8.3} allocations/op {:>10.1} ns/op",
+        name,
+        allocations as f64 / operations as f64,
+        elapsed.as_nanos() as f64 / operations as f64
+    );
+}
+
+fn main() {
+    let mut linked = LinkedList::new();
+    let mut arena = ArenaList::new();
+    measure("linked list add", SIZE, |i| linked.add(i as u64));
+    measure("arena list add", SIZE, |i| arena.add(i as u64));
+
+    measure("linked list push_front", SIZE, |i| {
+        linked.push_front(i as u64)
+    });
+    measure("arena list push_front", SIZE, |i| {
+        arena.push_front(i as u64)
+    });
+
+    measure("linked list iterate", 10, |_| {
+        black_box(linked.iter().sum::<u64>());
+    });
+    measure("arena list iterate", 10, |_| {
+        black_box(arena.iter().sum::<u64>());
+    });
+
+    // move items from the front to the back, freeing and reusing nodes
+    measure("linked list churn", SIZE, |_| {
+        let item = linked.pop_front().unwrap();
+        linked.add(item);
+    });
+    measure("arena list churn", SIZE, |_| {
+        let item = arena.pop_front().unwrap();
+        arena.add(item);
+    });
+
+    measure("linked list sort", 1, |_| linked.sort());
+    measure("arena list sort", 1, |_| arena.sort());
+
+    measure("linked list iterate sorted", 10, |_| {
+        black_box(linked.iter().sum::<u64>());
+    });
+    measure("arena list iterate sorted", 10, |_| {
+        black_box(arena.iter().sum::<u64>());
+    });
+}
@@ -0,0 +1,129 @@
+//! Counts heap allocations per `ArrayList` insert and remove, comparing the
+//! in-place implementation with the previous copy-and-merge strategy.
+//!
+//! Run with `cargo bench --bench array_list`.
+
+use data_structures::{Array, ArrayList, Result};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const INITIAL: usize = 1000;
+const OPERATIONS: usize = 1000;
+
+/// The insert/remove strategy `ArrayList` used before shifting in place:
+/// every edit builds new arrays with `copy_range` and `merge`.
+struct CopyMergeList {
+    size: usize,
+    array: Array<u64>,
+}
+
+impl CopyMergeList {
+    fn new() -> Self {
+        CopyMergeList {
+            size: 0,
+            array: Array::new(4),
+        }
+    }
+
+    fn add(&mut self, item: u64) -> Result<()> {
+        if self.array.size() == self.size {
+            self.array = self.array.copy_with_size(self.size * 2);
+        }
+        self.array.set(self.size, item)?;
+        self.size += 1;
+        Ok(())
+    }
+
+    fn insert(&mut self, index: usize, item: u64) -> Result<()> {
+        if self.array.size() == self.size {
+            self.array = self.array.copy_with_size(self.size * 2);
+        }
+        let mut left = self.array.copy_range(0, index)?;
+        let mut help_array = Array::new(1);
+        help_array.set(0, item)?;
+        left = left.merge(&help_array, left.size() + 1)?;
+        let right = self.array.copy_range(index, self.size)?;
+        self.array = left.merge(&right, self.array.size())?;
+        self.size += 1;
+        Ok(())
+    }
+
+    fn remove(&mut self, index: usize) -> Result<u64> {
+        let item = self.array.take(index)?;
+        let left = self.array.copy_range(0, index)?;
+        let right = self.array.copy_range(index + 1, self.array.size())?;
+        self.array = left.merge(&right, self.array.size())?;
+        self.size -= 1;
+        Ok(item)
+    }
+}
+
+fn measure<F: FnMut(usize)>(name: &str, mut operation: F) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    for i in 0..OPERATIONS {
+        operation(i);
+    }
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    println!(
+        "{:<30} {:>8.3} allocations/op {:>10.1} ns/op",
+        name,
+        allocations as f64 / OPERATIONS as f64,
+        elapsed.as_nanos() as f64 / OPERATIONS as f64
+    );
+}
+
+fn main() {
+    let mut before = CopyMergeList::new();
+    let mut after = ArrayList::new();
+    for i in 0..INITIAL as u64 {
+        before.add(i).unwrap();
+        after.add(i);
+    }
+
+    measure("copy-and-merge insert", |i| {
+        before.insert(before.size / 2, i as u64).unwrap()
+    });
+    measure("in-place insert", |i| {
+        after.insert(after.size() / 2, i as u64).unwrap()
+    });
+    measure("copy-and-merge remove", |_| {
+        before.remove(before.size / 2).unwrap();
+    });
+    measure("in-place remove", |_| {
+        after.remove(after.size() / 2).unwrap();
+    });
+
+    let mut after = ArrayList::new();
+    measure("in-place add", |i| after.add(i as u64));
+}
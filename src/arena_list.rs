@@ -0,0 +1,928 @@
+//! Doubly linked list whose nodes live in one growable [`Array`] slab.
+//!
+//! Nodes refer to each other by `u32` slot index instead of by pointer, so
+//! linking and unlinking nodes needs no `unsafe`, a node costs no
+//! allocation of its own and neighbouring nodes tend to share cache lines.
+//! Only the mutable iterator hands out items through a raw pointer to the
+//! slab. Slots of removed nodes
+//! are chained into a free list and reused before the slab grows.
+//!
+//! Moving nodes between two lists means moving their items from one slab
+//! to the other, so `append`, `splice_at`, `split_off` and the cursor
+//! splits take time linear in the number of moved items, unlike their O(1)
+//! [`LinkedList`] counterparts.
+//!
+//! [`LinkedList`]: crate::linked_list::LinkedList
+
+use crate::array::Array;
+use crate::error::{Error, Result};
+use crate::merge_sort::{self, Chain};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::{FromIterator, FusedIterator};
+use std::marker::PhantomData;
+use std::mem;
+
+/// Index standing for "no node", like a null pointer.
+const NIL: u32 = u32::MAX;
+
+/// Capacity of the slab after its first allocation.
+const INITIAL_CAPACITY: usize = 4;
+
+struct ArenaNode<T> {
+    data: T,
+    next: u32,
+    previous: u32,
+}
+
+enum ArenaSlot<T> {
+    Node(ArenaNode<T>),
+    /// Unused slot, linked to the next unused one.
+    Free {
+        next: u32,
+    },
+}
+
+pub struct ArenaList<T> {
+    size: usize,
+    begin: u32,
+    end: u32,
+    /// First unused slot. Every slot of the slab is initialized, either
+    /// with a node or as part of the free list.
+    free: u32,
+    slots: Array<ArenaSlot<T>>,
+}
+
+/// Iterator over references to the items of an [`ArenaList`].
+///
+/// Follows the slot indices inward from both ends and counts the items
+/// left, so the two ends never hand out the same item.
+pub struct ArenaListIterator<'a, T> {
+    size: usize,
+    begin: u32,
+    end: u32,
+    list: &'a ArenaList<T>,
+}
+
+impl<'a, T> Iterator for ArenaListIterator<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        if self.size == 0 {
+            return None;
+        }
+        let node = self.list.node(self.begin);
+        self.begin = node.next;
+        self.size -= 1;
+        Some(&node.data)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for ArenaListIterator<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.size == 0 {
+            return None;
+        }
+        let node = self.list.node(self.end);
+        self.end = node.previous;
+        self.size -= 1;
+        Some(&node.data)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for ArenaListIterator<'a, T> {}
+
+impl<'a, T> FusedIterator for ArenaListIterator<'a, T> {}
+
+/// Iterator over mutable references to the items of an [`ArenaList`].
+///
+/// Works on a raw pointer to the slots rather than a borrow of the list,
+/// so that items taken from either end can be borrowed at the same time.
+pub struct ArenaListIteratorMut<'a, T> {
+    size: usize,
+    begin: u32,
+    end: u32,
+    slots: *mut ArenaSlot<T>,
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> ArenaListIteratorMut<'a, T> {
+    /// Node at `index`, which must hold a node not handed out yet.
+    fn node(&mut self, index: u32) -> &'a mut ArenaNode<T> {
+        // the slab outlives 'a and the walk visits every node at most once
+        match unsafe { &mut *self.slots.add(index as usize) } {
+            ArenaSlot::Node(node) => node,
+            ArenaSlot::Free { .. } => unreachable!("linked slot {} is free", index),
+        }
+    }
+}
+
+impl<'a, T> Iterator for ArenaListIteratorMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.size == 0 {
+            return None;
+        }
+        let node = self.node(self.begin);
+        self.begin = node.next;
+        self.size -= 1;
+        Some(&mut node.data)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for ArenaListIteratorMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.size == 0 {
+            return None;
+        }
+        let node = self.node(self.end);
+        self.end = node.previous;
+        self.size -= 1;
+        Some(&mut node.data)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for ArenaListIteratorMut<'a, T> {}
+
+impl<'a, T> FusedIterator for ArenaListIteratorMut<'a, T> {}
+
+/// Iterator moving the items out of an [`ArenaList`].
+pub struct ArenaListIntoIterator<T> {
+    list: ArenaList<T>,
+}
+
+impl<T> Iterator for ArenaListIntoIterator<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.size, Some(self.list.size))
+    }
+}
+
+impl<T> DoubleEndedIterator for ArenaListIntoIterator<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for ArenaListIntoIterator<T> {}
+
+impl<T> FusedIterator for ArenaListIntoIterator<T> {}
+
+impl<T> Default for ArenaList<T> {
+    fn default() -> Self {
+        ArenaList::new()
+    }
+}
+
+impl<T> ArenaList<T> {
+    pub fn new() -> ArenaList<T> {
+        ArenaList::with_capacity(0)
+    }
+
+    /// Creates an empty list with room for at least `capacity` nodes.
+    pub fn with_capacity(capacity: usize) -> ArenaList<T> {
+        let mut list = ArenaList {
+            size: 0,
+            begin: NIL,
+            end: NIL,
+            free: NIL,
+            slots: Array::new(0),
+        };
+        list.grow(capacity);
+        list
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of nodes the slab holds without growing.
+    pub fn capacity(&self) -> usize {
+        self.slots.size()
+    }
+
+    fn check_index(&self, index: usize) -> Result<()> {
+        if index < self.size {
+            Ok(())
+        } else {
+            Err(Error::IndexOutOfBounds {
+                index,
+                len: self.size,
+            })
+        }
+    }
+
+    fn node(&self, index: u32) -> &ArenaNode<T> {
+        match self.slots.get(index as usize) {
+            Ok(ArenaSlot::Node(node)) => node,
+            _ => unreachable!("linked slot {} is free", index),
+        }
+    }
+
+    fn node_mut(&mut self, index: u32) -> &mut ArenaNode<T> {
+        match self.slots.get_mut(index as usize) {
+            Ok(ArenaSlot::Node(node)) => node,
+            _ => unreachable!("linked slot {} is free", index),
+        }
+    }
+
+    /// Slot of the node at list position `index`, walking from the closer
+    /// end.
+    fn get_node(&self, index: usize) -> u32 {
+        if index <= self.size / 2 {
+            (0..index).fold(self.begin, |node, _| self.node(node).next)
+        } else {
+            (index + 1..self.size).fold(self.end, |node, _| self.node(node).previous)
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Result<&T> {
+        self.check_index(index)?;
+        Ok(&self.node(self.get_node(index)).data)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Result<&mut T> {
+        self.check_index(index)?;
+        let node = self.get_node(index);
+        Ok(&mut self.node_mut(node).data)
+    }
+
+    pub fn add(&mut self, item: T) {
+        self.link_before(NIL, item);
+    }
+
+    /// Inserts `item` in front of the first item.
+    pub fn push_front(&mut self, item: T) {
+        self.link_before(self.begin, item);
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.begin == NIL {
+            None
+        } else {
+            Some(self.unlink(self.begin))
+        }
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.end == NIL {
+            None
+        } else {
+            Some(self.unlink(self.end))
+        }
+    }
+
+    pub fn set(&mut self, index: usize, item: T) -> Result<()> {
+        self.check_index(index)?;
+        let node = self.get_node(index);
+        self.node_mut(node).data = item;
+        Ok(())
+    }
+
+    /// Inserts `item` at `index`, shifting later items towards the end.
+    /// Inserting at `size()` appends the item.
+    pub fn insert(&mut self, index: usize, item: T) -> Result<()> {
+        if index > self.size {
+            return Err(Error::IndexOutOfBounds {
+                index,
+                len: self.size,
+            });
+        }
+        let next = if index == self.size {
+            NIL
+        } else {
+            self.get_node(index)
+        };
+        self.link_before(next, item);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) -> Result<T> {
+        self.check_index(index)?;
+        let node = self.get_node(index);
+        Ok(self.unlink(node))
+    }
+
+    /// Removes every item and releases the slab.
+    pub fn clear(&mut self) {
+        self.slots = Array::new(0);
+        self.size = 0;
+        self.begin = NIL;
+        self.end = NIL;
+        self.free = NIL;
+    }
+
+    /// Moves all items of `other` to the end of the list, leaving `other`
+    /// empty. Takes O(m), as the items move into this list's slab.
+    pub fn append(&mut self, other: &mut ArenaList<T>) {
+        self.splice_before(NIL, other);
+    }
+
+    /// Moves all items of `other` into the list, the first one ending up at
+    /// `index`, and leaves `other` empty. Splicing at `size()` appends them.
+    pub fn splice_at(&mut self, index: usize, other: &mut ArenaList<T>) -> Result<()> {
+        if index > self.size {
+            return Err(Error::IndexOutOfBounds {
+                index,
+                len: self.size,
+            });
+        }
+        let next = if index == self.size {
+            NIL
+        } else {
+            self.get_node(index)
+        };
+        self.splice_before(next, other);
+        Ok(())
+    }
+
+    /// Splits the list in two at `index`: returns a list with the items from
+    /// `index` on and keeps the ones before it. Splitting at `size()`
+    /// returns an empty list.
+    pub fn split_off(&mut self, index: usize) -> Result<ArenaList<T>> {
+        if index > self.size {
+            return Err(Error::IndexOutOfBounds {
+                index,
+                len: self.size,
+            });
+        }
+        let mut tail = ArenaList::with_capacity(self.size - index);
+        while self.size > index {
+            tail.push_front(self.pop_back().unwrap());
+        }
+        Ok(tail)
+    }
+
+    /// Reverses the order of the items in place by swapping the links of
+    /// every node.
+    pub fn reverse(&mut self) {
+        let mut node = self.begin;
+        while node != NIL {
+            let item = self.node_mut(node);
+            mem::swap(&mut item.next, &mut item.previous);
+            node = item.previous;
+        }
+        mem::swap(&mut self.begin, &mut self.end);
+    }
+
+    pub fn iter(&self) -> ArenaListIterator<'_, T> {
+        ArenaListIterator {
+            size: self.size,
+            begin: self.begin,
+            end: self.end,
+            list: self,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> ArenaListIteratorMut<'_, T> {
+        ArenaListIteratorMut {
+            size: self.size,
+            begin: self.begin,
+            end: self.end,
+            slots: self.slots.as_mut_ptr(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a cursor pointing at the first item, or at the ghost position
+    /// if the list is empty.
+    pub fn cursor_front(&mut self) -> ArenaListCursorMut<'_, T> {
+        ArenaListCursorMut {
+            index: 0,
+            current: self.begin,
+            list: self,
+        }
+    }
+
+    /// Returns a cursor pointing at the last item, or at the ghost position
+    /// if the list is empty.
+    pub fn cursor_back(&mut self) -> ArenaListCursorMut<'_, T> {
+        ArenaListCursorMut {
+            index: self.size.saturating_sub(1),
+            current: self.end,
+            list: self,
+        }
+    }
+
+    /// Sorts the list with `compare`, keeping equal items in their order.
+    ///
+    /// Runs a bottom-up merge sort over the nodes: they are relinked in
+    /// sorted order without moving any item, in O(n log n). If `compare`
+    /// panics, the list keeps every item in some order.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        merge_sort::sort(self, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less);
+    }
+
+    /// Removes consecutive items for which `same_bucket` returns `true`.
+    ///
+    /// `same_bucket` is given an item and the last item that was kept before
+    /// it; only the first item of each run stays in the list.
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let mut kept = self.begin;
+        if kept == NIL {
+            return;
+        }
+        let mut node = self.node(kept).next;
+        while node != NIL {
+            let next = self.node(node).next;
+            let (current, previous) = self.two_nodes_mut(node, kept);
+            if same_bucket(&mut current.data, &mut previous.data) {
+                drop(self.unlink(node));
+            } else {
+                kept = node;
+            }
+            node = next;
+        }
+    }
+
+    /// The nodes in the different slots `a` and `b`, borrowed at once by
+    /// splitting the slab between them.
+    fn two_nodes_mut(&mut self, a: u32, b: u32) -> (&mut ArenaNode<T>, &mut ArenaNode<T>) {
+        // every slot is initialized, so the slab is a full slice
+        let slots = self.slots.try_as_mut_slice().unwrap();
+        let (a, b) = if a < b {
+            let (low, high) = slots.split_at_mut(b as usize);
+            (&mut low[a as usize], &mut high[0])
+        } else {
+            let (low, high) = slots.split_at_mut(a as usize);
+            (&mut high[0], &mut low[b as usize])
+        };
+        match (a, b) {
+            (ArenaSlot::Node(a), ArenaSlot::Node(b)) => (a, b),
+            _ => unreachable!("linked slot is free"),
+        }
+    }
+
+    /// Grows the slab to hold at least `capacity` slots, at least doubling
+    /// it, and puts the new slots in front of the free list.
+    fn grow(&mut self, capacity: usize) {
+        let old = self.slots.size();
+        if capacity <= old {
+            return;
+        }
+        let new = capacity.max(old * 2).max(INITIAL_CAPACITY);
+        assert!(new < NIL as usize, "ArenaList capacity exceeded");
+        self.slots.resize(new);
+        for index in (old..new).rev() {
+            let _ = self.slots.set(index, ArenaSlot::Free { next: self.free });
+            self.free = index as u32;
+        }
+    }
+
+    /// Takes a free slot for `node`, growing the slab if none is left.
+    fn allocate(&mut self, node: ArenaNode<T>) -> u32 {
+        if self.free == NIL {
+            self.grow(self.size + 1);
+        }
+        let index = self.free;
+        let slot = self.slots.replace(index as usize, ArenaSlot::Node(node));
+        match slot {
+            Ok(Some(ArenaSlot::Free { next })) => self.free = next,
+            _ => unreachable!("free slot {} holds a node", index),
+        }
+        index
+    }
+
+    /// Stores `data` in a new node and links it in front of `next`, or at
+    /// the end of the list if `next` is `NIL`.
+    fn link_before(&mut self, next: u32, data: T) -> u32 {
+        let previous = if next == NIL {
+            self.end
+        } else {
+            self.node(next).previous
+        };
+        let node = self.allocate(ArenaNode {
+            data,
+            next,
+            previous,
+        });
+        if previous == NIL {
+            self.begin = node;
+        } else {
+            self.node_mut(previous).next = node;
+        }
+        if next == NIL {
+            self.end = node;
+        } else {
+            self.node_mut(next).previous = node;
+        }
+        self.size += 1;
+        node
+    }
+
+    /// Unlinks `node` from the list, puts its slot on the free list and
+    /// returns its data.
+    fn unlink(&mut self, node: u32) -> T {
+        let free = ArenaSlot::Free { next: self.free };
+        let removed = match self.slots.replace(node as usize, free) {
+            Ok(Some(ArenaSlot::Node(removed))) => removed,
+            _ => unreachable!("linked slot {} is free", node),
+        };
+        self.free = node;
+        if removed.previous == NIL {
+            self.begin = removed.next;
+        } else {
+            self.node_mut(removed.previous).next = removed.next;
+        }
+        if removed.next == NIL {
+            self.end = removed.previous;
+        } else {
+            self.node_mut(removed.next).previous = removed.previous;
+        }
+        self.size -= 1;
+        removed.data
+    }
+
+    /// Moves the items of `other` into the slab, linked in front of `next`,
+    /// and leaves `other` empty.
+    fn splice_before(&mut self, next: u32, other: &mut ArenaList<T>) {
+        self.grow(self.size + other.size);
+        while let Some(item) = other.pop_front() {
+            self.link_before(next, item);
+        }
+        other.clear();
+    }
+}
+
+impl<T> ArenaList<T>
+where
+    T: PartialEq,
+{
+    pub fn remove_item(&mut self, item: T) -> Result<T> {
+        let mut node = self.begin;
+        while node != NIL {
+            let current = self.node(node);
+            if current.data == item {
+                return Ok(self.unlink(node));
+            }
+            node = current.next;
+        }
+        Err(Error::NotFound)
+    }
+
+    /// Removes consecutive equal items, which on a sorted list removes every
+    /// duplicate.
+    pub fn dedup(&mut self) {
+        self.dedup_by(|a, b| a == b);
+    }
+}
+
+impl<T> ArenaList<T>
+where
+    T: PartialOrd,
+{
+    /// Inserts `item` into the sorted list behind the items less than or
+    /// equal to it, keeping the list sorted.
+    pub fn insert_sorted(&mut self, item: T) {
+        let mut next = self.begin;
+        while next != NIL {
+            let node = self.node(next);
+            if item < node.data {
+                break;
+            }
+            next = node.next;
+        }
+        self.link_before(next, item);
+    }
+
+    /// Merges the sorted list `other` into this sorted list, leaving `other`
+    /// empty. Of equal items, the ones from this list come first.
+    ///
+    /// The items of `other` move into this list's slab, then the nodes are
+    /// merged by relinking, in O(n + m).
+    pub fn merge_sorted(&mut self, other: &mut ArenaList<T>) {
+        let left_len = self.size;
+        self.append(other);
+        merge_sort::merge(self, left_len, &mut |a: &T, b: &T| a < b);
+    }
+
+    /// Checks whether every item is less than or equal to the next one.
+    pub fn is_sorted(&self) -> bool {
+        self.iter().zip(self.iter().skip(1)).all(|(a, b)| a <= b)
+    }
+}
+
+impl<T> ArenaList<T>
+where
+    T: Ord,
+{
+    /// Sorts the list, keeping equal items in their order. See
+    /// [`ArenaList::sort_by`].
+    pub fn sort(&mut self) {
+        self.sort_by(T::cmp);
+    }
+
+    /// Smallest item, the first one if several are equally small.
+    ///
+    /// Not called `min`: lists are [`Ord`] themselves, so `list.min()`
+    /// would find [`Ord::min`] comparing two lists.
+    pub fn min_item(&self) -> Option<&T> {
+        self.iter().min()
+    }
+
+    /// Largest item, the last one if several are equally large.
+    pub fn max_item(&self) -> Option<&T> {
+        self.iter().max()
+    }
+}
+
+impl<T> Chain for ArenaList<T> {
+    type Item = T;
+    type Node = u32;
+
+    const NIL: u32 = NIL;
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn first(&self) -> u32 {
+        self.begin
+    }
+
+    unsafe fn next(&self, node: u32) -> u32 {
+        self.node(node).next
+    }
+
+    unsafe fn set_next(&mut self, node: u32, next: u32) {
+        self.node_mut(node).next = next;
+    }
+
+    unsafe fn item(&self, node: u32) -> &T {
+        &self.node(node).data
+    }
+
+    fn relink(&mut self, begin: u32) {
+        self.begin = begin;
+        let mut previous = NIL;
+        let mut node = begin;
+        while node != NIL {
+            let item = self.node_mut(node);
+            item.previous = previous;
+            previous = node;
+            node = item.next;
+        }
+        self.end = previous;
+    }
+}
+
+impl<T> IntoIterator for ArenaList<T> {
+    type Item = T;
+    type IntoIter = ArenaListIntoIterator<T>;
+
+    fn into_iter(self) -> ArenaListIntoIterator<T> {
+        ArenaListIntoIterator { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArenaList<T> {
+    type Item = &'a T;
+    type IntoIter = ArenaListIterator<'a, T>;
+
+    fn into_iter(self) -> ArenaListIterator<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut ArenaList<T> {
+    type Item = &'a mut T;
+    type IntoIter = ArenaListIteratorMut<'a, T>;
+
+    fn into_iter(self) -> ArenaListIteratorMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+/// Cursor over an [`ArenaList`] that can edit the list around its position.
+///
+/// Besides pointing at an item, the cursor can rest on a "ghost" position
+/// between the last and the first item. Moving past either end of the list
+/// lands on the ghost, and moving once more wraps around to the other end.
+/// Every operation takes O(1), except for the splits, which move the split
+/// off items into a new slab.
+pub struct ArenaListCursorMut<'a, T> {
+    index: usize,
+    current: u32,
+    list: &'a mut ArenaList<T>,
+}
+
+impl<'a, T> ArenaListCursorMut<'a, T> {
+    /// Index of the current item, or `None` on the ghost position.
+    pub fn index(&self) -> Option<usize> {
+        if self.current == NIL {
+            None
+        } else {
+            Some(self.index)
+        }
+    }
+
+    pub fn move_next(&mut self) {
+        if self.current == NIL {
+            self.current = self.list.begin;
+            self.index = 0;
+        } else {
+            self.current = self.list.node(self.current).next;
+            self.index += 1;
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        if self.current == NIL {
+            self.current = self.list.end;
+            self.index = self.list.size.saturating_sub(1);
+        } else {
+            self.current = self.list.node(self.current).previous;
+            self.index = self.index.checked_sub(1).unwrap_or(self.list.size);
+        }
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.data(self.current)
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = if self.current == NIL {
+            self.list.begin
+        } else {
+            self.list.node(self.current).next
+        };
+        self.data(next)
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let previous = if self.current == NIL {
+            self.list.end
+        } else {
+            self.list.node(self.current).previous
+        };
+        self.data(previous)
+    }
+
+    /// Inserts `item` before the current item, or at the end of the list when
+    /// on the ghost position.
+    pub fn insert_before(&mut self, item: T) {
+        self.list.link_before(self.current, item);
+        self.index += 1;
+    }
+
+    /// Inserts `item` after the current item, or at the front of the list
+    /// when on the ghost position.
+    pub fn insert_after(&mut self, item: T) {
+        let next = if self.current == NIL {
+            self.list.begin
+        } else {
+            self.list.node(self.current).next
+        };
+        self.list.link_before(next, item);
+        if self.current == NIL {
+            self.index += 1;
+        }
+    }
+
+    /// Removes the current item and moves the cursor to the next one.
+    /// Does nothing on the ghost position.
+    pub fn remove_current(&mut self) -> Option<T> {
+        if self.current == NIL {
+            return None;
+        }
+        let node = self.current;
+        self.current = self.list.node(node).next;
+        Some(self.list.unlink(node))
+    }
+
+    /// Splits off every item after the current one into a new list.
+    /// On the ghost position the whole list is split off.
+    pub fn split_after(&mut self) -> ArenaList<T> {
+        let index = match self.index() {
+            None => {
+                self.index = 0;
+                0
+            }
+            Some(index) => index + 1,
+        };
+        self.list.split_off(index).unwrap()
+    }
+
+    /// Splits off every item before the current one into a new list.
+    /// On the ghost position the whole list is split off.
+    pub fn split_before(&mut self) -> ArenaList<T> {
+        let size = self.index().unwrap_or(self.list.size);
+        let mut head = ArenaList::with_capacity(size);
+        for _ in 0..size {
+            head.add(self.list.pop_front().unwrap());
+        }
+        self.index = 0;
+        head
+    }
+
+    fn data(&mut self, node: u32) -> Option<&mut T> {
+        if node == NIL {
+            None
+        } else {
+            Some(&mut self.list.node_mut(node).data)
+        }
+    }
+}
+
+/// Clones store the items in list order in a compact slab.
+impl<T> Clone for ArenaList<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        let mut list = ArenaList::with_capacity(self.size);
+        list.extend(self.iter().cloned());
+        list
+    }
+}
+
+impl<T> fmt::Debug for ArenaList<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> PartialEq for ArenaList<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &ArenaList<T>) -> bool {
+        self.size == other.size && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> Eq for ArenaList<T> where T: Eq {}
+
+impl<T> PartialOrd for ArenaList<T>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &ArenaList<T>) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T> Ord for ArenaList<T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &ArenaList<T>) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T> Hash for ArenaList<T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        self.iter().for_each(|item| item.hash(state));
+    }
+}
+
+impl<T> FromIterator<T> for ArenaList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = ArenaList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for ArenaList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.add(item);
+        }
+    }
+}
+
+impl<'a, T> Extend<&'a T> for ArenaList<T>
+where
+    T: Copy + 'a,
+{
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied())
+    }
+}
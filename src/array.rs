@@ -1,176 +1,814 @@
+use crate::array_list::ArrayList;
+use crate::error::{Error, Result};
 use std::alloc::{self, Layout};
+use std::borrow::{Borrow, BorrowMut};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::ops::{
+    Bound, Deref, DerefMut, Index, IndexMut, Range, RangeBounds, RangeFrom, RangeFull,
+    RangeInclusive, RangeTo, RangeToInclusive,
+};
 use std::ptr;
 use std::ptr::NonNull;
+use std::slice;
 
-pub fn array_index_out_of_bounds(index: usize, length: usize) {
-    println!(
-        "Array index out of bounds! Index {}, array length {}",
-        index, length
-    );
+fn allocate<U>(length: usize, zeroed: bool) -> NonNull<U> {
+    let layout = Layout::array::<U>(length).unwrap();
+    if layout.size() == 0 {
+        return NonNull::dangling();
+    }
+    let data = unsafe {
+        if zeroed {
+            alloc::alloc_zeroed(layout)
+        } else {
+            alloc::alloc(layout)
+        }
+    };
+    match NonNull::new(data as *mut U) {
+        Some(p) => p,
+        None => alloc::handle_alloc_error(layout),
+    }
+}
+
+fn deallocate<U>(data: NonNull<U>, length: usize) {
+    let layout = Layout::array::<U>(length).unwrap();
+    if layout.size() != 0 {
+        unsafe {
+            alloc::dealloc(data.as_ptr() as *mut u8, layout);
+        }
+    }
 }
 
+fn reallocate<U>(data: NonNull<U>, old_length: usize, new_length: usize) -> NonNull<U> {
+    let old_layout = Layout::array::<U>(old_length).unwrap();
+    let new_layout = Layout::array::<U>(new_length).unwrap();
+    if old_layout.size() == 0 {
+        return allocate(new_length, false);
+    }
+    if new_layout.size() == 0 {
+        deallocate(data, old_length);
+        return NonNull::dangling();
+    }
+    let result = unsafe { alloc::realloc(data.as_ptr() as *mut u8, old_layout, new_layout.size()) };
+    match NonNull::new(result as *mut U) {
+        Some(p) => p,
+        None => alloc::handle_alloc_error(new_layout),
+    }
+}
+
+/// Heap-allocated array whose slots may be uninitialized.
+///
+/// Every slot keeps track of whether it holds a value, so the array can store
+/// any `T` and drops exactly the live elements when it goes away.
+///
+/// `array[i]` and range indexing such as `array[2..5]` only need the indexed
+/// slots to be filled. Once every slot holds a value, the array can also be
+/// used as a slice: it dereferences to `[T]`. Viewing an array with empty
+/// slots as a slice panics, see [`Array::try_as_slice`].
 pub struct Array<T> {
     size: usize,
-    data: ptr::NonNull<T>,
+    initialized_count: usize,
+    data: NonNull<MaybeUninit<T>>,
+    initialized: NonNull<bool>,
+    _marker: PhantomData<T>,
+}
+
+/// Iterator over references to the initialized slots of an [`Array`].
+pub struct ArrayIterator<'a, T> {
+    start: usize,
+    end: usize,
+    array: &'a Array<T>,
+}
+
+impl<'a, T> Iterator for ArrayIterator<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        while self.start != self.end {
+            let index = self.start;
+            self.start += 1;
+            if let Ok(item) = self.array.get(index) {
+                return Some(item);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.start))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for ArrayIterator<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        while self.start != self.end {
+            self.end -= 1;
+            if let Ok(item) = self.array.get(self.end) {
+                return Some(item);
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over mutable references to the initialized slots of an [`Array`].
+pub struct ArrayIteratorMut<'a, T> {
+    start: usize,
+    end: usize,
+    data: *mut MaybeUninit<T>,
+    initialized: *const bool,
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> Iterator for ArrayIteratorMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<&'a mut T> {
+        while self.start != self.end {
+            let index = self.start;
+            self.start += 1;
+            unsafe {
+                if *self.initialized.add(index) {
+                    return Some((*self.data.add(index)).assume_init_mut());
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.start))
+    }
 }
 
-pub struct ArrayIterator<T> {
-    start: *const T,
-    end: *const T,
+impl<'a, T> DoubleEndedIterator for ArrayIteratorMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        while self.start != self.end {
+            self.end -= 1;
+            unsafe {
+                if *self.initialized.add(self.end) {
+                    return Some((*self.data.add(self.end)).assume_init_mut());
+                }
+            }
+        }
+        None
+    }
 }
 
-impl<T> Iterator for ArrayIterator<T> {
+/// Iterator moving the items out of an [`Array`]. Items that are not
+/// yielded are dropped together with the array.
+pub struct ArrayIntoIterator<T> {
+    start: usize,
+    end: usize,
+    array: Array<T>,
+}
+
+impl<T> Iterator for ArrayIntoIterator<T> {
     type Item = T;
     fn next(&mut self) -> Option<T> {
-        if self.start == self.end {
-            None
-        } else {
-            unsafe {
-                let result = ptr::read(self.start);
-                self.start = self.start.offset(1);
-                Some(result)
+        while self.start != self.end {
+            let index = self.start;
+            self.start += 1;
+            if let Ok(item) = self.array.take(index) {
+                return Some(item);
             }
         }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.start))
     }
 }
 
-impl<T> DoubleEndedIterator for ArrayIterator<T> {
+impl<T> DoubleEndedIterator for ArrayIntoIterator<T> {
     fn next_back(&mut self) -> Option<T> {
-        if self.start == self.end {
-            None
-        } else {
-            unsafe {
-                self.end = self.end.offset(-1);
-                Some(ptr::read(self.end))
+        while self.start != self.end {
+            self.end -= 1;
+            if let Ok(item) = self.array.take(self.end) {
+                return Some(item);
             }
         }
+        None
     }
 }
 
-impl<T> Array<T>
-where
-    T: Copy,
-{
+impl<T> Array<T> {
     pub fn new(length: usize) -> Self {
-        let layout = Layout::array::<T>(length).unwrap();
-        let data = unsafe { alloc::alloc_zeroed(layout) };
-        let result_data = match NonNull::new(data as *mut T) {
-            Some(p) => p,
-            None => alloc::handle_alloc_error(layout),
-        };
         Array {
             size: length,
-            data: result_data,
+            initialized_count: 0,
+            data: allocate(length, false),
+            initialized: allocate(length, true),
+            _marker: PhantomData,
         }
     }
 
-    pub fn copy_range(&mut self, from: usize, to: usize) -> Array<T> {
-        self.copy(from, to, to - from)
+    pub fn size(&self) -> usize {
+        self.size
     }
 
-    pub fn copy_with_size(&mut self, size: usize) -> Array<T> {
-        self.copy(0, self.size, size)
+    pub fn is_initialized(&self, index: usize) -> bool {
+        index < self.size && unsafe { *self.initialized.as_ptr().add(index) }
     }
 
-    pub fn clone(&mut self) -> Array<T> {
-        self.copy(0, self.size, self.size)
+    /// Whether every slot holds a value.
+    pub fn is_full(&self) -> bool {
+        self.initialized_count == self.size
     }
 
-    pub fn copy(&mut self, from: usize, to: usize, size: usize) -> Array<T> {
-        if to < from {
-            panic!("Index 'to' must be less than index 'from'");
+    pub fn as_ptr(&self) -> *const T {
+        self.data.as_ptr() as *const T
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.data.as_ptr() as *mut T
+    }
+
+    /// Views the array as a slice, or reports the first empty slot.
+    pub fn try_as_slice(&self) -> Result<&[T]> {
+        self.check_full()?;
+        unsafe { Ok(slice::from_raw_parts(self.as_ptr(), self.size)) }
+    }
+
+    pub fn try_as_mut_slice(&mut self) -> Result<&mut [T]> {
+        self.check_full()?;
+        unsafe { Ok(slice::from_raw_parts_mut(self.as_mut_ptr(), self.size)) }
+    }
+
+    fn check_full(&self) -> Result<()> {
+        if self.is_full() {
+            Ok(())
+        } else {
+            let index = (0..self.size)
+                .find(|&index| !self.is_initialized(index))
+                .unwrap();
+            Err(Error::Uninitialized { index })
         }
-        if from > self.size {
-            panic!("Index 'from' must be less than array size");
+    }
+
+    fn check_index(&self, index: usize) -> Result<()> {
+        if index < self.size {
+            Ok(())
+        } else {
+            Err(Error::IndexOutOfBounds {
+                index,
+                len: self.size,
+            })
         }
-        if to > self.size {
-            panic!("Index 'to' must be less than array size");
+    }
+
+    fn check_initialized(&self, index: usize) -> Result<()> {
+        self.check_index(index)?;
+        if self.is_initialized(index) {
+            Ok(())
+        } else {
+            Err(Error::Uninitialized { index })
         }
-        let layout = Layout::array::<T>(size).unwrap();
-        let data = unsafe { alloc::alloc(layout) };
-        let mut result_data = match NonNull::new(data as *mut T) {
-            Some(p) => p,
-            None => alloc::handle_alloc_error(layout),
+    }
+
+    /// Checks that `range` lies within the array and all its slots are
+    /// filled, and returns its start and end.
+    fn check_range<R>(&self, range: R) -> Result<(usize, usize)>
+    where
+        R: RangeBounds<usize>,
+    {
+        let from = match range.start_bound() {
+            Bound::Included(&from) => from,
+            Bound::Excluded(&from) => from + 1,
+            Bound::Unbounded => 0,
         };
+        let to = match range.end_bound() {
+            Bound::Included(&to) => to + 1,
+            Bound::Excluded(&to) => to,
+            Bound::Unbounded => self.size,
+        };
+        if from > to {
+            return Err(Error::InvalidRange { from, to });
+        }
+        if to > self.size {
+            return Err(Error::IndexOutOfBounds {
+                index: to,
+                len: self.size,
+            });
+        }
+        if !self.is_full() {
+            if let Some(index) = (from..to).find(|&index| !self.is_initialized(index)) {
+                return Err(Error::Uninitialized { index });
+            }
+        }
+        Ok((from, to))
+    }
+
+    /// Returns a reference to the item at `index`.
+    pub fn get(&self, index: usize) -> Result<&T> {
+        self.check_initialized(index)?;
+        unsafe { Ok((*self.data.as_ptr().add(index)).assume_init_ref()) }
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Result<&mut T> {
+        self.check_initialized(index)?;
+        unsafe { Ok((*self.data.as_ptr().add(index)).assume_init_mut()) }
+    }
+
+    /// Returns the items in `range` as a slice, or reports the first empty
+    /// slot in it.
+    pub fn get_range<R>(&self, range: R) -> Result<&[T]>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (from, to) = self.check_range(range)?;
+        unsafe { Ok(slice::from_raw_parts(self.as_ptr().add(from), to - from)) }
+    }
+
+    pub fn get_range_mut<R>(&mut self, range: R) -> Result<&mut [T]>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (from, to) = self.check_range(range)?;
         unsafe {
-            ptr::copy_nonoverlapping(
-                self.data.as_ptr().add(from),
-                result_data.as_mut(),
+            Ok(slice::from_raw_parts_mut(
+                self.as_mut_ptr().add(from),
                 to - from,
-            );
-        }
-        Array {
-            size: size,
-            data: result_data,
+            ))
         }
     }
 
-    pub fn merge(&mut self, other: &Array<T>, size: usize) -> Array<T> {
-        let layout = Layout::array::<T>(size).unwrap();
-        let data = unsafe { alloc::alloc(layout) };
-        let mut result_data = match NonNull::new(data as *mut T) {
-            Some(p) => p,
-            None => alloc::handle_alloc_error(layout),
-        };
+    /// Stores `item` at `index`, dropping the previous item if there was one.
+    pub fn set(&mut self, index: usize, item: T) -> Result<()> {
+        self.replace(index, item).map(drop)
+    }
+
+    /// Moves the item out of `index`, leaving the slot empty.
+    pub fn take(&mut self, index: usize) -> Result<T> {
+        self.check_initialized(index)?;
+        self.initialized_count -= 1;
         unsafe {
-            ptr::copy_nonoverlapping(self.data.as_ptr(), result_data.as_mut(), self.size);
-            ptr::copy_nonoverlapping(
-                other.data.as_ptr(),
-                result_data.as_ptr().add(self.size),
-                other.size,
-            )
+            *self.initialized.as_ptr().add(index) = false;
+            Ok(ptr::read(self.data.as_ptr().add(index)).assume_init())
         }
-        Array {
-            size: size,
-            data: result_data,
+    }
+
+    /// Iterates over the items of the initialized slots, skipping empty ones.
+    pub fn iter(&self) -> ArrayIterator<'_, T> {
+        self.iter_range(0, self.size)
+    }
+
+    pub fn iter_mut(&mut self) -> ArrayIteratorMut<'_, T> {
+        self.iter_range_mut(0, self.size)
+    }
+
+    /// Every slot in order, `None` for the empty ones.
+    fn slots(&self) -> impl Iterator<Item = Option<&T>> {
+        (0..self.size).map(move |index| self.get(index).ok())
+    }
+
+    pub(crate) fn iter_range(&self, from: usize, to: usize) -> ArrayIterator<'_, T> {
+        ArrayIterator {
+            start: from,
+            end: to.min(self.size),
+            array: self,
         }
     }
 
-    pub fn size(&self) -> usize {
-        self.size
+    pub(crate) fn iter_range_mut(&mut self, from: usize, to: usize) -> ArrayIteratorMut<'_, T> {
+        ArrayIteratorMut {
+            start: from,
+            end: to.min(self.size),
+            data: self.data.as_ptr(),
+            initialized: self.initialized.as_ptr(),
+            _marker: PhantomData,
+        }
     }
 
-    pub fn get(&mut self, index: usize) -> Option<T> {
-        if index < self.size {
-            unsafe { Some(ptr::read(self.data.as_ptr().add(index))) }
-        } else {
-            array_index_out_of_bounds(index, self.size());
-            None
+    /// Pointer to the item in the initialized slot `index`. It points into
+    /// the buffer rather than through `self`, so writing through it is fine
+    /// for callers that hold the array exclusively some other way.
+    pub(crate) fn slot_ptr(&self, index: usize) -> *mut T {
+        debug_assert!(self.is_initialized(index));
+        unsafe { self.data.as_ptr().add(index) as *mut T }
+    }
+
+    /// Swaps the slots at `index0` and `index1`, empty or not.
+    pub fn swap(&mut self, index0: usize, index1: usize) -> Result<()> {
+        self.check_index(index0)?;
+        self.check_index(index1)?;
+        unsafe {
+            ptr::swap(
+                self.data.as_ptr().add(index0),
+                self.data.as_ptr().add(index1),
+            );
+            ptr::swap(
+                self.initialized.as_ptr().add(index0),
+                self.initialized.as_ptr().add(index1),
+            );
         }
+        Ok(())
     }
 
-    pub fn set(&mut self, index: usize, item: T) {
-        if index < self.size {
-            unsafe {
-                ptr::write(self.data.as_ptr().add(index), item);
+    /// Moves the `count` slots starting at `from` so they start at `to`.
+    ///
+    /// Items in destination slots that are not part of the moved range are
+    /// dropped, and source slots that are not overwritten are left empty.
+    /// The items are moved in place, without allocating.
+    pub fn shift(&mut self, from: usize, to: usize, count: usize) -> Result<()> {
+        if from + count > self.size || to + count > self.size {
+            return Err(Error::IndexOutOfBounds {
+                index: from.max(to) + count,
+                len: self.size,
+            });
+        }
+        let source = from..from + count;
+        let target = to..to + count;
+        for index in target.clone() {
+            if !source.contains(&index) && self.is_initialized(index) {
+                drop(self.take(index));
+            }
+        }
+        unsafe {
+            ptr::copy(
+                self.data.as_ptr().add(from),
+                self.data.as_ptr().add(to),
+                count,
+            );
+            ptr::copy(
+                self.initialized.as_ptr().add(from),
+                self.initialized.as_ptr().add(to),
+                count,
+            );
+            for index in source {
+                if !target.contains(&index) {
+                    *self.initialized.as_ptr().add(index) = false;
+                }
             }
-        } else {
-            array_index_out_of_bounds(index, self.size());
         }
+        Ok(())
     }
 
-    pub fn into_iter(&mut self) -> ArrayIterator<T> {
-        let data = self.data;
-        let size = self.size;
+    /// Reallocates the array to hold `size` slots.
+    ///
+    /// Items past the new size are dropped and new slots start out empty.
+    pub fn resize(&mut self, size: usize) {
+        for index in size..self.size {
+            if self.is_initialized(index) {
+                drop(self.take(index));
+            }
+        }
+        unsafe {
+            self.data = reallocate(self.data, self.size, size);
+            self.initialized = reallocate(self.initialized, self.size, size);
+            if size > self.size {
+                ptr::write_bytes(
+                    self.initialized.as_ptr().add(self.size),
+                    0,
+                    size - self.size,
+                );
+            }
+        }
+        self.size = size;
+    }
 
+    /// Stores `item` at `index` and returns the item it replaced, if any.
+    pub fn replace(&mut self, index: usize, item: T) -> Result<Option<T>> {
+        self.check_index(index)?;
+        let old = self.take(index).ok();
         unsafe {
-            ArrayIterator {
-                start: data.as_ptr(),
-                end: if size == 0 {
-                    data.as_ptr()
-                } else {
-                    data.as_ptr().add(size)
-                },
+            ptr::write(self.data.as_ptr().add(index), MaybeUninit::new(item));
+            *self.initialized.as_ptr().add(index) = true;
+        }
+        self.initialized_count += 1;
+        Ok(old)
+    }
+}
+
+impl<T> Array<T>
+where
+    T: Clone,
+{
+    pub fn copy_range(&self, from: usize, to: usize) -> Result<Array<T>> {
+        if to < from {
+            return Err(Error::InvalidRange { from, to });
+        }
+        self.copy(from, to, to - from)
+    }
+
+    pub fn copy_with_size(&self, size: usize) -> Array<T> {
+        self.clone_slots(0, self.size.min(size), size)
+    }
+
+    pub fn copy(&self, from: usize, to: usize, size: usize) -> Result<Array<T>> {
+        if to < from {
+            return Err(Error::InvalidRange { from, to });
+        }
+        if to > self.size {
+            return Err(Error::IndexOutOfBounds {
+                index: to,
+                len: self.size,
+            });
+        }
+        if size < to - from {
+            return Err(Error::CapacityExceeded {
+                capacity: size,
+                required: to - from,
+            });
+        }
+        Ok(self.clone_slots(from, to, size))
+    }
+
+    pub fn merge(&self, other: &Array<T>, size: usize) -> Result<Array<T>> {
+        if size < self.size + other.size {
+            return Err(Error::CapacityExceeded {
+                capacity: size,
+                required: self.size + other.size,
+            });
+        }
+        let mut result = self.clone_slots(0, self.size, size);
+        for index in 0..other.size {
+            if let Ok(item) = other.get(index) {
+                result.set(self.size + index, item.clone())?;
+            }
+        }
+        Ok(result)
+    }
+
+    fn clone_slots(&self, from: usize, to: usize, size: usize) -> Array<T> {
+        let mut result = Array::new(size);
+        for index in from..to {
+            if let Ok(item) = self.get(index) {
+                let _ = result.set(index - from, item.clone());
             }
         }
+        result
+    }
+}
+
+impl<T> Clone for Array<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Array<T> {
+        self.clone_slots(0, self.size, self.size)
     }
 }
 
+// the array owns its items like a `Box<[T]>` does
+unsafe impl<T> Send for Array<T> where T: Send {}
+
+unsafe impl<T> Sync for Array<T> where T: Sync {}
+
 impl<T> Drop for Array<T> {
     fn drop(&mut self) {
-        let layout = Layout::array::<T>(self.size).unwrap();
-        unsafe {
-            alloc::dealloc(self.data.as_ptr() as *mut u8, layout);
+        for index in 0..self.size {
+            if self.is_initialized(index) {
+                unsafe {
+                    ptr::drop_in_place((*self.data.as_ptr().add(index)).as_mut_ptr());
+                }
+            }
+        }
+        deallocate(self.data, self.size);
+        deallocate(self.initialized, self.size);
+    }
+}
+
+impl<T> IntoIterator for Array<T> {
+    type Item = T;
+    type IntoIter = ArrayIntoIterator<T>;
+
+    fn into_iter(self) -> ArrayIntoIterator<T> {
+        ArrayIntoIterator {
+            start: 0,
+            end: self.size,
+            array: self,
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Array<T> {
+    type Item = &'a T;
+    type IntoIter = ArrayIterator<'a, T>;
+
+    fn into_iter(self) -> ArrayIterator<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Array<T> {
+    type Item = &'a mut T;
+    type IntoIter = ArrayIteratorMut<'a, T>;
+
+    fn into_iter(self) -> ArrayIteratorMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> Deref for Array<T> {
+    type Target = [T];
+
+    /// # Panics
+    ///
+    /// Panics if any slot is empty.
+    fn deref(&self) -> &[T] {
+        match self.try_as_slice() {
+            Ok(items) => items,
+            Err(error) => panic!("{}", error),
+        }
+    }
+}
+
+impl<T> DerefMut for Array<T> {
+    /// # Panics
+    ///
+    /// Panics if any slot is empty.
+    fn deref_mut(&mut self) -> &mut [T] {
+        match self.try_as_mut_slice() {
+            Ok(items) => items,
+            Err(error) => panic!("{}", error),
+        }
+    }
+}
+
+impl<T> AsRef<[T]> for Array<T> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> AsMut<[T]> for Array<T> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T> Borrow<[T]> for Array<T> {
+    fn borrow(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> BorrowMut<[T]> for Array<T> {
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+/// Indexes a single slot, which must be filled.
+///
+/// # Panics
+///
+/// Panics if `index` is out of bounds or the slot is empty. Other slots may
+/// be empty.
+impl<T> Index<usize> for Array<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        match self.get(index) {
+            Ok(item) => item,
+            Err(error) => panic!("{}", error),
+        }
+    }
+}
+
+impl<T> IndexMut<usize> for Array<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        match self.get_mut(index) {
+            Ok(item) => item,
+            Err(error) => panic!("{}", error),
+        }
+    }
+}
+
+// indexing a range only needs the slots in the range to be filled, see
+// `Array::get_range`; it panics like `array[index]` does
+macro_rules! impl_range_index {
+    ($($range:ty),*) => {
+        $(
+            impl<T> Index<$range> for Array<T> {
+                type Output = [T];
+
+                fn index(&self, range: $range) -> &[T] {
+                    match self.get_range(range) {
+                        Ok(items) => items,
+                        Err(error) => panic!("{}", error),
+                    }
+                }
+            }
+
+            impl<T> IndexMut<$range> for Array<T> {
+                fn index_mut(&mut self, range: $range) -> &mut [T] {
+                    match self.get_range_mut(range) {
+                        Ok(items) => items,
+                        Err(error) => panic!("{}", error),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_range_index!(
+    Range<usize>,
+    RangeFrom<usize>,
+    RangeTo<usize>,
+    RangeFull,
+    RangeInclusive<usize>,
+    RangeToInclusive<usize>,
+    (Bound<usize>, Bound<usize>)
+);
+
+impl<T> Default for Array<T> {
+    fn default() -> Self {
+        Array::new(0)
+    }
+}
+
+/// Stands in for an empty slot when formatting an [`Array`].
+struct EmptySlot;
+
+impl fmt::Debug for EmptySlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<empty>")
+    }
+}
+
+impl<T> fmt::Debug for Array<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.slots().map(|slot| match slot {
+                Some(item) => item as &dyn fmt::Debug,
+                None => &EmptySlot as &dyn fmt::Debug,
+            }))
+            .finish()
+    }
+}
+
+/// Arrays are equal if they have the same size and the same slots are
+/// filled with equal items.
+impl<T> PartialEq for Array<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Array<T>) -> bool {
+        self.size == other.size && self.slots().eq(other.slots())
+    }
+}
+
+impl<T> Eq for Array<T> where T: Eq {}
+
+/// Compares slot by slot, an empty slot being less than any item.
+impl<T> PartialOrd for Array<T>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Array<T>) -> Option<Ordering> {
+        self.slots().partial_cmp(other.slots())
+    }
+}
+
+impl<T> Ord for Array<T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &Array<T>) -> Ordering {
+        self.slots().cmp(other.slots())
+    }
+}
+
+impl<T> Hash for Array<T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // full arrays hash like their slice, as `Borrow<[T]>` requires
+        match self.try_as_slice() {
+            Ok(items) => items.hash(state),
+            Err(_) => {
+                self.size.hash(state);
+                self.slots().for_each(|slot| slot.hash(state));
+            }
+        }
+    }
+}
+
+/// Collects the items into a full array.
+impl<T> FromIterator<T> for Array<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        iter.into_iter().collect::<ArrayList<T>>().into()
+    }
+}
+
+/// Grows the array by one filled slot per item.
+impl<T> Extend<T> for Array<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let items: ArrayList<T> = iter.into_iter().collect();
+        let start = self.size;
+        self.resize(start + items.size());
+        for (index, item) in items.into_iter().enumerate() {
+            let _ = self.set(start + index, item);
         }
     }
 }
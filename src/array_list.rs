@@ -1,190 +1,732 @@
-use crate::array::Array;
+use crate::array::{Array, ArrayIteratorMut};
+use crate::error::{Error, Result};
+use crate::growth_policy::GrowthPolicy;
+use std::borrow::{Borrow, BorrowMut};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::mem;
+use std::ops::{Bound, Deref, DerefMut, Index, IndexMut, RangeBounds};
+use std::slice::{self, SliceIndex};
 
+/// Growable list stored in an [`Array`].
+///
+/// Dereferences to a slice of its items, so slice methods such as `sort`,
+/// `binary_search` or `windows` and range indexing work on the list.
+///
+/// Besides its own methods the list offers the API of `Vec`, so code can
+/// switch between the two. Unlike `Vec`, the list gives memory back when
+/// items are removed; how it grows and shrinks is set by its
+/// [`GrowthPolicy`].
 pub struct ArrayList<T> {
     size: usize,
     array: Array<T>,
+    policy: GrowthPolicy,
 }
 
-pub struct ArrayListIterator<T> {
+/// Iterator over references to the items of an [`ArrayList`].
+pub struct ArrayListIterator<'a, T> {
+    start: usize,
+    end: usize,
+    array: &'a Array<T>,
+}
+
+impl<'a, T> Iterator for ArrayListIterator<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        if self.start == self.end {
+            None
+        } else {
+            let result = self.array.get(self.start).ok();
+            self.start += 1;
+            result
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.start;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for ArrayListIterator<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.start == self.end {
+            None
+        } else {
+            self.end -= 1;
+            self.array.get(self.end).ok()
+        }
+    }
+}
+
+/// Iterator over mutable references to the items of an [`ArrayList`].
+pub struct ArrayListIteratorMut<'a, T> {
+    remaining: usize,
+    items: ArrayIteratorMut<'a, T>,
+}
+
+impl<'a, T> Iterator for ArrayListIteratorMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<&'a mut T> {
+        let result = self.items.next();
+        if result.is_some() {
+            self.remaining -= 1;
+        }
+        result
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for ArrayListIteratorMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        let result = self.items.next_back();
+        if result.is_some() {
+            self.remaining -= 1;
+        }
+        result
+    }
+}
+
+/// Iterator moving the items out of an [`ArrayList`].
+pub struct ArrayListIntoIterator<T> {
     start: usize,
     end: usize,
     array: Array<T>,
 }
 
-impl<T> Iterator for ArrayListIterator<T>
-where
-    T: Copy,
-{
+impl<T> Iterator for ArrayListIntoIterator<T> {
     type Item = T;
     fn next(&mut self) -> Option<T> {
         if self.start == self.end {
             None
         } else {
-            let result = self.array.get(self.start);
+            let result = self.array.take(self.start).ok();
             self.start += 1;
             result
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.start;
+        (remaining, Some(remaining))
+    }
 }
 
-impl<T> DoubleEndedIterator for ArrayListIterator<T>
-where
-    T: Copy,
-{
+impl<T> DoubleEndedIterator for ArrayListIntoIterator<T> {
     fn next_back(&mut self) -> Option<T> {
         if self.start == self.end {
             None
         } else {
             self.end -= 1;
-            self.array.get(self.end)
+            self.array.take(self.end).ok()
         }
     }
 }
 
-impl<T> ArrayList<T>
-where
-    T: Copy + PartialEq,
-{
+impl<T> Default for ArrayList<T> {
+    fn default() -> Self {
+        ArrayList::new()
+    }
+}
+
+impl<T> ArrayList<T> {
     pub fn new() -> ArrayList<T> {
+        ArrayList::with_policy(GrowthPolicy::default())
+    }
+
+    /// Creates an empty list with room for `capacity` items.
+    pub fn with_capacity(capacity: usize) -> ArrayList<T> {
+        ArrayList::with_capacity_and_policy(capacity, GrowthPolicy::default())
+    }
+
+    /// Creates an empty list that grows and shrinks according to `policy`.
+    pub fn with_policy(policy: GrowthPolicy) -> ArrayList<T> {
+        ArrayList::with_capacity_and_policy(policy.initial_capacity(), policy)
+    }
+
+    /// Creates an empty list with room for `capacity` items that grows and
+    /// shrinks according to `policy`.
+    pub fn with_capacity_and_policy(capacity: usize, policy: GrowthPolicy) -> ArrayList<T> {
         ArrayList {
             size: 0,
-            array: Array::new(4),
+            array: Array::new(capacity),
+            policy,
         }
     }
 
+    pub fn policy(&self) -> &GrowthPolicy {
+        &self.policy
+    }
+
+    /// Replaces the growth policy. The capacity changes the next time the
+    /// list grows or shrinks.
+    pub fn set_policy(&mut self, policy: GrowthPolicy) {
+        self.policy = policy;
+    }
+
+    /// Number of items the list can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.array.size()
+    }
+
     pub fn size(&self) -> usize {
         self.size
     }
 
-    pub fn get(&mut self, index: usize) -> Option<T> {
+    fn check_index(&self, index: usize) -> Result<()> {
+        if index < self.size {
+            Ok(())
+        } else {
+            Err(Error::IndexOutOfBounds {
+                index,
+                len: self.size,
+            })
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Result<&T> {
+        self.check_index(index)?;
         self.array.get(index)
     }
 
-    fn expand(&mut self) {
-        let new_array = self.array.copy_with_size(self.size * 2);
-        self.array = new_array;
+    pub fn get_mut(&mut self, index: usize) -> Result<&mut T> {
+        self.check_index(index)?;
+        self.array.get_mut(index)
+    }
+
+    /// Grows the capacity as the policy says until `required` items fit.
+    fn expand(&mut self, required: usize) {
+        if required <= self.array.size() {
+            return;
+        }
+        let capacity = self.policy.grown(self.array.size(), required);
+        self.array.resize(capacity);
     }
 
+    /// Gives memory back as the policy says after items were removed.
     fn shrink(&mut self) {
-        let new_array = self.array.copy_with_size(self.size / 2);
-        self.array = new_array;
+        let capacity = self.policy.shrunk(self.array.size(), self.size);
+        if capacity != self.array.size() {
+            self.array.resize(capacity);
+        }
+    }
+
+    /// Checks that `range` lies within the list and returns its bounds.
+    fn check_range<R>(&self, range: R) -> Result<(usize, usize)>
+    where
+        R: RangeBounds<usize>,
+    {
+        let from = match range.start_bound() {
+            Bound::Included(&from) => from,
+            Bound::Excluded(&from) => from + 1,
+            Bound::Unbounded => 0,
+        };
+        let to = match range.end_bound() {
+            Bound::Included(&to) => to + 1,
+            Bound::Excluded(&to) => to,
+            Bound::Unbounded => self.size,
+        };
+        if from > to {
+            Err(Error::InvalidRange { from, to })
+        } else if to > self.size {
+            Err(Error::IndexOutOfBounds {
+                index: to,
+                len: self.size,
+            })
+        } else {
+            Ok((from, to))
+        }
     }
 
     pub fn add(&mut self, item: T) {
-        if self.array.size() == self.size {
-            self.expand();
+        self.expand(self.size + 1);
+        // the slot right after the last item always exists after expanding
+        let _ = self.array.set(self.size, item);
+        self.size += 1;
+    }
+
+    pub fn set(&mut self, index: usize, item: T) -> Result<()> {
+        self.check_index(index)?;
+        self.array.set(index, item)
+    }
+
+    /// Inserts `item` at `index`, shifting later items to the right.
+    /// Inserting at `size()` appends the item.
+    pub fn insert(&mut self, index: usize, item: T) -> Result<()> {
+        if index > self.size {
+            return Err(Error::IndexOutOfBounds {
+                index,
+                len: self.size,
+            });
         }
-        self.array.set(self.size, item);
+        self.expand(self.size + 1);
+        self.array.shift(index, index + 1, self.size - index)?;
+        self.array.set(index, item)?;
         self.size += 1;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) -> Result<T> {
+        self.check_index(index)?;
+        let item = self.array.take(index)?;
+        self.array.shift(index + 1, index, self.size - index - 1)?;
+        self.size -= 1;
+        self.shrink();
+        Ok(item)
+    }
+
+    pub fn clear(&mut self) {
+        self.size = 0;
+        self.array = Array::new(self.policy.initial_capacity());
+    }
+
+    /// Appends `item` to the end of the list, same as [`ArrayList::add`].
+    pub fn push(&mut self, item: T) {
+        self.add(item);
+    }
+
+    /// Removes the last item, or returns `None` if the list is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.size == 0 {
+            return None;
+        }
+        self.size -= 1;
+        let item = self.array.take(self.size).ok();
+        self.shrink();
+        item
     }
 
-    pub fn set(&mut self, index: usize, item: T) {
-        self.array.set(index, item);
+    /// Removes the item at `index` and moves the last item into its place.
+    /// Unlike [`ArrayList::remove`] this does not shift the other items.
+    pub fn swap_remove(&mut self, index: usize) -> Result<T> {
+        self.check_index(index)?;
+        self.array.swap(index, self.size - 1)?;
+        self.size -= 1;
+        let item = self.array.take(self.size)?;
+        self.shrink();
+        Ok(item)
     }
 
-    pub fn insert(&mut self, index: usize, item: T) {
-        if index >= self.size {
-            crate::array::array_index_out_of_bounds(index, self.size);
+    /// Drops the items past the first `len`. Does nothing if the list is
+    /// not longer than `len`.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.size {
+            return;
         }
-        if self.array.size() == self.size {
-            self.expand();
+        let size = mem::replace(&mut self.size, len);
+        for index in len..size {
+            drop(self.array.take(index));
         }
-        let new_array: Array<T>;
-        let mut left: Array<T>;
-        if index != 0 {
-            left = self.array.copy_range(0, index);
-            let mut help_array: Array<T> = Array::new(1);
-            help_array.set(0, item);
-            let new_left = left.merge(&help_array, left.size() + 1);
-            left = new_left;
-        } else {
-            left = Array::new(1);
-            left.set(0, item);
+        self.shrink();
+    }
+
+    /// Makes room for at least `additional` more items.
+    pub fn reserve(&mut self, additional: usize) {
+        self.expand(self.size + additional);
+    }
+
+    /// Reduces the capacity to the number of items.
+    pub fn shrink_to_fit(&mut self) {
+        self.array.resize(self.size);
+    }
+
+    /// Moves all items of `other` to the end of this list, leaving `other`
+    /// empty.
+    ///
+    /// Like `Vec::append`, `other` keeps its capacity so it can be refilled
+    /// without reallocating; its growth policy is not asked to shrink it.
+    pub fn append(&mut self, other: &mut ArrayList<T>) {
+        let count = other.size;
+        self.expand(self.size + count);
+        for index in 0..count {
+            let item = other.array.take(index).unwrap();
+            let _ = self.array.set(self.size + index, item);
         }
-        let right = self.array.copy_range(index, self.size);
-        new_array = left.merge(&right, self.array.size());
-        self.array = new_array;
-        self.size += 1;
+        other.size = 0;
+        self.size += count;
     }
 
-    pub fn remove_item(&mut self, item: T) -> Option<T> {
-        let index_to_remove = self.index_of(item);
-        if index_to_remove == usize::MAX {
-            println!("Item does not exist in this list!");
-            None
-        } else {
-            self.remove(index_to_remove)
+    /// Splits the list in two at `at`. The list keeps the items before `at`
+    /// and the returned list holds the rest.
+    pub fn split_off(&mut self, at: usize) -> Result<ArrayList<T>> {
+        if at > self.size {
+            return Err(Error::IndexOutOfBounds {
+                index: at,
+                len: self.size,
+            });
         }
+        Ok(ArrayList {
+            size: self.size - at,
+            array: self.take_range(at, self.size),
+            policy: self.policy.clone(),
+        })
     }
 
-    pub fn remove(&mut self, index: usize) -> Option<T> {
-        // invalid index
-        if index >= self.size {
-            crate::array::array_index_out_of_bounds(index, self.size);
-            None
-        } else {
-            self.size -= 1;
-            if self.size == self.array.size() / 4 && self.array.size() > 4 {
-                self.shrink(); // if 2 -> 8 --> 4 - the 2nd index we removed will preserve so its safe
-            }
-            // last index
-            if index == self.size - 1 {
-                self.array.get(index)
+    /// Removes the items in `range` and returns an iterator over them.
+    ///
+    /// The items are moved out right away, so the list can be used again
+    /// while the iterator is alive.
+    pub fn drain<R>(&mut self, range: R) -> Result<ArrayListIntoIterator<T>>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (from, to) = self.check_range(range)?;
+        Ok(ArrayListIntoIterator {
+            start: 0,
+            end: to - from,
+            array: self.take_range(from, to),
+        })
+    }
+
+    /// Replaces the items in `range` with the items of `replace_with` and
+    /// returns an iterator over the removed items.
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Result<ArrayListIntoIterator<T>>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        let (from, to) = self.check_range(range)?;
+        let items = replace_with.into_iter().collect();
+        let removed = self.drain(from..to)?;
+        self.insert_list(from, items);
+        Ok(removed)
+    }
+
+    /// Keeps only the items for which `keep` returns `true`, in their
+    /// original order.
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        // the list looks empty while items are moved, so a panic in `keep`
+        // cannot leave it pointing at empty slots
+        let size = mem::replace(&mut self.size, 0);
+        let mut kept = 0;
+        for index in 0..size {
+            if keep(self.array.get(index).unwrap()) {
+                self.array.shift(index, kept, 1).unwrap();
+                kept += 1;
+            } else {
+                drop(self.array.take(index));
             }
-            // other index
-            else {
-                let item = self.array.get(index);
-                let new_array: Array<T>;
-                if index == 0 {
-                    new_array = self.array.copy(1, self.array.size(), self.array.size());
-                } else {
-                    let mut left = self.array.copy_range(0, index);
-                    let right = self.array.copy_range(index + 1, self.array.size());
-                    new_array = left.merge(&right, self.array.size());
-                }
-                self.array = new_array;
-                item
+        }
+        self.size = kept;
+        self.shrink();
+    }
+
+    /// Removes consecutive items for which `same_bucket` returns `true`.
+    ///
+    /// `same_bucket` is given an item and the last item that was kept before
+    /// it; only the first item of each run stays in the list.
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        if self.size == 0 {
+            return;
+        }
+        // see `retain`
+        let size = mem::replace(&mut self.size, 0);
+        let mut kept = 1;
+        for index in 1..size {
+            // both slots hold items and `kept - 1 < index`
+            let duplicate = unsafe {
+                let items = self.array.as_mut_ptr();
+                same_bucket(&mut *items.add(index), &mut *items.add(kept - 1))
+            };
+            if duplicate {
+                drop(self.array.take(index));
+            } else {
+                self.array.shift(index, kept, 1).unwrap();
+                kept += 1;
             }
         }
+        self.size = kept;
+        self.shrink();
     }
 
-    pub fn clear(&mut self) {
-        self.size = 0;
-        if self.array.size() > 4 {
-            self.array = Array::new(4);
+    /// Removes consecutive items that map to the same key.
+    pub fn dedup_by_key<F, K>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b));
+    }
+
+    /// Moves the items in `from..to` into a new array and closes the gap.
+    fn take_range(&mut self, from: usize, to: usize) -> Array<T> {
+        let mut taken = Array::new(to - from);
+        for index in from..to {
+            let _ = taken.set(index - from, self.array.take(index).unwrap());
         }
+        let _ = self.array.shift(to, from, self.size - to);
+        self.size -= to - from;
+        self.shrink();
+        taken
     }
 
-    pub fn swap(&mut self, index0: usize, index1: usize) {
-        if index0 >= self.size {
-            crate::array::array_index_out_of_bounds(index0, self.size);
+    /// Moves all `items` into the list, the first one ending up at `index`.
+    fn insert_list(&mut self, index: usize, items: ArrayList<T>) {
+        let count = items.size;
+        self.expand(self.size + count);
+        let _ = self.array.shift(index, index + count, self.size - index);
+        for (offset, item) in items.into_iter().enumerate() {
+            let _ = self.array.set(index + offset, item);
         }
-        if index1 >= self.size {
-            crate::array::array_index_out_of_bounds(index1, self.size);
+        self.size += count;
+    }
+
+    pub fn swap(&mut self, index0: usize, index1: usize) -> Result<()> {
+        self.check_index(index0)?;
+        self.check_index(index1)?;
+        self.array.swap(index0, index1)
+    }
+
+    /// Iterates over the items from the first to the last. The list stays
+    /// borrowed while iterating, so it cannot be modified in the meantime:
+    ///
+    /// ```compile_fail
+    /// let mut list = data_structures::ArrayList::new();
+    /// list.add(1);
+    /// for item in list.iter() {
+    ///     list.add(*item);
+    /// }
+    /// ```
+    pub fn iter(&self) -> ArrayListIterator<'_, T> {
+        ArrayListIterator {
+            start: 0,
+            end: self.size,
+            array: &self.array,
         }
-        let first = self.get(index0).unwrap();
-        let second = self.get(index1).unwrap();
-        self.set(index0, second);
-        self.set(index1, first);
     }
 
-    fn index_of(&mut self, item: T) -> usize {
-        for i in 0..self.size {
-            if self.array.get(i).unwrap() == item {
-                return i;
-            }
+    pub fn iter_mut(&mut self) -> ArrayListIteratorMut<'_, T> {
+        ArrayListIteratorMut {
+            remaining: self.size,
+            items: self.array.iter_range_mut(0, self.size),
         }
-        return usize::MAX;
     }
+}
 
-    pub fn into_iter(&mut self) -> ArrayListIterator<T> {
-        let size = self.size;
-        let array = self.array.clone();
+impl<T> ArrayList<T>
+where
+    T: PartialEq,
+{
+    pub fn remove_item(&mut self, item: T) -> Result<T> {
+        match self.index_of(&item) {
+            Some(index) => self.remove(index),
+            None => Err(Error::NotFound),
+        }
+    }
 
-        ArrayListIterator {
+    /// Removes consecutive equal items.
+    pub fn dedup(&mut self) {
+        self.dedup_by(|a, b| a == b);
+    }
+
+    fn index_of(&self, item: &T) -> Option<usize> {
+        (0..self.size).find(|&i| self.array.get(i) == Ok(item))
+    }
+}
+
+impl<T> ArrayList<T>
+where
+    T: Clone,
+{
+    /// Appends clones of all items in `items`.
+    pub fn extend_from_slice(&mut self, items: &[T]) {
+        self.reserve(items.len());
+        for item in items {
+            self.add(item.clone());
+        }
+    }
+}
+
+impl<T> IntoIterator for ArrayList<T> {
+    type Item = T;
+    type IntoIter = ArrayListIntoIterator<T>;
+
+    fn into_iter(self) -> ArrayListIntoIterator<T> {
+        ArrayListIntoIterator {
             start: 0,
-            end: size,
-            array: array,
+            end: self.size,
+            array: self.array,
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArrayList<T> {
+    type Item = &'a T;
+    type IntoIter = ArrayListIterator<'a, T>;
+
+    fn into_iter(self) -> ArrayListIterator<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut ArrayList<T> {
+    type Item = &'a mut T;
+    type IntoIter = ArrayListIteratorMut<'a, T>;
+
+    fn into_iter(self) -> ArrayListIteratorMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> Deref for ArrayList<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        // the first `size` slots always hold items
+        unsafe { slice::from_raw_parts(self.array.as_ptr(), self.size) }
+    }
+}
+
+impl<T> DerefMut for ArrayList<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.array.as_mut_ptr(), self.size) }
+    }
+}
+
+impl<T> AsRef<[T]> for ArrayList<T> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> AsMut<[T]> for ArrayList<T> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T> Borrow<[T]> for ArrayList<T> {
+    fn borrow(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> BorrowMut<[T]> for ArrayList<T> {
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T, I> Index<I> for ArrayList<T>
+where
+    I: SliceIndex<[T]>,
+{
+    type Output = I::Output;
+
+    fn index(&self, index: I) -> &I::Output {
+        Index::index(&**self, index)
+    }
+}
+
+impl<T, I> IndexMut<I> for ArrayList<T>
+where
+    I: SliceIndex<[T]>,
+{
+    fn index_mut(&mut self, index: I) -> &mut I::Output {
+        IndexMut::index_mut(&mut **self, index)
+    }
+}
+
+impl<T> Clone for ArrayList<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        let mut list = ArrayList::with_capacity_and_policy(self.size, self.policy.clone());
+        list.extend(self.iter().cloned());
+        list
+    }
+}
+
+impl<T> fmt::Debug for ArrayList<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> PartialEq for ArrayList<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &ArrayList<T>) -> bool {
+        **self == **other
+    }
+}
+
+impl<T> Eq for ArrayList<T> where T: Eq {}
+
+impl<T> PartialOrd for ArrayList<T>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &ArrayList<T>) -> Option<Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<T> Ord for ArrayList<T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &ArrayList<T>) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl<T> Hash for ArrayList<T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<T> FromIterator<T> for ArrayList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = ArrayList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for ArrayList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for item in iter {
+            self.add(item);
         }
     }
 }
+
+impl<'a, T> Extend<&'a T> for ArrayList<T>
+where
+    T: Copy + 'a,
+{
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied())
+    }
+}
+
+/// Turns the list into a full array holding exactly its items.
+impl<T> From<ArrayList<T>> for Array<T> {
+    fn from(mut list: ArrayList<T>) -> Array<T> {
+        list.array.resize(list.size);
+        list.array
+    }
+}
@@ -0,0 +1,28 @@
+use std::hint;
+use std::thread;
+
+/// Waits a little longer on every call: first by spinning, then by yielding
+/// the thread. Used by the lock-free queues while another thread finishes
+/// its operation or while a blocking call waits for room or items.
+pub(crate) struct Backoff {
+    step: u32,
+}
+
+const SPIN_LIMIT: u32 = 6;
+
+impl Backoff {
+    pub(crate) fn new() -> Backoff {
+        Backoff { step: 0 }
+    }
+
+    pub(crate) fn snooze(&mut self) {
+        if self.step <= SPIN_LIMIT {
+            for _ in 0..1 << self.step {
+                hint::spin_loop();
+            }
+            self.step += 1;
+        } else {
+            thread::yield_now();
+        }
+    }
+}
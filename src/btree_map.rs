@@ -0,0 +1,1307 @@
+//! Ordered map backed by a B-tree.
+//!
+//! Every node keeps its keys, values and child pointers in fixed-capacity
+//! [`Array`]s, so a lookup touches a few contiguous buffers per level
+//! instead of one allocation per entry. Nodes hold at most
+//! `branching_factor - 1` entries and, except for the root, at least half
+//! as many. Inserting splits full nodes on the way back up, and removing
+//! refills nodes that got too small from a sibling or merges them.
+//!
+//! ```
+//! use data_structures::BTreeMap;
+//!
+//! let mut map = BTreeMap::with_branching_factor(4);
+//! map.extend((0..100).map(|key| (key, key * key)));
+//! assert_eq!(map.get(&7), Some(&49));
+//! assert!(map.range(10..13).map(|(key, _)| *key).eq(10..13));
+//! map.check_invariants().unwrap();
+//! ```
+
+use crate::array::Array;
+use crate::array_list::ArrayList;
+use crate::error::{Error, Result};
+use crate::map_iter::{map_iterators, Entries};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::{FromIterator, FusedIterator};
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Bound, Index, RangeBounds};
+
+/// Branching factor of maps created with [`BTreeMap::new`].
+pub const DEFAULT_BRANCHING_FACTOR: usize = 16;
+
+struct BTreeNode<K, V> {
+    len: usize,
+    /// Room for `branching_factor` entries: one more than a node may keep,
+    /// so a node can overflow before it is split.
+    keys: Array<K>,
+    values: Array<V>,
+    /// `len + 1` children, or none for leaves.
+    children: Array<*mut BTreeNode<K, V>>,
+}
+
+/// Map from keys of type `K` to values of type `V`, sorted by key.
+pub struct BTreeMap<K, V> {
+    size: usize,
+    branching_factor: usize,
+    root: *mut BTreeNode<K, V>,
+    _marker: PhantomData<Box<BTreeNode<K, V>>>,
+}
+
+unsafe impl<K, V> Send for BTreeMap<K, V>
+where
+    K: Send,
+    V: Send,
+{
+}
+
+unsafe impl<K, V> Sync for BTreeMap<K, V>
+where
+    K: Sync,
+    V: Sync,
+{
+}
+
+impl<K, V> BTreeNode<K, V> {
+    fn allocate(branching_factor: usize, leaf: bool) -> *mut BTreeNode<K, V> {
+        let children = if leaf { 0 } else { branching_factor + 1 };
+        Box::into_raw(Box::new(BTreeNode {
+            len: 0,
+            keys: Array::new(branching_factor),
+            values: Array::new(branching_factor),
+            children: Array::new(children),
+        }))
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.children.size() == 0
+    }
+
+    fn key(&self, index: usize) -> &K {
+        self.keys.get(index).unwrap()
+    }
+
+    fn child(&self, index: usize) -> *mut BTreeNode<K, V> {
+        *self.children.get(index).unwrap()
+    }
+
+    /// Index of the entry holding `key`, or of the child that would hold it.
+    fn search<Q>(&self, key: &Q) -> std::result::Result<usize, usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (mut low, mut high) = (0, self.len);
+        while low < high {
+            let middle = (low + high) / 2;
+            match self.key(middle).borrow().cmp(key) {
+                Ordering::Less => low = middle + 1,
+                Ordering::Greater => high = middle,
+                Ordering::Equal => return Ok(middle),
+            }
+        }
+        Err(low)
+    }
+
+    /// Number of leading keys for which `before` holds. The keys are
+    /// sorted, so these are exactly the keys for which it holds.
+    fn partition<F>(&self, before: &F) -> usize
+    where
+        F: Fn(&K) -> bool,
+    {
+        let (mut low, mut high) = (0, self.len);
+        while low < high {
+            let middle = (low + high) / 2;
+            if before(self.key(middle)) {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        low
+    }
+
+    fn insert_entry(&mut self, index: usize, key: K, value: V) {
+        let _ = self.keys.shift(index, index + 1, self.len - index);
+        let _ = self.values.shift(index, index + 1, self.len - index);
+        let _ = self.keys.set(index, key);
+        let _ = self.values.set(index, value);
+        self.len += 1;
+    }
+
+    /// Puts `child` at `index`. Call after inserting the entry it belongs
+    /// to, so the node has one child too few.
+    fn insert_child(&mut self, index: usize, child: *mut BTreeNode<K, V>) {
+        let _ = self.children.shift(index, index + 1, self.len - index);
+        let _ = self.children.set(index, child);
+    }
+
+    fn remove_entry(&mut self, index: usize) -> (K, V) {
+        let key = self.keys.take(index).unwrap();
+        let value = self.values.take(index).unwrap();
+        let _ = self.keys.shift(index + 1, index, self.len - index - 1);
+        let _ = self.values.shift(index + 1, index, self.len - index - 1);
+        self.len -= 1;
+        (key, value)
+    }
+
+    /// Takes out the child at `index`. Call after removing the entry it
+    /// belonged to, so the node has one child too many.
+    fn remove_child(&mut self, index: usize) -> *mut BTreeNode<K, V> {
+        let child = self.children.take(index).unwrap();
+        let _ = self.children.shift(index + 1, index, self.len + 1 - index);
+        child
+    }
+
+    /// Splits the overflowing child at `index` around its middle entry,
+    /// which moves up into this node.
+    fn split_child(&mut self, index: usize, branching_factor: usize) {
+        let (key, value, right) = unsafe {
+            let child = &mut *self.child(index);
+            let middle = child.len / 2;
+            let right = BTreeNode::allocate(branching_factor, child.is_leaf());
+            let moved = child.len - middle - 1;
+            for offset in 0..moved {
+                let _ = (*right)
+                    .keys
+                    .set(offset, child.keys.take(middle + 1 + offset).unwrap());
+                let _ = (*right)
+                    .values
+                    .set(offset, child.values.take(middle + 1 + offset).unwrap());
+            }
+            if !child.is_leaf() {
+                for offset in 0..=moved {
+                    let grandchild = child.children.take(middle + 1 + offset).unwrap();
+                    let _ = (*right).children.set(offset, grandchild);
+                }
+            }
+            (*right).len = moved;
+            child.len = middle;
+            (
+                child.keys.take(middle).unwrap(),
+                child.values.take(middle).unwrap(),
+                right,
+            )
+        };
+        self.insert_entry(index, key, value);
+        self.insert_child(index + 1, right);
+    }
+
+    /// Moves the last entry of the child left of entry `index` up, and the
+    /// entry down into the child on its right.
+    fn rotate_right(&mut self, index: usize) {
+        unsafe {
+            let left = &mut *self.child(index);
+            let (key, value) = left.remove_entry(left.len - 1);
+            let moved = if left.is_leaf() {
+                None
+            } else {
+                Some(left.remove_child(left.len + 1))
+            };
+            let key = self.keys.replace(index, key).unwrap().unwrap();
+            let value = self.values.replace(index, value).unwrap().unwrap();
+            let right = &mut *self.child(index + 1);
+            right.insert_entry(0, key, value);
+            if let Some(moved) = moved {
+                right.insert_child(0, moved);
+            }
+        }
+    }
+
+    /// Moves the first entry of the child right of entry `index` up, and
+    /// the entry down into the child on its left.
+    fn rotate_left(&mut self, index: usize) {
+        unsafe {
+            let right = &mut *self.child(index + 1);
+            let (key, value) = right.remove_entry(0);
+            let moved = if right.is_leaf() {
+                None
+            } else {
+                Some(right.remove_child(0))
+            };
+            let key = self.keys.replace(index, key).unwrap().unwrap();
+            let value = self.values.replace(index, value).unwrap().unwrap();
+            let left = &mut *self.child(index);
+            left.insert_entry(left.len, key, value);
+            if let Some(moved) = moved {
+                left.insert_child(left.len, moved);
+            }
+        }
+    }
+
+    /// Merges the children on both sides of entry `index` together with the
+    /// entry into the left one, and frees the right one.
+    fn merge_children(&mut self, index: usize) {
+        let (key, value) = self.remove_entry(index);
+        let mut right = unsafe { Box::from_raw(self.remove_child(index + 1)) };
+        let left = unsafe { &mut *self.child(index) };
+        left.insert_entry(left.len, key, value);
+        let start = left.len;
+        for offset in 0..right.len {
+            let _ = left
+                .keys
+                .set(start + offset, right.keys.take(offset).unwrap());
+            let _ = left
+                .values
+                .set(start + offset, right.values.take(offset).unwrap());
+        }
+        if !right.is_leaf() {
+            for offset in 0..=right.len {
+                let child = right.children.take(offset).unwrap();
+                let _ = left.children.set(start + offset, child);
+            }
+        }
+        left.len += right.len;
+    }
+
+    /// Refills the child at `index` if it has fewer than `min_len` entries,
+    /// from a sibling that can spare one or by merging it with a sibling.
+    fn fix_child(&mut self, index: usize, min_len: usize) {
+        let len = |child: *mut BTreeNode<K, V>| unsafe { (*child).len };
+        if len(self.child(index)) >= min_len {
+            return;
+        }
+        if index > 0 && len(self.child(index - 1)) > min_len {
+            self.rotate_right(index - 1);
+        } else if index < self.len && len(self.child(index + 1)) > min_len {
+            self.rotate_left(index);
+        } else if index > 0 {
+            self.merge_children(index - 1);
+        } else {
+            self.merge_children(index);
+        }
+    }
+}
+
+/// Inserts into the subtree under `node`, splitting children that overflow.
+unsafe fn insert<K, V>(
+    node: *mut BTreeNode<K, V>,
+    key: K,
+    value: V,
+    branching_factor: usize,
+) -> Option<V>
+where
+    K: Ord,
+{
+    let node = &mut *node;
+    match node.search(&key) {
+        Ok(index) => Some(mem::replace(node.values.get_mut(index).unwrap(), value)),
+        Err(index) if node.is_leaf() => {
+            node.insert_entry(index, key, value);
+            None
+        }
+        Err(index) => {
+            let old = insert(node.child(index), key, value, branching_factor);
+            if (*node.child(index)).len == branching_factor {
+                node.split_child(index, branching_factor);
+            }
+            old
+        }
+    }
+}
+
+/// Removes `key` from the subtree under `node`, refilling children that
+/// get too small.
+unsafe fn remove<K, V, Q>(node: *mut BTreeNode<K, V>, key: &Q, min_len: usize) -> Option<(K, V)>
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    let node = &mut *node;
+    match node.search(key) {
+        Ok(index) if node.is_leaf() => Some(node.remove_entry(index)),
+        Ok(index) => {
+            // replace the entry with the one before it, which is in a leaf
+            let (key, value) = pop_last(node.child(index), min_len);
+            let key = node.keys.replace(index, key).unwrap().unwrap();
+            let value = node.values.replace(index, value).unwrap().unwrap();
+            node.fix_child(index, min_len);
+            Some((key, value))
+        }
+        Err(_) if node.is_leaf() => None,
+        Err(index) => {
+            let removed = remove(node.child(index), key, min_len)?;
+            node.fix_child(index, min_len);
+            Some(removed)
+        }
+    }
+}
+
+/// Removes the first entry of the non-empty subtree under `node`.
+unsafe fn pop_first<K, V>(node: *mut BTreeNode<K, V>, min_len: usize) -> (K, V) {
+    let node = &mut *node;
+    if node.is_leaf() {
+        return node.remove_entry(0);
+    }
+    let entry = pop_first(node.child(0), min_len);
+    node.fix_child(0, min_len);
+    entry
+}
+
+/// Removes the last entry of the non-empty subtree under `node`.
+unsafe fn pop_last<K, V>(node: *mut BTreeNode<K, V>, min_len: usize) -> (K, V) {
+    let node = &mut *node;
+    if node.is_leaf() {
+        return node.remove_entry(node.len - 1);
+    }
+    let entry = pop_last(node.child(node.len), min_len);
+    node.fix_child(node.len, min_len);
+    entry
+}
+
+/// Frees the subtree under `node`, dropping its entries.
+unsafe fn free<K, V>(node: *mut BTreeNode<K, V>) {
+    let node = Box::from_raw(node);
+    if !node.is_leaf() {
+        for index in 0..=node.len {
+            free(node.child(index));
+        }
+    }
+}
+
+/// Cuts the subtree under `node` at `key` and returns a new subtree of
+/// the same height with the entries whose keys are greater than or equal to
+/// `key`. Only the nodes on the search path are split, so the nodes along
+/// the cut may be short of entries or even empty.
+unsafe fn split_off<K, V, Q>(
+    node: *mut BTreeNode<K, V>,
+    key: &Q,
+    branching_factor: usize,
+) -> *mut BTreeNode<K, V>
+where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    let node = &mut *node;
+    let at = node.partition(&|entry_key: &K| entry_key.borrow() < key);
+    let right = BTreeNode::allocate(branching_factor, node.is_leaf());
+    let moved = node.len - at;
+    for offset in 0..moved {
+        let _ = (*right)
+            .keys
+            .set(offset, node.keys.take(at + offset).unwrap());
+        let _ = (*right)
+            .values
+            .set(offset, node.values.take(at + offset).unwrap());
+    }
+    if !node.is_leaf() {
+        // the child at the cut is split in turn, the ones after it move
+        for offset in 1..=moved {
+            let child = node.children.take(at + offset).unwrap();
+            let _ = (*right).children.set(offset, child);
+        }
+        let _ = (*right)
+            .children
+            .set(0, split_off(node.child(at), key, branching_factor));
+    }
+    (*right).len = moved;
+    node.len = at;
+    right
+}
+
+/// Number of entries in the subtree under `node`.
+unsafe fn count_entries<K, V>(node: *const BTreeNode<K, V>) -> usize {
+    let node = &*node;
+    let mut count = node.len;
+    if !node.is_leaf() {
+        for index in 0..=node.len {
+            count += count_entries(node.child(index));
+        }
+    }
+    count
+}
+
+unsafe fn clone_subtree<K, V>(node: *const BTreeNode<K, V>) -> *mut BTreeNode<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    let node = &*node;
+    let copy = BTreeNode::allocate(node.keys.size(), node.is_leaf());
+    for index in 0..node.len {
+        let _ = (*copy).keys.set(index, node.key(index).clone());
+        let _ = (*copy)
+            .values
+            .set(index, node.values.get(index).unwrap().clone());
+    }
+    if !node.is_leaf() {
+        for index in 0..=node.len {
+            let _ = (*copy)
+                .children
+                .set(index, clone_subtree(node.child(index)));
+        }
+    }
+    (*copy).len = node.len;
+    copy
+}
+
+/// Checks the subtree under `node`, whose keys must lie strictly between
+/// `lower` and `upper`, and returns its number of entries and its depth.
+unsafe fn check_subtree<K, V>(
+    node: *const BTreeNode<K, V>,
+    lower: Option<&K>,
+    upper: Option<&K>,
+    min_len: usize,
+    max_len: usize,
+) -> Result<(usize, usize)>
+where
+    K: Ord,
+{
+    let node = &*node;
+    let broken = |reason| Err(Error::BrokenInvariant { reason });
+    if node.len < min_len {
+        return broken("a node has too few entries");
+    }
+    if node.len > max_len {
+        return broken("a node has too many entries");
+    }
+    for index in 0..node.keys.size() {
+        let filled = index < node.len;
+        if node.keys.is_initialized(index) != filled || node.values.is_initialized(index) != filled
+        {
+            return broken("the filled slots of a node do not match its length");
+        }
+    }
+    let children = if node.is_leaf() { 0 } else { node.len + 1 };
+    for index in 0..node.children.size() {
+        if node.children.is_initialized(index) != (index < children) {
+            return broken("a node does not have one child more than entries");
+        }
+    }
+    for index in 0..node.len {
+        let key = node.key(index);
+        let previous = if index == 0 {
+            lower
+        } else {
+            Some(node.key(index - 1))
+        };
+        if previous.is_some_and(|previous| key <= previous)
+            || upper.is_some_and(|upper| key >= upper)
+        {
+            return broken("keys are out of order");
+        }
+    }
+    if node.is_leaf() {
+        return Ok((node.len, 1));
+    }
+    // only the root may hold fewer entries, so the children get the real
+    // minimum even when this is the root
+    let min_len = max_len / 2;
+    let mut count = node.len;
+    let mut depth = None;
+    for index in 0..=node.len {
+        let lower = if index == 0 {
+            lower
+        } else {
+            Some(node.key(index - 1))
+        };
+        let upper = if index == node.len {
+            upper
+        } else {
+            Some(node.key(index))
+        };
+        let (child_count, child_depth) =
+            check_subtree(node.child(index), lower, upper, min_len, max_len)?;
+        if depth.is_some_and(|depth| depth != child_depth) {
+            return broken("leaves are at different depths");
+        }
+        depth = Some(child_depth);
+        count += child_count;
+    }
+    Ok((count, depth.unwrap() + 1))
+}
+
+/// Position of an entry: the nodes on the way from the root down to the
+/// entry. Every node but the last is stored with the index of the child
+/// the way goes through, the last one with the index of the entry. The path
+/// is empty once the cursor moved past either end.
+struct Cursor<K, V> {
+    path: ArrayList<(*mut BTreeNode<K, V>, usize)>,
+}
+
+impl<K, V> Cursor<K, V> {
+    fn current(&self) -> Option<(*mut BTreeNode<K, V>, usize)> {
+        self.path.last().copied()
+    }
+
+    /// Cursor at the first entry under `root` whose key `before` does not
+    /// hold for.
+    fn first_after<F>(root: *mut BTreeNode<K, V>, before: F) -> Cursor<K, V>
+    where
+        F: Fn(&K) -> bool,
+    {
+        let mut cursor = Cursor {
+            path: ArrayList::new(),
+        };
+        let mut node = root;
+        loop {
+            let node_ref = unsafe { &*node };
+            let index = node_ref.partition(&before);
+            cursor.path.push((node, index));
+            if node_ref.is_leaf() {
+                if index == node_ref.len {
+                    cursor.climb_forward();
+                }
+                return cursor;
+            }
+            node = node_ref.child(index);
+        }
+    }
+
+    /// Cursor at the last entry under `root` whose key `after` does not
+    /// hold for.
+    fn last_before<F>(root: *mut BTreeNode<K, V>, after: F) -> Cursor<K, V>
+    where
+        F: Fn(&K) -> bool,
+    {
+        let mut cursor = Cursor {
+            path: ArrayList::new(),
+        };
+        let mut node = root;
+        loop {
+            let node_ref = unsafe { &*node };
+            let count = node_ref.partition(&|key: &K| !after(key));
+            if node_ref.is_leaf() {
+                if count == 0 {
+                    cursor.path.push((node, 0));
+                    cursor.climb_backward();
+                } else {
+                    cursor.path.push((node, count - 1));
+                }
+                return cursor;
+            }
+            cursor.path.push((node, count));
+            node = node_ref.child(count);
+        }
+    }
+
+    fn move_next(&mut self) {
+        let (node, index) = match self.current() {
+            Some(current) => current,
+            None => return,
+        };
+        let node = unsafe { &*node };
+        if node.is_leaf() {
+            if index + 1 < node.len {
+                self.path.last_mut().unwrap().1 = index + 1;
+            } else {
+                self.climb_forward();
+            }
+            return;
+        }
+        // go down the child after the entry to its first entry
+        self.path.last_mut().unwrap().1 = index + 1;
+        let mut child = node.child(index + 1);
+        loop {
+            self.path.push((child, 0));
+            if unsafe { (*child).is_leaf() } {
+                return;
+            }
+            child = unsafe { (*child).child(0) };
+        }
+    }
+
+    fn move_prev(&mut self) {
+        let (node, index) = match self.current() {
+            Some(current) => current,
+            None => return,
+        };
+        let node = unsafe { &*node };
+        if node.is_leaf() {
+            if index > 0 {
+                self.path.last_mut().unwrap().1 = index - 1;
+            } else {
+                self.climb_backward();
+            }
+            return;
+        }
+        // go down the child before the entry, which has the same index, to
+        // its last entry
+        let mut child = node.child(index);
+        loop {
+            let len = unsafe { (*child).len };
+            if unsafe { (*child).is_leaf() } {
+                self.path.push((child, len - 1));
+                return;
+            }
+            self.path.push((child, len));
+            child = unsafe { (*child).child(len) };
+        }
+    }
+
+    /// Leaves the last node of the path, which has no entries left, for the
+    /// first ancestor with an entry after the child the path went through.
+    fn climb_forward(&mut self) {
+        self.path.pop();
+        while let Some((node, child)) = self.current() {
+            if child < unsafe { (*node).len } {
+                return;
+            }
+            self.path.pop();
+        }
+    }
+
+    /// Leaves the last node of the path for the first ancestor with an
+    /// entry before the child the path went through.
+    fn climb_backward(&mut self) {
+        self.path.pop();
+        while let Some((_, child)) = self.current() {
+            if child > 0 {
+                self.path.last_mut().unwrap().1 = child - 1;
+                return;
+            }
+            self.path.pop();
+        }
+    }
+}
+
+/// Entries between two cursors, both included, walked from both ends.
+struct Walk<K, V> {
+    front: Cursor<K, V>,
+    back: Cursor<K, V>,
+}
+
+impl<K, V> Walk<K, V> {
+    fn all(root: *mut BTreeNode<K, V>) -> Walk<K, V> {
+        Walk {
+            front: Cursor::first_after(root, |_| false),
+            back: Cursor::last_before(root, |_| false),
+        }
+    }
+
+    fn finish(&mut self) {
+        self.front.path.clear();
+        self.back.path.clear();
+    }
+
+    fn next(&mut self) -> Option<(*mut BTreeNode<K, V>, usize)> {
+        let current = self.front.current()?;
+        if Some(current) == self.back.current() {
+            self.finish();
+        } else {
+            self.front.move_next();
+        }
+        Some(current)
+    }
+
+    fn next_back(&mut self) -> Option<(*mut BTreeNode<K, V>, usize)> {
+        let current = self.back.current()?;
+        if Some(current) == self.front.current() {
+            self.finish();
+        } else {
+            self.back.move_prev();
+        }
+        Some(current)
+    }
+}
+
+unsafe fn entry<'a, K, V>((node, index): (*mut BTreeNode<K, V>, usize)) -> (&'a K, &'a V) {
+    let node = &*node;
+    (node.key(index), node.values.get(index).unwrap())
+}
+
+unsafe fn entry_mut<'a, K, V>((node, index): (*mut BTreeNode<K, V>, usize)) -> (&'a K, &'a mut V) {
+    let node = &mut *node;
+    (
+        node.keys.get(index).unwrap(),
+        node.values.get_mut(index).unwrap(),
+    )
+}
+
+/// Pointers to the entry at `position`, which the iterators turn into
+/// shared or mutable references.
+unsafe fn entry_ptr<K, V>((node, index): (*mut BTreeNode<K, V>, usize)) -> (*const K, *mut V) {
+    let node = &*node;
+    (node.keys.slot_ptr(index), node.values.slot_ptr(index))
+}
+
+impl<K, V> Entries for Walk<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn next(&mut self) -> Option<(*const K, *mut V)> {
+        Walk::next(self).map(|position| unsafe { entry_ptr(position) })
+    }
+
+    fn next_back(&mut self) -> Option<(*const K, *mut V)> {
+        Walk::next_back(self).map(|position| unsafe { entry_ptr(position) })
+    }
+}
+
+map_iterators! {
+    BTreeMap walked by Walk<K, V>;
+    BTreeMapIterator, BTreeMapIteratorMut, BTreeMapIntoIterator,
+    BTreeMapRange, BTreeMapRangeMut,
+    BTreeMapKeys, BTreeMapValues, BTreeMapValuesMut,
+}
+
+impl<K, V> Default for BTreeMap<K, V> {
+    fn default() -> Self {
+        BTreeMap::new()
+    }
+}
+
+impl<K, V> BTreeMap<K, V> {
+    pub fn new() -> BTreeMap<K, V> {
+        BTreeMap::with_branching_factor(DEFAULT_BRANCHING_FACTOR)
+    }
+
+    /// Creates an empty map whose nodes have at most `branching_factor`
+    /// children and hold one entry less.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `branching_factor` is less than 3.
+    pub fn with_branching_factor(branching_factor: usize) -> BTreeMap<K, V> {
+        assert!(branching_factor >= 3, "Branching factor must be at least 3");
+        BTreeMap {
+            size: 0,
+            branching_factor,
+            root: BTreeNode::allocate(branching_factor, true),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn branching_factor(&self) -> usize {
+        self.branching_factor
+    }
+
+    /// Number of levels of nodes, 1 for a map that fits into the root.
+    pub fn height(&self) -> usize {
+        let mut height = 1;
+        let mut node = self.root;
+        unsafe {
+            while !(*node).is_leaf() {
+                node = (*node).child(0);
+                height += 1;
+            }
+        }
+        height
+    }
+
+    pub fn clear(&mut self) {
+        let root = mem::replace(
+            &mut self.root,
+            BTreeNode::allocate(self.branching_factor, true),
+        );
+        self.size = 0;
+        unsafe { free(root) };
+    }
+
+    /// Entry with the smallest key, at the start of the leftmost leaf.
+    pub fn first(&self) -> Option<(&K, &V)> {
+        if self.size == 0 {
+            return None;
+        }
+        let mut node = self.root;
+        unsafe {
+            while !(*node).is_leaf() {
+                node = (*node).child(0);
+            }
+            Some(entry((node, 0)))
+        }
+    }
+
+    /// Entry with the largest key, at the end of the rightmost leaf.
+    pub fn last(&self) -> Option<(&K, &V)> {
+        if self.size == 0 {
+            return None;
+        }
+        let mut node = self.root;
+        unsafe {
+            while !(*node).is_leaf() {
+                node = (*node).child((*node).len);
+            }
+            Some(entry((node, (*node).len - 1)))
+        }
+    }
+
+    /// Removes and returns the entry with the smallest key.
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        if self.size == 0 {
+            return None;
+        }
+        let entry = unsafe { pop_first(self.root, self.min_len()) };
+        self.removed();
+        Some(entry)
+    }
+
+    /// Removes and returns the entry with the largest key.
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        if self.size == 0 {
+            return None;
+        }
+        let entry = unsafe { pop_last(self.root, self.min_len()) };
+        self.removed();
+        Some(entry)
+    }
+
+    pub fn iter(&self) -> BTreeMapIterator<'_, K, V> {
+        BTreeMapIterator {
+            remaining: self.size,
+            walk: Walk::all(self.root),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Iterates over the entries in key order, allowing to change the
+    /// values.
+    pub fn iter_mut(&mut self) -> BTreeMapIteratorMut<'_, K, V> {
+        BTreeMapIteratorMut {
+            remaining: self.size,
+            walk: Walk::all(self.root),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn keys(&self) -> BTreeMapKeys<'_, K, V> {
+        BTreeMapKeys {
+            entries: self.iter(),
+        }
+    }
+
+    pub fn values(&self) -> BTreeMapValues<'_, K, V> {
+        BTreeMapValues {
+            entries: self.iter(),
+        }
+    }
+
+    pub fn values_mut(&mut self) -> BTreeMapValuesMut<'_, K, V> {
+        BTreeMapValuesMut {
+            entries: self.iter_mut(),
+        }
+    }
+
+    /// Fewest entries a node other than the root may hold.
+    fn min_len(&self) -> usize {
+        (self.branching_factor - 1) / 2
+    }
+
+    /// Updates the size after a removal and drops the root if it ran out of
+    /// entries but still has a child.
+    fn removed(&mut self) {
+        self.size -= 1;
+        self.drop_empty_roots();
+    }
+
+    /// Replaces the root by its only child for as long as it has no entries.
+    fn drop_empty_roots(&mut self) {
+        unsafe {
+            while (*self.root).len == 0 && !(*self.root).is_leaf() {
+                let mut root = Box::from_raw(self.root);
+                self.root = root.children.take(0).unwrap();
+            }
+        }
+    }
+
+    /// Refills the short nodes along one edge of a tree that was cut by
+    /// [`split_off`], where `edge` picks the child on the edge. Each step
+    /// fixes the deepest edge node that is short and has a parent with
+    /// entries, from a sibling off the edge, which has enough entries.
+    fn fix_edge<F>(&mut self, edge: F)
+    where
+        F: Fn(&BTreeNode<K, V>) -> usize,
+    {
+        let min_len = self.min_len();
+        loop {
+            self.drop_empty_roots();
+            let mut short = None;
+            let mut node = self.root;
+            unsafe {
+                while !(*node).is_leaf() {
+                    let index = edge(&*node);
+                    let child = (*node).child(index);
+                    if (*node).len > 0 && (*child).len < min_len {
+                        short = Some((node, index));
+                    }
+                    node = child;
+                }
+                match short {
+                    Some((node, index)) => (*node).fix_child(index, min_len),
+                    None => return,
+                }
+            }
+        }
+    }
+}
+
+impl<K, V> BTreeMap<K, V>
+where
+    K: Ord,
+{
+    /// Inserts `value` under `key` and returns the value it replaced.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let old = unsafe { insert(self.root, key, value, self.branching_factor) };
+        if old.is_none() {
+            self.size += 1;
+        }
+        if unsafe { (*self.root).len } == self.branching_factor {
+            let root = BTreeNode::allocate(self.branching_factor, false);
+            unsafe {
+                let _ = (*root).children.set(0, self.root);
+                (*root).split_child(0, self.branching_factor);
+            }
+            self.root = root;
+        }
+        old
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        Some(unsafe { entry(self.find(key)?) })
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        Some(unsafe { entry_mut(self.find(key)?) }.1)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Removes `key` and returns its value. Nodes left less than half full
+    /// borrow an entry from a sibling or merge with it on the way back up.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let entry = unsafe { remove(self.root, key, self.min_len())? };
+        self.removed();
+        Some(entry)
+    }
+
+    /// Entries with keys in `range`, in key order. Two cursors descend to
+    /// the first and the last entry, one node search per level, and then
+    /// move towards each other. A reversed range yields nothing.
+    pub fn range<Q, R>(&self, range: R) -> BTreeMapRange<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        BTreeMapRange {
+            walk: self.range_walk(range),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Like [`BTreeMap::range`], with mutable values.
+    pub fn range_mut<Q, R>(&mut self, range: R) -> BTreeMapRangeMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        BTreeMapRangeMut {
+            walk: self.range_walk(range),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Moves all entries of `other` into the map, leaving `other` empty.
+    /// Where both maps hold a key, the value from `other` wins.
+    ///
+    /// The entries of the smaller map are inserted into the larger one, so
+    /// this takes O(m log n) for m entries in the smaller map and n in the
+    /// larger. The larger map keeps its nodes if both have the same
+    /// branching factor.
+    pub fn append(&mut self, other: &mut BTreeMap<K, V>) {
+        if other.size > self.size && other.branching_factor == self.branching_factor {
+            mem::swap(&mut self.root, &mut other.root);
+            mem::swap(&mut self.size, &mut other.size);
+            // `other` now holds the old entries, which lose to the ones here
+            while let Some((key, value)) = other.pop_first() {
+                if !self.contains_key(&key) {
+                    self.insert(key, value);
+                }
+            }
+        } else {
+            while let Some((key, value)) = other.pop_first() {
+                self.insert(key, value);
+            }
+        }
+    }
+
+    /// Splits the map in two at `key`: returns a map with the entries whose
+    /// keys are greater than or equal to `key` and keeps the others. The
+    /// returned map has the same branching factor.
+    ///
+    /// Only the nodes on the search path for `key` are cut, O(log n) of
+    /// them, and the short nodes along both cuts are refilled from their
+    /// siblings. The other entries stay in their nodes, but the entries of
+    /// the lower of the two trees are counted node by node to get the sizes.
+    pub fn split_off<Q>(&mut self, key: &Q) -> BTreeMap<K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut other = BTreeMap {
+            size: 0,
+            branching_factor: self.branching_factor,
+            root: unsafe { split_off(self.root, key, self.branching_factor) },
+            _marker: PhantomData,
+        };
+        self.fix_edge(|node| node.len);
+        other.fix_edge(|_| 0);
+        if self.height() < other.height() {
+            let size = unsafe { count_entries(self.root) };
+            other.size = self.size - size;
+            self.size = size;
+        } else {
+            other.size = unsafe { count_entries(other.root) };
+            self.size -= other.size;
+        }
+        other
+    }
+
+    /// Checks the structure of the tree: the number of entries per node,
+    /// that all leaves are at the same depth, the key order and the size.
+    /// Meant for tests; takes O(n).
+    pub fn check_invariants(&self) -> Result<()> {
+        let root = unsafe { &*self.root };
+        // the root may run short of entries, but an inner root needs one
+        let min_len = if root.is_leaf() { 0 } else { 1 };
+        let max_len = self.branching_factor - 1;
+        let (count, _) = unsafe { check_subtree(root, None, None, min_len, max_len)? };
+        if count != self.size {
+            return Err(Error::BrokenInvariant {
+                reason: "the size does not match the number of entries",
+            });
+        }
+        Ok(())
+    }
+
+    fn find<Q>(&self, key: &Q) -> Option<(*mut BTreeNode<K, V>, usize)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut node = self.root;
+        loop {
+            let node_ref = unsafe { &*node };
+            match node_ref.search(key) {
+                Ok(index) => return Some((node, index)),
+                Err(_) if node_ref.is_leaf() => return None,
+                Err(index) => node = node_ref.child(index),
+            }
+        }
+    }
+
+    fn range_walk<Q, R>(&self, range: R) -> Walk<K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let front = Cursor::first_after(self.root, |key: &K| match range.start_bound() {
+            Bound::Included(start) => key.borrow() < start,
+            Bound::Excluded(start) => key.borrow() <= start,
+            Bound::Unbounded => false,
+        });
+        let back = Cursor::last_before(self.root, |key: &K| match range.end_bound() {
+            Bound::Included(end) => key.borrow() > end,
+            Bound::Excluded(end) => key.borrow() >= end,
+            Bound::Unbounded => false,
+        });
+        let mut walk = Walk { front, back };
+        match (walk.front.current(), walk.back.current()) {
+            (Some(front), Some(back)) if unsafe { entry(front).0 <= entry(back).0 } => {}
+            _ => walk.finish(),
+        }
+        walk
+    }
+}
+
+impl<K, V> Drop for BTreeMap<K, V> {
+    fn drop(&mut self) {
+        unsafe { free(self.root) };
+    }
+}
+
+impl<K, V> IntoIterator for BTreeMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = BTreeMapIntoIterator<K, V>;
+
+    fn into_iter(self) -> BTreeMapIntoIterator<K, V> {
+        BTreeMapIntoIterator { map: self }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a BTreeMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = BTreeMapIterator<'a, K, V>;
+
+    fn into_iter(self) -> BTreeMapIterator<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut BTreeMap<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = BTreeMapIteratorMut<'a, K, V>;
+
+    fn into_iter(self) -> BTreeMapIteratorMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+/// `map[&key]` searches one node per level like [`BTreeMap::get`] and
+/// panics if `key` is in none of them.
+impl<K, Q, V> Index<&Q> for BTreeMap<K, V>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("Key does not exist in this map")
+    }
+}
+
+/// Clones keep the shape of the tree, so no key is compared.
+impl<K, V> Clone for BTreeMap<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        BTreeMap {
+            size: self.size,
+            branching_factor: self.branching_factor,
+            root: unsafe { clone_subtree(self.root) },
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V> fmt::Debug for BTreeMap<K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Maps are equal if they hold the same entries, whatever their branching
+/// factors.
+impl<K, V> PartialEq for BTreeMap<K, V>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &BTreeMap<K, V>) -> bool {
+        self.size == other.size && self.iter().eq(other.iter())
+    }
+}
+
+impl<K, V> Eq for BTreeMap<K, V>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<K, V> PartialOrd for BTreeMap<K, V>
+where
+    K: PartialOrd,
+    V: PartialOrd,
+{
+    fn partial_cmp(&self, other: &BTreeMap<K, V>) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<K, V> Ord for BTreeMap<K, V>
+where
+    K: Ord,
+    V: Ord,
+{
+    fn cmp(&self, other: &BTreeMap<K, V>) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<K, V> Hash for BTreeMap<K, V>
+where
+    K: Hash,
+    V: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        self.iter().for_each(|entry| entry.hash(state));
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for BTreeMap<K, V>
+where
+    K: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = BTreeMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V> Extend<(K, V)> for BTreeMap<K, V>
+where
+    K: Ord,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a, K, V> Extend<(&'a K, &'a V)> for BTreeMap<K, V>
+where
+    K: Ord + Copy + 'a,
+    V: Copy + 'a,
+{
+    fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().map(|(&key, &value)| (key, value)))
+    }
+}
@@ -0,0 +1,504 @@
+use crate::array::Array;
+use crate::error::{Error, Result};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::{Chain, FromIterator, FusedIterator};
+use std::ops::{Index, IndexMut};
+use std::slice;
+
+/// Double-ended queue stored in a circular [`Array`].
+///
+/// Items can be pushed and popped at both ends in amortized O(1). The items
+/// start at `head` and wrap around to the start of the array, so they are
+/// not always contiguous; see [`Deque::as_slices`] and
+/// [`Deque::make_contiguous`].
+pub struct Deque<T> {
+    head: usize,
+    size: usize,
+    array: Array<T>,
+}
+
+/// Iterator over references to the items of a [`Deque`], front to back.
+pub struct DequeIterator<'a, T> {
+    items: Chain<slice::Iter<'a, T>, slice::Iter<'a, T>>,
+}
+
+impl<'a, T> Iterator for DequeIterator<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        self.items.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for DequeIterator<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.items.next_back()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for DequeIterator<'a, T> {}
+
+impl<'a, T> FusedIterator for DequeIterator<'a, T> {}
+
+/// Iterator over mutable references to the items of a [`Deque`].
+pub struct DequeIteratorMut<'a, T> {
+    items: Chain<slice::IterMut<'a, T>, slice::IterMut<'a, T>>,
+}
+
+impl<'a, T> Iterator for DequeIteratorMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<&'a mut T> {
+        self.items.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for DequeIteratorMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        self.items.next_back()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for DequeIteratorMut<'a, T> {}
+
+impl<'a, T> FusedIterator for DequeIteratorMut<'a, T> {}
+
+/// Iterator moving the items out of a [`Deque`].
+pub struct DequeIntoIterator<T> {
+    deque: Deque<T>,
+}
+
+impl<T> Iterator for DequeIntoIterator<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.deque.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.deque.size, Some(self.deque.size))
+    }
+}
+
+impl<T> DoubleEndedIterator for DequeIntoIterator<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.deque.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for DequeIntoIterator<T> {}
+
+impl<T> FusedIterator for DequeIntoIterator<T> {}
+
+impl<T> Default for Deque<T> {
+    fn default() -> Self {
+        Deque::new()
+    }
+}
+
+impl<T> Deque<T> {
+    pub fn new() -> Deque<T> {
+        Deque::with_capacity(4)
+    }
+
+    /// Creates an empty deque with room for `capacity` items.
+    pub fn with_capacity(capacity: usize) -> Deque<T> {
+        Deque {
+            head: 0,
+            size: 0,
+            array: Array::new(capacity),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Number of items the deque can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.array.size()
+    }
+
+    /// Array slot holding the item at `index`.
+    fn slot(&self, index: usize) -> usize {
+        let slot = self.head + index;
+        if slot >= self.array.size() {
+            slot - self.array.size()
+        } else {
+            slot
+        }
+    }
+
+    fn check_index(&self, index: usize) -> Result<()> {
+        if index < self.size {
+            Ok(())
+        } else {
+            Err(Error::IndexOutOfBounds {
+                index,
+                len: self.size,
+            })
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Result<&T> {
+        self.check_index(index)?;
+        self.array.get(self.slot(index))
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Result<&mut T> {
+        self.check_index(index)?;
+        let slot = self.slot(index);
+        self.array.get_mut(slot)
+    }
+
+    pub fn front(&self) -> Option<&T> {
+        self.get(0).ok()
+    }
+
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0).ok()
+    }
+
+    pub fn back(&self) -> Option<&T> {
+        let last = self.size.checked_sub(1)?;
+        self.get(last).ok()
+    }
+
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        let last = self.size.checked_sub(1)?;
+        self.get_mut(last).ok()
+    }
+
+    pub fn push_back(&mut self, item: T) {
+        self.reserve(1);
+        let slot = self.slot(self.size);
+        let _ = self.array.set(slot, item);
+        self.size += 1;
+    }
+
+    pub fn push_front(&mut self, item: T) {
+        self.reserve(1);
+        self.head = self.slot(self.array.size() - 1);
+        let _ = self.array.set(self.head, item);
+        self.size += 1;
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.size == 0 {
+            return None;
+        }
+        self.size -= 1;
+        self.array.take(self.slot(self.size)).ok()
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.size == 0 {
+            return None;
+        }
+        let item = self.array.take(self.head).ok();
+        self.head = self.slot(1);
+        self.size -= 1;
+        item
+    }
+
+    pub fn swap(&mut self, index0: usize, index1: usize) -> Result<()> {
+        self.check_index(index0)?;
+        self.check_index(index1)?;
+        self.array.swap(self.slot(index0), self.slot(index1))
+    }
+
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.size = 0;
+        self.array = Array::new(4);
+    }
+
+    /// Makes room for at least `additional` more items, doubling the
+    /// capacity as often as needed.
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self.size + additional;
+        let old_capacity = self.array.size();
+        if required <= old_capacity {
+            return;
+        }
+        let mut capacity = old_capacity.max(4);
+        while capacity < required {
+            capacity *= 2;
+        }
+        self.array.resize(capacity);
+        if self.head + self.size > old_capacity {
+            // the items wrapped around the old end; move the shorter part so
+            // they wrap around the new end instead
+            let wrapped = self.head + self.size - old_capacity;
+            let front = old_capacity - self.head;
+            if wrapped <= capacity - old_capacity && wrapped <= front {
+                let _ = self.array.shift(0, old_capacity, wrapped);
+            } else {
+                let _ = self.array.shift(self.head, capacity - front, front);
+                self.head = capacity - front;
+            }
+        }
+    }
+
+    /// Reduces the capacity to the number of items.
+    pub fn shrink_to_fit(&mut self) {
+        self.make_contiguous();
+        let _ = self.array.shift(self.head, 0, self.size);
+        self.head = 0;
+        self.array.resize(self.size);
+    }
+
+    /// Rotates the deque `count` places to the left, so the item at `count`
+    /// becomes the first one. Moves `min(count, size - count)` items.
+    pub fn rotate_left(&mut self, count: usize) -> Result<()> {
+        if count > self.size {
+            return Err(Error::IndexOutOfBounds {
+                index: count,
+                len: self.size,
+            });
+        }
+        if count <= self.size - count {
+            for _ in 0..count {
+                self.move_front_to_back();
+            }
+        } else {
+            for _ in count..self.size {
+                self.move_back_to_front();
+            }
+        }
+        Ok(())
+    }
+
+    /// Rotates the deque `count` places to the right, so the item at
+    /// `size - count` becomes the first one.
+    pub fn rotate_right(&mut self, count: usize) -> Result<()> {
+        if count > self.size {
+            return Err(Error::IndexOutOfBounds {
+                index: count,
+                len: self.size,
+            });
+        }
+        self.rotate_left(self.size - count)
+    }
+
+    fn move_front_to_back(&mut self) {
+        let back = self.slot(self.size);
+        if back != self.head {
+            let _ = self.array.shift(self.head, back, 1);
+        }
+        self.head = self.slot(1);
+    }
+
+    fn move_back_to_front(&mut self) {
+        let front = self.slot(self.array.size() - 1);
+        let back = self.slot(self.size - 1);
+        if back != front {
+            let _ = self.array.shift(back, front, 1);
+        }
+        self.head = front;
+    }
+
+    /// The items as two slices: from the front up to the end of the array,
+    /// and the rest which wrapped around to its start.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (first, second) = self.slice_lengths();
+        let items = self.array.as_ptr();
+        // both ranges only hold items
+        unsafe {
+            (
+                slice::from_raw_parts(items.add(self.head), first),
+                slice::from_raw_parts(items, second),
+            )
+        }
+    }
+
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (first, second) = self.slice_lengths();
+        let head = self.head;
+        let items = self.array.as_mut_ptr();
+        // the ranges are disjoint because `second <= head`
+        unsafe {
+            (
+                slice::from_raw_parts_mut(items.add(head), first),
+                slice::from_raw_parts_mut(items, second),
+            )
+        }
+    }
+
+    fn slice_lengths(&self) -> (usize, usize) {
+        let first = self.size.min(self.array.size() - self.head);
+        (first, self.size - first)
+    }
+
+    /// Moves the items so they are stored in one piece, and returns them
+    /// as a slice.
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        let (first, second) = self.slice_lengths();
+        if second > 0 {
+            // put the front part right after the wrapped part, then rotate
+            // the now fully initialized range into order
+            let _ = self.array.shift(self.head, second, first);
+            self.head = 0;
+            let items = unsafe { slice::from_raw_parts_mut(self.array.as_mut_ptr(), self.size) };
+            items.rotate_left(second);
+        }
+        self.as_mut_slices().0
+    }
+
+    pub fn iter(&self) -> DequeIterator<'_, T> {
+        let (first, second) = self.as_slices();
+        DequeIterator {
+            items: first.iter().chain(second.iter()),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> DequeIteratorMut<'_, T> {
+        let (first, second) = self.as_mut_slices();
+        DequeIteratorMut {
+            items: first.iter_mut().chain(second.iter_mut()),
+        }
+    }
+}
+
+impl<T> IntoIterator for Deque<T> {
+    type Item = T;
+    type IntoIter = DequeIntoIterator<T>;
+
+    fn into_iter(self) -> DequeIntoIterator<T> {
+        DequeIntoIterator { deque: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Deque<T> {
+    type Item = &'a T;
+    type IntoIter = DequeIterator<'a, T>;
+
+    fn into_iter(self) -> DequeIterator<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Deque<T> {
+    type Item = &'a mut T;
+    type IntoIter = DequeIteratorMut<'a, T>;
+
+    fn into_iter(self) -> DequeIteratorMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> Index<usize> for Deque<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        match self.get(index) {
+            Ok(item) => item,
+            Err(error) => panic!("{}", error),
+        }
+    }
+}
+
+impl<T> IndexMut<usize> for Deque<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        match self.get_mut(index) {
+            Ok(item) => item,
+            Err(error) => panic!("{}", error),
+        }
+    }
+}
+
+impl<T> Clone for Deque<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> fmt::Debug for Deque<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> PartialEq for Deque<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Deque<T>) -> bool {
+        self.size == other.size && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> Eq for Deque<T> where T: Eq {}
+
+impl<T> PartialOrd for Deque<T>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &Deque<T>) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T> Ord for Deque<T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &Deque<T>) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+/// Hashes the size and then the items, wherever the deque wraps around.
+impl<T> Hash for Deque<T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        self.iter().for_each(|item| item.hash(state));
+    }
+}
+
+impl<T> FromIterator<T> for Deque<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut deque = Deque::new();
+        deque.extend(iter);
+        deque
+    }
+}
+
+impl<T> Extend<T> for Deque<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for item in iter {
+            self.push_back(item);
+        }
+    }
+}
+
+impl<'a, T> Extend<&'a T> for Deque<T>
+where
+    T: Copy + 'a,
+{
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied())
+    }
+}
@@ -0,0 +1,46 @@
+use std::fmt;
+
+/// Errors reported by the containers in this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// `index` is not smaller than the container length `len`.
+    IndexOutOfBounds { index: usize, len: usize },
+    /// The array slot at `index` does not hold a value.
+    Uninitialized { index: usize },
+    /// The range `from..to` is reversed.
+    InvalidRange { from: usize, to: usize },
+    /// `required` slots were needed but only `capacity` are available.
+    CapacityExceeded { capacity: usize, required: usize },
+    /// The container holds no items.
+    Empty,
+    /// The requested item is not in the container.
+    NotFound,
+    /// A consistency check found the container's internal structure broken.
+    BrokenInvariant { reason: &'static str },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IndexOutOfBounds { index, len } => {
+                write!(f, "Index out of bounds! Index {}, length {}", index, len)
+            }
+            Error::Uninitialized { index } => write!(f, "Slot {} is not initialized", index),
+            Error::InvalidRange { from, to } => {
+                write!(f, "Invalid range! From {} is greater than to {}", from, to)
+            }
+            Error::CapacityExceeded { capacity, required } => write!(
+                f,
+                "Capacity exceeded! Capacity {}, required {}",
+                capacity, required
+            ),
+            Error::Empty => write!(f, "Container is empty"),
+            Error::NotFound => write!(f, "Item does not exist in this container"),
+            Error::BrokenInvariant { reason } => write!(f, "Broken invariant! {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,183 @@
+use std::fmt;
+use std::sync::Arc;
+
+/// Decides how an [`ArrayList`](crate::ArrayList) grows when it runs out of
+/// room and how it gives memory back when items are removed.
+///
+/// The default policy starts with room for 4 items, doubles the capacity
+/// when the list is full and halves it once at most a quarter is in use.
+///
+/// ```
+/// use data_structures::{ArrayList, GrowthPolicy};
+///
+/// let policy = GrowthPolicy::increment(64).with_initial_capacity(64).never_shrink();
+/// let mut list = ArrayList::with_policy(policy);
+/// list.extend(0..100);
+/// assert_eq!(list.capacity(), 128);
+/// list.truncate(0);
+/// assert_eq!(list.capacity(), 128);
+/// ```
+#[derive(Clone)]
+pub struct GrowthPolicy {
+    strategy: Strategy,
+    initial_capacity: usize,
+    shrink: bool,
+}
+
+#[derive(Clone)]
+enum Strategy {
+    /// Multiplies the capacity by `numerator / denominator`.
+    Factor {
+        numerator: usize,
+        denominator: usize,
+    },
+    /// Adds a fixed number of slots.
+    Increment(usize),
+    /// Leaves the decision to the user, see [`GrowthPolicy::custom`].
+    Custom(Arc<dyn Fn(usize, usize) -> usize + Send + Sync>),
+}
+
+impl Default for GrowthPolicy {
+    fn default() -> Self {
+        GrowthPolicy::doubling()
+    }
+}
+
+impl GrowthPolicy {
+    fn new(strategy: Strategy) -> GrowthPolicy {
+        GrowthPolicy {
+            strategy,
+            initial_capacity: 4,
+            shrink: true,
+        }
+    }
+
+    /// Doubles the capacity when growing and halves it once at most a
+    /// quarter is in use.
+    pub fn doubling() -> GrowthPolicy {
+        GrowthPolicy::new(Strategy::Factor {
+            numerator: 2,
+            denominator: 1,
+        })
+    }
+
+    /// Grows the capacity by half. Wastes less memory than doubling at the
+    /// cost of reallocating more often.
+    pub fn one_and_a_half() -> GrowthPolicy {
+        GrowthPolicy::new(Strategy::Factor {
+            numerator: 3,
+            denominator: 2,
+        })
+    }
+
+    /// Grows the capacity by `step` slots at a time and shrinks it by `step`
+    /// once two steps are unused. A `step` of 0 is treated as 1.
+    pub fn increment(step: usize) -> GrowthPolicy {
+        GrowthPolicy::new(Strategy::Increment(step.max(1)))
+    }
+
+    /// Lets `capacity` pick the capacity.
+    ///
+    /// It is called with the current capacity and the number of items the
+    /// list has to hold, both when the list is full and after items were
+    /// removed. A result smaller than the number of items is raised to it.
+    pub fn custom<F>(capacity: F) -> GrowthPolicy
+    where
+        F: Fn(usize, usize) -> usize + Send + Sync + 'static,
+    {
+        GrowthPolicy::new(Strategy::Custom(Arc::new(capacity)))
+    }
+
+    /// Keeps the capacity when items are removed. Only `clear` and
+    /// `shrink_to_fit` give memory back.
+    pub fn never_shrink(mut self) -> GrowthPolicy {
+        self.shrink = false;
+        self
+    }
+
+    /// Sets the capacity of new and cleared lists. Automatic shrinking does
+    /// not go below it.
+    pub fn with_initial_capacity(mut self, capacity: usize) -> GrowthPolicy {
+        self.initial_capacity = capacity;
+        self
+    }
+
+    pub fn initial_capacity(&self) -> usize {
+        self.initial_capacity
+    }
+
+    /// Capacity to grow to so that `required` items fit.
+    pub(crate) fn grown(&self, capacity: usize, required: usize) -> usize {
+        let capacity = capacity.max(self.initial_capacity);
+        if capacity >= required {
+            return capacity;
+        }
+        match &self.strategy {
+            Strategy::Factor {
+                numerator,
+                denominator,
+            } => {
+                let mut capacity = capacity;
+                while capacity < required {
+                    capacity =
+                        (capacity.saturating_mul(*numerator) / denominator).max(capacity + 1);
+                }
+                capacity
+            }
+            Strategy::Increment(step) => {
+                let steps = (required - capacity).div_ceil(*step);
+                capacity + steps * step
+            }
+            Strategy::Custom(pick) => pick(capacity, required).max(required),
+        }
+    }
+
+    /// Capacity to shrink to once only `len` items are left.
+    pub(crate) fn shrunk(&self, capacity: usize, len: usize) -> usize {
+        if !self.shrink {
+            return capacity;
+        }
+        match &self.strategy {
+            Strategy::Factor {
+                numerator,
+                denominator,
+            } => {
+                // shrink once the items would still fit after shrinking twice
+                let mut capacity = capacity;
+                loop {
+                    let smaller = (capacity * denominator / numerator).max(self.initial_capacity);
+                    if smaller >= capacity || len > smaller * denominator / numerator {
+                        return capacity;
+                    }
+                    capacity = smaller;
+                }
+            }
+            Strategy::Increment(step) => {
+                // keep less than two unused steps
+                if capacity < len + 2 * step || capacity < self.initial_capacity + step {
+                    return capacity;
+                }
+                let steps =
+                    ((capacity - len) / step - 1).min((capacity - self.initial_capacity) / step);
+                capacity - steps * step
+            }
+            Strategy::Custom(pick) => pick(capacity, len).max(len),
+        }
+    }
+}
+
+impl fmt::Debug for GrowthPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let strategy = match &self.strategy {
+            Strategy::Factor { numerator: 2, .. } => "doubling".to_string(),
+            Strategy::Factor { .. } => "one_and_a_half".to_string(),
+            Strategy::Increment(step) => format!("increment({})", step),
+            Strategy::Custom(_) => "custom".to_string(),
+        };
+        f.debug_struct("GrowthPolicy")
+            .field("strategy", &format_args!("{}", strategy))
+            .field("initial_capacity", &self.initial_capacity)
+            .field("shrink", &self.shrink)
+            .finish()
+    }
+}
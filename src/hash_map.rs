@@ -0,0 +1,748 @@
+//! Hash map with open addressing.
+//!
+//! The buckets live in an [`Array`] whose empty slots are free buckets.
+//! Collisions are resolved by Robin Hood linear probing: an entry that is
+//! further away from its ideal bucket takes the place of one that is closer,
+//! which keeps probe sequences short. Removal shifts the following entries
+//! back, so the table never holds tombstones.
+
+use crate::array::{Array, ArrayIntoIterator, ArrayIterator, ArrayIteratorMut};
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FromIterator;
+use std::mem;
+use std::ops::Index;
+
+/// The table grows once more than `MAX_LOAD_NUMERATOR / MAX_LOAD_DENOMINATOR`
+/// of its buckets are used.
+const MAX_LOAD_NUMERATOR: usize = 7;
+const MAX_LOAD_DENOMINATOR: usize = 8;
+
+/// Number of buckets of the first table.
+const MIN_BUCKETS: usize = 8;
+
+struct Bucket<K, V> {
+    hash: u64,
+    key: K,
+    value: V,
+}
+
+/// Map from keys of type `K` to values of type `V`.
+///
+/// Keys are hashed with the [`BuildHasher`] `S`, by default std's
+/// [`RandomState`]. Like `std::collections::HashMap`, the map grows when its
+/// load factor gets too high and only gives memory back in
+/// [`HashMap::shrink_to_fit`] and [`HashMap::clear`].
+pub struct HashMap<K, V, S = RandomState> {
+    size: usize,
+    buckets: Array<Bucket<K, V>>,
+    hasher: S,
+}
+
+/// Iterator over the entries of a [`HashMap`], in no particular order.
+pub struct HashMapIterator<'a, K, V> {
+    remaining: usize,
+    buckets: ArrayIterator<'a, Bucket<K, V>>,
+}
+
+impl<'a, K, V> Iterator for HashMapIterator<'a, K, V> {
+    type Item = (&'a K, &'a V);
+    fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        let bucket = self.buckets.next()?;
+        self.remaining -= 1;
+        Some((&bucket.key, &bucket.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for HashMapIterator<'a, K, V> {}
+
+/// Iterator over the entries of a [`HashMap`] with mutable values.
+pub struct HashMapIteratorMut<'a, K, V> {
+    remaining: usize,
+    buckets: ArrayIteratorMut<'a, Bucket<K, V>>,
+}
+
+impl<'a, K, V> Iterator for HashMapIteratorMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+    fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
+        let bucket = self.buckets.next()?;
+        self.remaining -= 1;
+        Some((&bucket.key, &mut bucket.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for HashMapIteratorMut<'a, K, V> {}
+
+/// Iterator moving the entries out of a [`HashMap`].
+pub struct HashMapIntoIterator<K, V> {
+    remaining: usize,
+    buckets: ArrayIntoIterator<Bucket<K, V>>,
+}
+
+impl<K, V> Iterator for HashMapIntoIterator<K, V> {
+    type Item = (K, V);
+    fn next(&mut self) -> Option<(K, V)> {
+        let bucket = self.buckets.next()?;
+        self.remaining -= 1;
+        Some((bucket.key, bucket.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for HashMapIntoIterator<K, V> {}
+
+/// Iterator over the keys of a [`HashMap`].
+pub struct HashMapKeys<'a, K, V> {
+    entries: HashMapIterator<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for HashMapKeys<'a, K, V> {
+    type Item = &'a K;
+    fn next(&mut self) -> Option<&'a K> {
+        self.entries.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for HashMapKeys<'a, K, V> {}
+
+/// Iterator over the values of a [`HashMap`].
+pub struct HashMapValues<'a, K, V> {
+    entries: HashMapIterator<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for HashMapValues<'a, K, V> {
+    type Item = &'a V;
+    fn next(&mut self) -> Option<&'a V> {
+        self.entries.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for HashMapValues<'a, K, V> {}
+
+/// Iterator over mutable references to the values of a [`HashMap`].
+pub struct HashMapValuesMut<'a, K, V> {
+    entries: HashMapIteratorMut<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for HashMapValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+    fn next(&mut self) -> Option<&'a mut V> {
+        self.entries.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for HashMapValuesMut<'a, K, V> {}
+
+/// A key's place in a [`HashMap`], which may or may not hold a value.
+pub enum Entry<'a, K, V, S> {
+    Occupied(OccupiedEntry<'a, K, V, S>),
+    Vacant(VacantEntry<'a, K, V, S>),
+}
+
+/// Entry of a key that is in the map.
+pub struct OccupiedEntry<'a, K, V, S> {
+    map: &'a mut HashMap<K, V, S>,
+    index: usize,
+}
+
+/// Entry of a key that is not in the map yet.
+pub struct VacantEntry<'a, K, V, S> {
+    map: &'a mut HashMap<K, V, S>,
+    hash: u64,
+    key: K,
+}
+
+impl<K, V> HashMap<K, V, RandomState> {
+    pub fn new() -> HashMap<K, V, RandomState> {
+        HashMap::with_hasher(RandomState::new())
+    }
+
+    /// Creates an empty map that holds `capacity` entries without growing.
+    pub fn with_capacity(capacity: usize) -> HashMap<K, V, RandomState> {
+        HashMap::with_capacity_and_hasher(capacity, RandomState::new())
+    }
+}
+
+impl<K, V, S> Default for HashMap<K, V, S>
+where
+    S: Default,
+{
+    fn default() -> Self {
+        HashMap::with_hasher(S::default())
+    }
+}
+
+impl<K, V, S> HashMap<K, V, S> {
+    /// Creates an empty map hashing its keys with `hasher`. No memory is
+    /// allocated until the first insert.
+    pub fn with_hasher(hasher: S) -> HashMap<K, V, S> {
+        HashMap::with_capacity_and_hasher(0, hasher)
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> HashMap<K, V, S> {
+        HashMap {
+            size: 0,
+            buckets: Array::new(buckets_for(capacity)),
+            hasher,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Number of entries the map can hold without growing.
+    pub fn capacity(&self) -> usize {
+        self.buckets.size() / MAX_LOAD_DENOMINATOR * MAX_LOAD_NUMERATOR
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hasher
+    }
+
+    /// Removes all entries and frees the table.
+    pub fn clear(&mut self) {
+        self.size = 0;
+        self.buckets = Array::new(0);
+    }
+
+    pub fn iter(&self) -> HashMapIterator<'_, K, V> {
+        HashMapIterator {
+            remaining: self.size,
+            buckets: self.buckets.iter(),
+        }
+    }
+
+    /// Iterates over the entries, allowing to change the values.
+    pub fn iter_mut(&mut self) -> HashMapIteratorMut<'_, K, V> {
+        HashMapIteratorMut {
+            remaining: self.size,
+            buckets: self.buckets.iter_mut(),
+        }
+    }
+
+    pub fn keys(&self) -> HashMapKeys<'_, K, V> {
+        HashMapKeys {
+            entries: self.iter(),
+        }
+    }
+
+    pub fn values(&self) -> HashMapValues<'_, K, V> {
+        HashMapValues {
+            entries: self.iter(),
+        }
+    }
+
+    pub fn values_mut(&mut self) -> HashMapValuesMut<'_, K, V> {
+        HashMapValuesMut {
+            entries: self.iter_mut(),
+        }
+    }
+
+    /// Keeps only the entries for which `keep` returns `true`.
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        // start after a free bucket: removing shifts entries back, and no
+        // entry is ever shifted across a free bucket, so every entry is
+        // visited exactly once
+        let start = match (0..self.buckets.size()).find(|&i| !self.buckets.is_initialized(i)) {
+            Some(start) => start,
+            None => return,
+        };
+        let mut offset = 1;
+        while offset < self.buckets.size() {
+            let index = (start + offset) & self.mask();
+            let remove = match self.buckets.get_mut(index) {
+                Ok(bucket) => !keep(&bucket.key, &mut bucket.value),
+                Err(_) => false,
+            };
+            if remove {
+                // the next entry may have moved into `index`
+                self.remove_at(index);
+            } else {
+                offset += 1;
+            }
+        }
+    }
+
+    fn mask(&self) -> usize {
+        self.buckets.size() - 1
+    }
+
+    /// How far the entry in bucket `index` is from its ideal bucket.
+    fn distance(&self, index: usize, hash: u64) -> usize {
+        index.wrapping_sub(hash as usize) & self.mask()
+    }
+
+    /// Puts a new entry into the table, which must have a free bucket, and
+    /// returns the bucket it ended up in.
+    fn insert_new(&mut self, hash: u64, key: K, value: V) -> usize {
+        let mut carried = Bucket { hash, key, value };
+        let mut index = hash as usize & self.mask();
+        let mut distance = 0;
+        let mut placed = None;
+        loop {
+            let existing = match self.buckets.get(index) {
+                Ok(bucket) => self.distance(index, bucket.hash),
+                Err(_) => {
+                    let _ = self.buckets.set(index, carried);
+                    return placed.unwrap_or(index);
+                }
+            };
+            if existing < distance {
+                // take from the rich: the carried entry is further from home
+                carried = self.buckets.replace(index, carried).unwrap().unwrap();
+                placed.get_or_insert(index);
+                distance = existing;
+            }
+            index = (index + 1) & self.mask();
+            distance += 1;
+        }
+    }
+
+    /// Takes the entry out of bucket `index` and moves the following
+    /// entries one bucket back, closing the gap.
+    fn remove_at(&mut self, index: usize) -> (K, V) {
+        let bucket = self.buckets.take(index).unwrap();
+        let mut gap = index;
+        loop {
+            let next = (gap + 1) & self.mask();
+            match self.buckets.get(next) {
+                Ok(following) if self.distance(next, following.hash) > 0 => {
+                    let _ = self.buckets.shift(next, gap, 1);
+                    gap = next;
+                }
+                _ => break,
+            }
+        }
+        self.size -= 1;
+        (bucket.key, bucket.value)
+    }
+
+    /// Moves all entries into a table of `buckets` buckets.
+    fn rebuild(&mut self, buckets: usize) {
+        let old = mem::replace(&mut self.buckets, Array::new(buckets));
+        for bucket in old {
+            self.insert_new(bucket.hash, bucket.key, bucket.value);
+        }
+    }
+}
+
+impl<K, V, S> HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    fn hash<Q>(&self, key: &Q) -> u64
+    where
+        Q: Hash + ?Sized,
+    {
+        self.hasher.hash_one(key)
+    }
+
+    /// Bucket holding `key`, if it is in the map.
+    fn find<Q>(&self, hash: u64, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        if self.size == 0 {
+            return None;
+        }
+        let mut index = hash as usize & self.mask();
+        let mut distance = 0;
+        loop {
+            let bucket = self.buckets.get(index).ok()?;
+            if self.distance(index, bucket.hash) < distance {
+                // the key would have taken this bucket
+                return None;
+            }
+            if bucket.hash == hash && bucket.key.borrow() == key {
+                return Some(index);
+            }
+            index = (index + 1) & self.mask();
+            distance += 1;
+        }
+    }
+
+    /// Makes room for at least `additional` more entries.
+    pub fn reserve(&mut self, additional: usize) {
+        let buckets = buckets_for(self.size + additional);
+        if buckets > self.buckets.size() {
+            self.rebuild(buckets);
+        }
+    }
+
+    /// Shrinks the table as far as the load factor allows.
+    pub fn shrink_to_fit(&mut self) {
+        let buckets = buckets_for(self.size);
+        if buckets < self.buckets.size() {
+            self.rebuild(buckets);
+        }
+    }
+
+    /// Inserts `value` under `key` and returns the value it replaced.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.find(self.hash(key), key)?;
+        let bucket = self.buckets.get(index).ok()?;
+        Some((&bucket.key, &bucket.value))
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.find(self.hash(key), key)?;
+        Some(&mut self.buckets.get_mut(index).ok()?.value)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(self.hash(key), key).is_some()
+    }
+
+    /// Removes `key` and returns its value. The entries probing past its
+    /// bucket shift back by one, so no tombstone is left behind.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let index = self.find(self.hash(key), key)?;
+        Some(self.remove_at(index))
+    }
+
+    /// Gets the entry of `key` for in-place manipulation.
+    ///
+    /// Makes room for one more entry up front, so inserting into a vacant
+    /// entry never has to grow the table.
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S> {
+        self.reserve(1);
+        let hash = self.hash(&key);
+        match self.find(hash, &key) {
+            Some(index) => Entry::Occupied(OccupiedEntry { map: self, index }),
+            None => Entry::Vacant(VacantEntry {
+                map: self,
+                hash,
+                key,
+            }),
+        }
+    }
+}
+
+/// Number of buckets needed to hold `capacity` entries.
+fn buckets_for(capacity: usize) -> usize {
+    if capacity == 0 {
+        return 0;
+    }
+    let buckets = (capacity * MAX_LOAD_DENOMINATOR).div_ceil(MAX_LOAD_NUMERATOR);
+    buckets.next_power_of_two().max(MIN_BUCKETS)
+}
+
+impl<'a, K, V, S> Entry<'a, K, V, S> {
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Returns the value, inserting `default` first if the key is vacant.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut V
+    where
+        F: FnOnce() -> V,
+    {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Calls `modify` on the value if the key is occupied.
+    pub fn and_modify<F>(mut self, modify: F) -> Self
+    where
+        F: FnOnce(&mut V),
+    {
+        if let Entry::Occupied(entry) = &mut self {
+            modify(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K, V, S> OccupiedEntry<'a, K, V, S> {
+    fn bucket(&self) -> &Bucket<K, V> {
+        self.map.buckets.get(self.index).unwrap()
+    }
+
+    fn bucket_mut(&mut self) -> &mut Bucket<K, V> {
+        self.map.buckets.get_mut(self.index).unwrap()
+    }
+
+    pub fn key(&self) -> &K {
+        &self.bucket().key
+    }
+
+    pub fn get(&self) -> &V {
+        &self.bucket().value
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.bucket_mut().value
+    }
+
+    /// Turns the entry into a reference that lives as long as the map
+    /// borrow.
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.buckets.get_mut(self.index).unwrap().value
+    }
+
+    /// Replaces the value and returns the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    pub fn remove_entry(self) -> (K, V) {
+        self.map.remove_at(self.index)
+    }
+}
+
+impl<'a, K, V, S> VacantEntry<'a, K, V, S> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Inserts `value` under the entry's key and returns a reference to it.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let map = self.map;
+        let index = map.insert_new(self.hash, self.key, value);
+        map.size += 1;
+        &mut map.buckets.get_mut(index).unwrap().value
+    }
+}
+
+impl<K, V, S> IntoIterator for HashMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = HashMapIntoIterator<K, V>;
+
+    fn into_iter(self) -> HashMapIntoIterator<K, V> {
+        HashMapIntoIterator {
+            remaining: self.size,
+            buckets: self.buckets.into_iter(),
+        }
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a HashMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = HashMapIterator<'a, K, V>;
+
+    fn into_iter(self) -> HashMapIterator<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a mut HashMap<K, V, S> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = HashMapIteratorMut<'a, K, V>;
+
+    fn into_iter(self) -> HashMapIteratorMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+/// `map[&key]` probes like [`HashMap::get`] and panics once the probe
+/// reaches an empty bucket or an entry closer to home than `key` would be.
+impl<K, Q, V, S> Index<&Q> for HashMap<K, V, S>
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+    S: BuildHasher,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("Key does not exist in this map")
+    }
+}
+
+/// Clones keep the table layout, so no key is hashed again.
+impl<K, V, S> Clone for HashMap<K, V, S>
+where
+    K: Clone,
+    V: Clone,
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        let mut buckets = Array::new(self.buckets.size());
+        for index in 0..self.buckets.size() {
+            if let Ok(bucket) = self.buckets.get(index) {
+                let bucket = Bucket {
+                    hash: bucket.hash,
+                    key: bucket.key.clone(),
+                    value: bucket.value.clone(),
+                };
+                let _ = buckets.set(index, bucket);
+            }
+        }
+        HashMap {
+            size: self.size,
+            buckets,
+            hasher: self.hasher.clone(),
+        }
+    }
+}
+
+impl<K, V, S> fmt::Debug for HashMap<K, V, S>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Maps are equal if they hold the same keys with equal values.
+impl<K, V, S> PartialEq for HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    V: PartialEq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &HashMap<K, V, S>) -> bool {
+        self.size == other.size
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<K, V, S> Eq for HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    V: Eq,
+    S: BuildHasher,
+{
+}
+
+impl<K, V, S> FromIterator<(K, V)> for HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = HashMap::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V, S> Extend<(K, V)> for HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a, K, V, S> Extend<(&'a K, &'a V)> for HashMap<K, V, S>
+where
+    K: Hash + Eq + Copy + 'a,
+    V: Copy + 'a,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().map(|(&key, &value)| (key, value)))
+    }
+}
@@ -0,0 +1,425 @@
+//! Hash set built on [`HashMap`].
+//!
+//! The set is a map whose values are `()`, so it shares the map's table and
+//! growth rules. The set operations return lazy iterators which look items
+//! up in the other set as they go and never allocate.
+
+use crate::hash_map::{HashMap, HashMapIntoIterator, HashMapKeys};
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::{Chain, FromIterator};
+
+/// Set of distinct items of type `T`, hashed with the [`BuildHasher`] `S`.
+pub struct HashSet<T, S = RandomState> {
+    map: HashMap<T, (), S>,
+}
+
+/// Iterator over the items of a [`HashSet`], in no particular order.
+pub struct HashSetIterator<'a, T> {
+    keys: HashMapKeys<'a, T, ()>,
+}
+
+impl<'a, T> Iterator for HashSetIterator<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        self.keys.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for HashSetIterator<'a, T> {}
+
+/// Iterator moving the items out of a [`HashSet`].
+pub struct HashSetIntoIterator<T> {
+    entries: HashMapIntoIterator<T, ()>,
+}
+
+impl<T> Iterator for HashSetIntoIterator<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.entries.next().map(|(item, _)| item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for HashSetIntoIterator<T> {}
+
+/// Items of one set that are not in another, see [`HashSet::difference`].
+pub struct HashSetDifference<'a, T, S> {
+    items: HashSetIterator<'a, T>,
+    other: &'a HashSet<T, S>,
+}
+
+impl<'a, T, S> Iterator for HashSetDifference<'a, T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        let other = self.other;
+        self.items.find(|item| !other.contains(*item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.items.size_hint().1)
+    }
+}
+
+/// Items that are in both sets, see [`HashSet::intersection`].
+pub struct HashSetIntersection<'a, T, S> {
+    /// Items of the smaller set.
+    items: HashSetIterator<'a, T>,
+    other: &'a HashSet<T, S>,
+}
+
+impl<'a, T, S> Iterator for HashSetIntersection<'a, T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        let other = self.other;
+        self.items.find(|item| other.contains(*item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.items.size_hint().1)
+    }
+}
+
+/// Items that are in either set, see [`HashSet::union`].
+pub struct HashSetUnion<'a, T, S> {
+    items: Chain<HashSetIterator<'a, T>, HashSetDifference<'a, T, S>>,
+}
+
+impl<'a, T, S> Iterator for HashSetUnion<'a, T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        self.items.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+/// Items that are in exactly one of the sets, see
+/// [`HashSet::symmetric_difference`].
+pub struct HashSetSymmetricDifference<'a, T, S> {
+    items: Chain<HashSetDifference<'a, T, S>, HashSetDifference<'a, T, S>>,
+}
+
+impl<'a, T, S> Iterator for HashSetSymmetricDifference<'a, T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        self.items.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+impl<T> HashSet<T, RandomState> {
+    pub fn new() -> HashSet<T, RandomState> {
+        HashSet {
+            map: HashMap::new(),
+        }
+    }
+
+    /// Creates an empty set that holds `capacity` items without growing.
+    pub fn with_capacity(capacity: usize) -> HashSet<T, RandomState> {
+        HashSet {
+            map: HashMap::with_capacity(capacity),
+        }
+    }
+}
+
+impl<T, S> Default for HashSet<T, S>
+where
+    S: Default,
+{
+    fn default() -> Self {
+        HashSet {
+            map: HashMap::default(),
+        }
+    }
+}
+
+impl<T, S> HashSet<T, S> {
+    /// Creates an empty set hashing its items with `hasher`.
+    pub fn with_hasher(hasher: S) -> HashSet<T, S> {
+        HashSet {
+            map: HashMap::with_hasher(hasher),
+        }
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> HashSet<T, S> {
+        HashSet {
+            map: HashMap::with_capacity_and_hasher(capacity, hasher),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.map.size()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Number of items the set can hold without growing.
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    pub fn hasher(&self) -> &S {
+        self.map.hasher()
+    }
+
+    /// Removes all items and frees the table.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    pub fn iter(&self) -> HashSetIterator<'_, T> {
+        HashSetIterator {
+            keys: self.map.keys(),
+        }
+    }
+
+    /// Keeps only the items for which `keep` returns `true`.
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.map.retain(|item, _| keep(item));
+    }
+}
+
+impl<T, S> HashSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Makes room for at least `additional` more items.
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+    }
+
+    /// Shrinks the table as far as the load factor allows.
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit();
+    }
+
+    /// Adds `item` to the set. Returns `false` and leaves the set unchanged
+    /// if an equal item is already in it.
+    pub fn insert(&mut self, item: T) -> bool {
+        self.map.insert(item, ()).is_none()
+    }
+
+    /// Removes `item` from the set and returns whether it was in it.
+    pub fn remove<Q>(&mut self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove(item).is_some()
+    }
+
+    /// Removes `item` from the set and returns the stored item.
+    pub fn take<Q>(&mut self, item: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.remove_entry(item).map(|(item, _)| item)
+    }
+
+    pub fn contains<Q>(&self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(item)
+    }
+
+    /// Returns the stored item equal to `item`.
+    pub fn get<Q>(&self, item: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get_key_value(item).map(|(item, _)| item)
+    }
+
+    /// Iterates over the items that are in `self` or `other`, each once.
+    pub fn union<'a>(&'a self, other: &'a HashSet<T, S>) -> HashSetUnion<'a, T, S> {
+        // walk the bigger set in full and the smaller one looking for extras
+        let (bigger, smaller) = if self.size() >= other.size() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        HashSetUnion {
+            items: bigger.iter().chain(smaller.difference(bigger)),
+        }
+    }
+
+    /// Iterates over the items that are in both `self` and `other`.
+    pub fn intersection<'a>(&'a self, other: &'a HashSet<T, S>) -> HashSetIntersection<'a, T, S> {
+        // look up the items of the smaller set in the bigger one
+        let (smaller, bigger) = if self.size() <= other.size() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        HashSetIntersection {
+            items: smaller.iter(),
+            other: bigger,
+        }
+    }
+
+    /// Iterates over the items of `self` that are not in `other`.
+    pub fn difference<'a>(&'a self, other: &'a HashSet<T, S>) -> HashSetDifference<'a, T, S> {
+        HashSetDifference {
+            items: self.iter(),
+            other,
+        }
+    }
+
+    /// Iterates over the items that are in exactly one of `self` and
+    /// `other`.
+    pub fn symmetric_difference<'a>(
+        &'a self,
+        other: &'a HashSet<T, S>,
+    ) -> HashSetSymmetricDifference<'a, T, S> {
+        HashSetSymmetricDifference {
+            items: self.difference(other).chain(other.difference(self)),
+        }
+    }
+
+    /// Returns `true` if every item of `self` is in `other`.
+    pub fn is_subset(&self, other: &HashSet<T, S>) -> bool {
+        self.size() <= other.size() && self.iter().all(|item| other.contains(item))
+    }
+
+    /// Returns `true` if every item of `other` is in `self`.
+    pub fn is_superset(&self, other: &HashSet<T, S>) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Returns `true` if no item is in both `self` and `other`.
+    pub fn is_disjoint(&self, other: &HashSet<T, S>) -> bool {
+        self.intersection(other).next().is_none()
+    }
+}
+
+impl<T, S> IntoIterator for HashSet<T, S> {
+    type Item = T;
+    type IntoIter = HashSetIntoIterator<T>;
+
+    fn into_iter(self) -> HashSetIntoIterator<T> {
+        HashSetIntoIterator {
+            entries: self.map.into_iter(),
+        }
+    }
+}
+
+impl<'a, T, S> IntoIterator for &'a HashSet<T, S> {
+    type Item = &'a T;
+    type IntoIter = HashSetIterator<'a, T>;
+
+    fn into_iter(self) -> HashSetIterator<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T, S> Clone for HashSet<T, S>
+where
+    T: Clone,
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        HashSet {
+            map: self.map.clone(),
+        }
+    }
+}
+
+impl<T, S> fmt::Debug for HashSet<T, S>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Sets are equal if they hold the same items.
+impl<T, S> PartialEq for HashSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &HashSet<T, S>) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<T, S> Eq for HashSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+}
+
+impl<T, S> FromIterator<T> for HashSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = HashSet::default();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T, S> Extend<T> for HashSet<T, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.map.extend(iter.into_iter().map(|item| (item, ())));
+    }
+}
+
+impl<'a, T, S> Extend<&'a T> for HashSet<T, S>
+where
+    T: Hash + Eq + Copy + 'a,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied())
+    }
+}
@@ -1,22 +1,186 @@
-use crate::array_list::ArrayList;
+use crate::array_list::{ArrayList, ArrayListIntoIterator, ArrayListIterator};
+use crate::error::{Error, Result};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
 
-#[derive(Copy, Clone, PartialEq)]
-pub struct HeapItem<T> {
-    priority: i64,
+/// Decides which of two priorities leaves the heap first.
+pub trait Comparator<P> {
+    /// Returns `Ordering::Less` if `a` should be popped before `b`.
+    fn compare(&self, a: &P, b: &P) -> Ordering;
+}
+
+/// Pops the smallest priority first.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct MinOrder;
+
+/// Pops the largest priority first.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct MaxOrder;
+
+impl<P> Comparator<P> for MinOrder
+where
+    P: Ord,
+{
+    fn compare(&self, a: &P, b: &P) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+impl<P> Comparator<P> for MaxOrder
+where
+    P: Ord,
+{
+    fn compare(&self, a: &P, b: &P) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+impl<P, F> Comparator<P> for F
+where
+    F: Fn(&P, &P) -> Ordering,
+{
+    fn compare(&self, a: &P, b: &P) -> Ordering {
+        self(a, b)
+    }
+}
+
+/// An item of a [`Heap`] with its priority and the slot of its handle.
+#[derive(Clone)]
+pub(crate) struct HeapItem<P, T> {
+    priority: P,
     data: T,
+    slot: usize,
+}
+
+/// Stable reference to an item pushed into a [`Heap`].
+///
+/// A handle stays valid until its item leaves the heap; afterwards every
+/// operation taking it reports [`Error::NotFound`]. Handles must only be
+/// used with the heap that returned them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Handle {
+    slot: usize,
+    generation: u64,
+}
+
+/// Position of a handle's item in the heap, reused once the item is gone.
+#[derive(Copy, Clone)]
+struct Slot {
+    position: Option<usize>,
+    generation: u64,
+}
+
+/// Binary heap ordered by priorities of type `P`.
+///
+/// The comparator `C` decides the order; by default the lowest priority is
+/// popped first. A closure `Fn(&P, &P) -> Ordering` can be used as well,
+/// see [`Heap::with_comparator`].
+///
+/// `push` returns a [`Handle`] which can later change the item's priority
+/// or remove it, both in O(log n).
+pub struct Heap<P, T, C = MinOrder> {
+    list: ArrayList<HeapItem<P, T>>,
+    slots: ArrayList<Slot>,
+    free_slots: ArrayList<usize>,
+    comparator: C,
+}
+
+pub type MinHeap<P, T> = Heap<P, T, MinOrder>;
+pub type MaxHeap<P, T> = Heap<P, T, MaxOrder>;
+
+/// Iterator over the priorities and items of a [`Heap`], in no particular order.
+pub struct HeapIterator<'a, P, T> {
+    items: ArrayListIterator<'a, HeapItem<P, T>>,
+}
+
+impl<'a, P, T> Iterator for HeapIterator<'a, P, T> {
+    type Item = (&'a P, &'a T);
+    fn next(&mut self) -> Option<(&'a P, &'a T)> {
+        self.items.next().map(|item| (&item.priority, &item.data))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+/// Iterator moving the priorities and items out of a [`Heap`], in no
+/// particular order.
+pub struct HeapIntoIterator<P, T> {
+    items: ArrayListIntoIterator<HeapItem<P, T>>,
+}
+
+impl<P, T> Iterator for HeapIntoIterator<P, T> {
+    type Item = (P, T);
+    fn next(&mut self) -> Option<(P, T)> {
+        self.items.next().map(|item| (item.priority, item.data))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
 }
 
-pub struct Heap<T> {
-    list: ArrayList<HeapItem<T>>,
+impl<P, T, C> Default for Heap<P, T, C>
+where
+    C: Comparator<P> + Default,
+{
+    fn default() -> Self {
+        Heap::new()
+    }
+}
+
+impl<P, T, C> Heap<P, T, C>
+where
+    C: Comparator<P> + Default,
+{
+    pub fn new() -> Heap<P, T, C> {
+        Heap::with_comparator(C::default())
+    }
+}
+
+impl<P, T, C> Heap<P, T, C> {
+    /// Iterates over all priorities and items without popping them. The
+    /// order is the heap's internal layout, not priority order.
+    pub fn iter(&self) -> HeapIterator<'_, P, T> {
+        HeapIterator {
+            items: self.list.iter(),
+        }
+    }
+}
+
+impl<P, T, C> IntoIterator for Heap<P, T, C> {
+    type Item = (P, T);
+    type IntoIter = HeapIntoIterator<P, T>;
+
+    fn into_iter(self) -> HeapIntoIterator<P, T> {
+        HeapIntoIterator {
+            items: self.list.into_iter(),
+        }
+    }
+}
+
+impl<'a, P, T, C> IntoIterator for &'a Heap<P, T, C> {
+    type Item = (&'a P, &'a T);
+    type IntoIter = HeapIterator<'a, P, T>;
+
+    fn into_iter(self) -> HeapIterator<'a, P, T> {
+        self.iter()
+    }
 }
 
-impl<T> Heap<T>
+impl<P, T, C> Heap<P, T, C>
 where
-    T: Copy + PartialEq,
+    C: Comparator<P>,
 {
-    pub fn new() -> Heap<T> {
+    pub fn with_comparator(comparator: C) -> Heap<P, T, C> {
         Heap {
             list: ArrayList::new(),
+            slots: ArrayList::new(),
+            free_slots: ArrayList::new(),
+            comparator,
         }
     }
 
@@ -24,75 +188,171 @@ where
         self.list.size()
     }
 
-    pub fn push(&mut self, priority: i64, data: T) {
+    pub fn push(&mut self, priority: P, data: T) -> Handle {
+        let position = self.list.size();
+        let handle = self.acquire_slot(position);
         let item = HeapItem {
-            priority: priority,
-            data: data,
+            priority,
+            data,
+            slot: handle.slot,
         };
         self.list.add(item);
-        let mut current_index = self.list.size() - 1;
-        let mut parent_index = self.parent_index(current_index);
-        loop {
-            let current_item = self.list.get(current_index).unwrap(); // we know its not null
-            let parent = self.list.get(parent_index).unwrap(); //same lol
-            if current_item.priority >= parent.priority {
-                break;
-            }
-            self.list.swap(current_index, parent_index);
-            current_index = parent_index;
-            parent_index = self.parent_index(current_index);
-        }
+        self.sift_up(position);
+        handle
     }
 
-    pub fn pop(&mut self) -> Option<T> {
+    pub fn pop(&mut self) -> Result<T> {
         if self.list.size() == 0 {
-            println!("Empty heap!");
-            None
+            Err(Error::Empty)
         } else {
-            let out = self.list.get(0);
-            if self.list.size() > 1 {
-                let last = self.list.get(self.list.size() - 1);
-                self.list.set(0, last.unwrap());
-                let mut current_index = 0;
-                let mut son_index = self.greater_son_index(current_index);
-                loop {
-                    let current_item = self.list.get(current_index).unwrap();
-                    let son = self.list.get(son_index).unwrap();
-                    if son_index >= self.list.size() || current_item.priority <= son.priority {
-                        break;
-                    }
-                    self.list.swap(current_index, son_index);
-                    current_index = son_index;
-                    son_index = self.greater_son_index(current_index);
-                }
-            }
-            Some(out.unwrap().data)
+            self.remove_at(0)
         }
     }
 
-    pub fn peek(&mut self) -> Option<T> {
+    pub fn peek(&self) -> Result<&T> {
         if self.list.size() == 0 {
-            println!("Empty heap!");
-            None
+            Err(Error::Empty)
         } else {
-            Some(self.list.get(0).unwrap().data)
+            Ok(&self.list.get(0)?.data)
         }
     }
 
     pub fn clear(&mut self) {
+        for index in 0..self.list.size() {
+            let slot = self.list.get(index).unwrap().slot;
+            self.release_slot(slot);
+        }
         self.list.clear();
     }
 
-    pub fn peek_priority(&mut self) -> i64 {
+    pub fn peek_priority(&self) -> Result<&P> {
         if self.list.size() == 0 {
-            println!("Empty heap!");
-            0
+            Err(Error::Empty)
         } else {
-            self.list.get(0).unwrap().priority
+            Ok(&self.list.get(0)?.priority)
         }
     }
 
-    fn parent_index(&mut self, index: usize) -> usize {
+    /// Whether the item behind `handle` is still in the heap.
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.position(handle).is_ok()
+    }
+
+    pub fn get(&self, handle: Handle) -> Result<&T> {
+        let position = self.position(handle)?;
+        Ok(&self.list.get(position)?.data)
+    }
+
+    pub fn priority(&self, handle: Handle) -> Result<&P> {
+        let position = self.position(handle)?;
+        Ok(&self.list.get(position)?.priority)
+    }
+
+    /// Gives the item behind `handle` a new priority and returns the old one.
+    ///
+    /// Works in both directions, so it serves as decrease-key and increase-key.
+    pub fn change_priority(&mut self, handle: Handle, priority: P) -> Result<P> {
+        let position = self.position(handle)?;
+        let item = self.list.get_mut(position)?;
+        let old = std::mem::replace(&mut item.priority, priority);
+        let position = self.sift_up(position);
+        self.sift_down(position);
+        Ok(old)
+    }
+
+    /// Removes the item behind `handle` from the heap and returns its data.
+    pub fn remove(&mut self, handle: Handle) -> Result<T> {
+        let position = self.position(handle)?;
+        self.remove_at(position)
+    }
+
+    fn position(&self, handle: Handle) -> Result<usize> {
+        match self.slots.get(handle.slot) {
+            Ok(slot) if slot.generation == handle.generation => {
+                slot.position.ok_or(Error::NotFound)
+            }
+            _ => Err(Error::NotFound),
+        }
+    }
+
+    fn acquire_slot(&mut self, position: usize) -> Handle {
+        let slot = if self.free_slots.size() > 0 {
+            let slot = self.free_slots.remove(self.free_slots.size() - 1).unwrap();
+            self.slots.get_mut(slot).unwrap().position = Some(position);
+            slot
+        } else {
+            self.slots.add(Slot {
+                position: Some(position),
+                generation: 0,
+            });
+            self.slots.size() - 1
+        };
+        Handle {
+            slot,
+            generation: self.slots.get(slot).unwrap().generation,
+        }
+    }
+
+    fn release_slot(&mut self, slot: usize) {
+        let entry = self.slots.get_mut(slot).unwrap();
+        entry.position = None;
+        entry.generation += 1;
+        self.free_slots.add(slot);
+    }
+
+    fn remove_at(&mut self, position: usize) -> Result<T> {
+        let last_index = self.list.size() - 1;
+        self.swap(position, last_index);
+        let out = self.list.remove(last_index)?;
+        self.release_slot(out.slot);
+        if position < self.list.size() {
+            let position = self.sift_up(position);
+            self.sift_down(position);
+        }
+        Ok(out.data)
+    }
+
+    /// Swaps two items and records their new positions in their slots.
+    fn swap(&mut self, index0: usize, index1: usize) {
+        self.list.swap(index0, index1).unwrap();
+        for &index in &[index0, index1] {
+            let slot = self.list.get(index).unwrap().slot;
+            self.slots.get_mut(slot).unwrap().position = Some(index);
+        }
+    }
+
+    /// Moves the item at `index` up until its parent comes before it.
+    fn sift_up(&mut self, index: usize) -> usize {
+        let mut current_index = index;
+        let mut parent_index = self.parent_index(current_index);
+        while self.is_before(current_index, parent_index) {
+            self.swap(current_index, parent_index);
+            current_index = parent_index;
+            parent_index = self.parent_index(current_index);
+        }
+        current_index
+    }
+
+    /// Moves the item at `index` down until it comes before both sons.
+    fn sift_down(&mut self, index: usize) -> usize {
+        let mut current_index = index;
+        let mut son_index = self.greater_son_index(current_index);
+        while son_index < self.list.size() && self.is_before(son_index, current_index) {
+            self.swap(current_index, son_index);
+            current_index = son_index;
+            son_index = self.greater_son_index(current_index);
+        }
+        current_index
+    }
+
+    /// Whether the item at `index0` must be popped before the one at `index1`.
+    fn is_before(&self, index0: usize, index1: usize) -> bool {
+        let first = &self.list.get(index0).unwrap().priority;
+        let second = &self.list.get(index1).unwrap().priority;
+        self.comparator.compare(first, second) == Ordering::Less
+    }
+
+    fn parent_index(&self, index: usize) -> usize {
         if index <= 1 {
             0
         } else {
@@ -100,19 +360,130 @@ where
         }
     }
 
-    fn greater_son_index(&mut self, index: usize) -> usize {
+    fn greater_son_index(&self, index: usize) -> usize {
         let right_son = (2 * index) + 2;
         let left_son = (2 * index) + 1;
-        if left_son >= self.list.size() || right_son >= self.list.size() {
-            return left_son;
+        if right_son < self.list.size() && self.is_before(right_son, left_son) {
+            right_son
         } else {
-            return if self.list.get(left_son).unwrap().priority
-                <= self.list.get(right_son).unwrap().priority
-            {
-                left_son
-            } else {
-                right_son
-            };
+            left_son
+        }
+    }
+}
+
+/// Clones keep the handles of the original heap valid.
+impl<P, T, C> Clone for Heap<P, T, C>
+where
+    P: Clone,
+    T: Clone,
+    C: Clone,
+{
+    fn clone(&self) -> Self {
+        Heap {
+            list: self.list.clone(),
+            slots: self.slots.clone(),
+            free_slots: self.free_slots.clone(),
+            comparator: self.comparator.clone(),
+        }
+    }
+}
+
+/// Lists the priorities and items in the heap's internal order.
+impl<P, T, C> fmt::Debug for Heap<P, T, C>
+where
+    P: fmt::Debug,
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<P, T, C> Heap<P, T, C>
+where
+    P: Ord,
+    T: Ord,
+{
+    /// The priorities and items sorted by priority, then by item, which
+    /// does not depend on how the heap laid them out.
+    fn sorted_items(&self) -> ArrayList<(&P, &T)> {
+        let mut items: ArrayList<(&P, &T)> = self.iter().collect();
+        items.sort_unstable();
+        items
+    }
+}
+
+/// Heaps are equal if they hold the same priorities and items, each as
+/// many times, however they are laid out. Takes O(n log n).
+impl<P, T, C> PartialEq for Heap<P, T, C>
+where
+    P: Ord,
+    T: Ord,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.list.size() == other.list.size() && self.sorted_items() == other.sorted_items()
+    }
+}
+
+impl<P, T, C> Eq for Heap<P, T, C>
+where
+    P: Ord,
+    T: Ord,
+{
+}
+
+/// Compares the priorities and items of both heaps in sorted order, like
+/// sorted lists of `(priority, item)` pairs.
+impl<P, T, C> PartialOrd for Heap<P, T, C>
+where
+    P: Ord,
+    T: Ord,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P, T, C> Ord for Heap<P, T, C>
+where
+    P: Ord,
+    T: Ord,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sorted_items().cmp(&other.sorted_items())
+    }
+}
+
+/// Hashes the priorities and items in sorted order, so equal heaps hash
+/// alike.
+impl<P, T, C> Hash for Heap<P, T, C>
+where
+    P: Ord + Hash,
+    T: Ord + Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.sorted_items().hash(state)
+    }
+}
+
+impl<P, T, C> FromIterator<(P, T)> for Heap<P, T, C>
+where
+    C: Comparator<P> + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (P, T)>>(iter: I) -> Self {
+        let mut heap = Heap::new();
+        heap.extend(iter);
+        heap
+    }
+}
+
+impl<P, T, C> Extend<(P, T)> for Heap<P, T, C>
+where
+    C: Comparator<P>,
+{
+    fn extend<I: IntoIterator<Item = (P, T)>>(&mut self, iter: I) {
+        for (priority, data) in iter {
+            self.push(priority, data);
         }
     }
 }
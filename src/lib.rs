@@ -0,0 +1,65 @@
+//! Hand-written containers: a fixed-size `Array`, the growable `ArrayList`
+//! with its configurable `GrowthPolicy`, a doubly linked `LinkedList` and
+//! the `ArenaList` keeping its nodes in an `Array` slab, the ring buffer
+//! `Deque`, a priority `Heap`, the open addressing `HashMap` with the
+//! `HashSet` built on it, the AVL tree backed `TreeMap`, the `BTreeMap`
+//! whose nodes are stored in `Array`s and the `SkipList`. All three lists
+//! implement the common `List` trait. The `spsc` and `mpmc` modules hold
+//! bounded lock-free queues for passing items between threads.
+
+pub mod arena_list;
+pub mod array;
+pub mod array_list;
+mod backoff;
+pub mod btree_map;
+pub mod deque;
+pub mod error;
+pub mod growth_policy;
+pub mod hash_map;
+pub mod hash_set;
+pub mod heap;
+pub mod linked_list;
+pub mod list;
+mod map_iter;
+mod merge_sort;
+pub mod mpmc;
+pub mod skip_list;
+pub mod spsc;
+pub mod tree_map;
+
+pub use arena_list::{
+    ArenaList, ArenaListCursorMut, ArenaListIntoIterator, ArenaListIterator, ArenaListIteratorMut,
+};
+pub use array::{Array, ArrayIntoIterator, ArrayIterator, ArrayIteratorMut};
+pub use array_list::{ArrayList, ArrayListIntoIterator, ArrayListIterator, ArrayListIteratorMut};
+pub use btree_map::{
+    BTreeMap, BTreeMapIntoIterator, BTreeMapIterator, BTreeMapIteratorMut, BTreeMapKeys,
+    BTreeMapRange, BTreeMapRangeMut, BTreeMapValues, BTreeMapValuesMut,
+};
+pub use deque::{Deque, DequeIntoIterator, DequeIterator, DequeIteratorMut};
+pub use error::{Error, Result};
+pub use growth_policy::GrowthPolicy;
+pub use hash_map::{
+    HashMap, HashMapIntoIterator, HashMapIterator, HashMapIteratorMut, HashMapKeys, HashMapValues,
+    HashMapValuesMut,
+};
+pub use hash_set::{
+    HashSet, HashSetDifference, HashSetIntersection, HashSetIntoIterator, HashSetIterator,
+    HashSetSymmetricDifference, HashSetUnion,
+};
+pub use heap::{
+    Comparator, Handle, Heap, HeapIntoIterator, HeapIterator, MaxHeap, MaxOrder, MinHeap, MinOrder,
+};
+pub use linked_list::{
+    CursorMut, LinkedList, LinkedListIntoIterator, LinkedListItem, LinkedListIterator,
+    LinkedListIteratorMut,
+};
+pub use list::List;
+pub use skip_list::{
+    SkipList, SkipListIntoIterator, SkipListIterator, SkipListIteratorMut, SkipListKeys,
+    SkipListRange, SkipListRangeMut, SkipListValues, SkipListValuesMut,
+};
+pub use tree_map::{
+    TreeMap, TreeMapIntoIterator, TreeMapIterator, TreeMapIteratorMut, TreeMapKeys, TreeMapRange,
+    TreeMapRangeMut, TreeMapValues, TreeMapValuesMut,
+};
@@ -1,12 +1,13 @@
+use crate::error::{Error, Result};
+use crate::merge_sort::{self, Chain};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::{FromIterator, FusedIterator};
+use std::marker::PhantomData;
+use std::mem;
 use std::ptr;
 
-pub fn list_index_out_of_bounds(index: usize, length: usize) {
-    println!(
-        "Linked list index out of bounds! Index {}, array length {}",
-        index, length
-    );
-}
-
 pub struct LinkedListItem<T> {
     data: T,
     next: *mut LinkedListItem<T>,
@@ -17,59 +18,146 @@ pub struct LinkedList<T> {
     size: usize,
     begin: *mut LinkedListItem<T>,
     end: *mut LinkedListItem<T>,
+    _marker: PhantomData<Box<LinkedListItem<T>>>,
 }
 
-pub struct LinkedListIterator<T> {
+/// Iterator over references to the items of a [`LinkedList`].
+///
+/// Iterates from both ends; the front and the back stop once they meet.
+pub struct LinkedListIterator<'a, T> {
+    size: usize,
     begin: *const LinkedListItem<T>,
     end: *const LinkedListItem<T>,
+    _marker: PhantomData<&'a T>,
 }
 
-impl<T> Iterator for LinkedListIterator<T>
-where
-    T: Copy,
-{
-    type Item = T;
-    fn next(&mut self) -> Option<T> {
-        if self.begin == ptr::null() {
+impl<'a, T> Iterator for LinkedListIterator<'a, T> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<&'a T> {
+        if self.size == 0 {
             None
         } else {
             unsafe {
-                let item = ptr::read(self.begin);
-                let result = item.data;
+                let item = &*self.begin;
                 self.begin = item.next;
-                Some(result)
+                self.size -= 1;
+                Some(&item.data)
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
 }
 
-impl<T> DoubleEndedIterator for LinkedListIterator<T>
-where
-    T: Copy,
-{
-    fn next_back(&mut self) -> Option<T> {
-        if self.end == ptr::null() {
+impl<'a, T> DoubleEndedIterator for LinkedListIterator<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        if self.size == 0 {
             None
         } else {
             unsafe {
-                let item = ptr::read(self.end);
-                let result = item.data;
-                self.begin = item.previous;
-                Some(result)
+                let item = &*self.end;
+                self.end = item.previous;
+                self.size -= 1;
+                Some(&item.data)
             }
         }
     }
 }
 
-impl<T> LinkedList<T>
-where
-    T: Copy + PartialOrd,
-{
+impl<'a, T> ExactSizeIterator for LinkedListIterator<'a, T> {}
+
+impl<'a, T> FusedIterator for LinkedListIterator<'a, T> {}
+
+/// Iterator over mutable references to the items of a [`LinkedList`].
+///
+/// Iterates from both ends; the front and the back stop once they meet.
+pub struct LinkedListIteratorMut<'a, T> {
+    size: usize,
+    begin: *mut LinkedListItem<T>,
+    end: *mut LinkedListItem<T>,
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> Iterator for LinkedListIteratorMut<'a, T> {
+    type Item = &'a mut T;
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.size == 0 {
+            None
+        } else {
+            unsafe {
+                let item = &mut *self.begin;
+                self.begin = item.next;
+                self.size -= 1;
+                Some(&mut item.data)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.size, Some(self.size))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for LinkedListIteratorMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        if self.size == 0 {
+            None
+        } else {
+            unsafe {
+                let item = &mut *self.end;
+                self.end = item.previous;
+                self.size -= 1;
+                Some(&mut item.data)
+            }
+        }
+    }
+}
+
+impl<'a, T> ExactSizeIterator for LinkedListIteratorMut<'a, T> {}
+
+impl<'a, T> FusedIterator for LinkedListIteratorMut<'a, T> {}
+
+/// Iterator moving the items out of a [`LinkedList`].
+pub struct LinkedListIntoIterator<T> {
+    list: LinkedList<T>,
+}
+
+impl<T> Iterator for LinkedListIntoIterator<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.list.size, Some(self.list.size))
+    }
+}
+
+impl<T> DoubleEndedIterator for LinkedListIntoIterator<T> {
+    fn next_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for LinkedListIntoIterator<T> {}
+
+impl<T> FusedIterator for LinkedListIntoIterator<T> {}
+
+impl<T> Default for LinkedList<T> {
+    fn default() -> Self {
+        LinkedList::new()
+    }
+}
+
+impl<T> LinkedList<T> {
     pub fn new() -> LinkedList<T> {
         LinkedList {
             size: 0,
             begin: ptr::null_mut(),
             end: ptr::null_mut(),
+            _marker: PhantomData,
         }
     }
 
@@ -77,19 +165,27 @@ where
         self.size
     }
 
-    fn get_node(&mut self, index: usize) -> *mut LinkedListItem<T> {
-        let begin = if index <= self.size / 2 { true } else { false };
+    fn check_index(&self, index: usize) -> Result<()> {
+        if index < self.size {
+            Ok(())
+        } else {
+            Err(Error::IndexOutOfBounds {
+                index,
+                len: self.size,
+            })
+        }
+    }
+
+    fn get_node(&self, index: usize) -> *mut LinkedListItem<T> {
+        let begin = index <= self.size / 2;
         let mut item = if begin { self.begin } else { self.end };
         let mut current_index = if begin { 0 } else { self.size - 1 };
-        loop {
-            if current_index == index {
-                break;
-            }
+        while current_index != index {
             unsafe {
                 item = if begin {
-                    ptr::read(item).next
+                    (*item).next
                 } else {
-                    ptr::read(item).previous
+                    (*item).previous
                 }
             }
             if begin {
@@ -101,196 +197,707 @@ where
         item
     }
 
-    pub fn get(&mut self, index: usize) -> Option<T> {
-        if index >= self.size {
-            list_index_out_of_bounds(index, self.size);
+    pub fn get(&self, index: usize) -> Result<&T> {
+        self.check_index(index)?;
+        unsafe { Ok(&(*self.get_node(index)).data) }
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Result<&mut T> {
+        self.check_index(index)?;
+        unsafe { Ok(&mut (*self.get_node(index)).data) }
+    }
+
+    pub fn add(&mut self, item: T) {
+        self.link_before(ptr::null_mut(), item);
+    }
+
+    /// Inserts `item` in front of the first item.
+    pub fn push_front(&mut self, item: T) {
+        self.link_before(self.begin, item);
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.begin.is_null() {
             None
         } else {
-            unsafe { Some(ptr::read(self.get_node(index)).data) }
+            Some(self.unlink(self.begin))
         }
     }
 
-    pub fn add(&mut self, item: T) {
-        if self.size == 0 {
-            let new_item = LinkedListItem {
-                data: item,
-                next: ptr::null_mut(),
-                previous: ptr::null_mut(),
-            };
-            self.begin = Box::into_raw(Box::new(new_item));
-            self.end = self.begin;
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.end.is_null() {
+            None
         } else {
-            let new_item = LinkedListItem {
-                data: item,
-                next: ptr::null_mut(),
-                previous: self.end,
-            };
-            unsafe {
-                let mut end = ptr::read(self.end);
-                let new_ptr = Box::into_raw(Box::new(new_item));
-                end.next = new_ptr;
-                ptr::drop_in_place(self.end);
-                ptr::write(self.end, end);
-                self.end = new_ptr;
-            }
+            Some(self.unlink(self.end))
         }
-        self.size += 1;
     }
 
-    pub fn set(&mut self, index: usize, item: T) {
-        if index >= self.size {
-            list_index_out_of_bounds(index, self.size);
+    pub fn set(&mut self, index: usize, item: T) -> Result<()> {
+        self.check_index(index)?;
+        unsafe {
+            (*self.get_node(index)).data = item;
+        }
+        Ok(())
+    }
+
+    /// Inserts `item` at `index`, shifting later items towards the end.
+    /// Inserting at `size()` appends the item.
+    pub fn insert(&mut self, index: usize, item: T) -> Result<()> {
+        if index > self.size {
+            return Err(Error::IndexOutOfBounds {
+                index,
+                len: self.size,
+            });
+        }
+        let next = if index == self.size {
+            ptr::null_mut()
         } else {
-            unsafe {
-                let ptr = self.get_node(index);
-                let mut copy = ptr::read(ptr);
-                copy.data = item;
-                ptr::drop_in_place(ptr);
-                ptr::write(ptr, copy);
-            }
+            self.get_node(index)
+        };
+        self.link_before(next, item);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) -> Result<T> {
+        self.check_index(index)?;
+        let node = self.get_node(index);
+        Ok(self.unlink(node))
+    }
+
+    pub fn clear(&mut self) {
+        while !self.begin.is_null() {
+            self.unlink(self.begin);
         }
     }
 
-    pub fn insert(&mut self, index: usize, item: T) {
-        if index >= self.size {
-            list_index_out_of_bounds(index, self.size);
+    /// Moves all items of `other` to the end of the list in O(1), leaving
+    /// `other` empty.
+    pub fn append(&mut self, other: &mut LinkedList<T>) {
+        self.splice_before(ptr::null_mut(), other);
+    }
+
+    /// Moves all items of `other` into the list, the first one ending up at
+    /// `index`, and leaves `other` empty. Splicing at `size()` appends them.
+    ///
+    /// Finding the position takes O(min(index, size - index)); the nodes of
+    /// `other` are relinked in O(1).
+    pub fn splice_at(&mut self, index: usize, other: &mut LinkedList<T>) -> Result<()> {
+        if index > self.size {
+            return Err(Error::IndexOutOfBounds {
+                index,
+                len: self.size,
+            });
+        }
+        let next = if index == self.size {
+            ptr::null_mut()
         } else {
-            self.size += 1;
-            let mut new_node = LinkedListItem {
-                data: item,
-                next: ptr::null_mut(),
-                previous: ptr::null_mut(),
-            };
-            if index == 0 {
-                new_node.next = self.begin;
-                let new_ptr = Box::into_raw(Box::new(new_node));
-                unsafe {
-                    let mut begin = ptr::read(self.begin);
-                    begin.previous = new_ptr;
-                    ptr::drop_in_place(self.begin);
-                    ptr::write(self.begin, begin);
-                    self.begin = new_ptr;
-                }
+            self.get_node(index)
+        };
+        self.splice_before(next, other);
+        Ok(())
+    }
+
+    /// Splits the list in two at `index`: returns a list with the items from
+    /// `index` on and keeps the ones before it. Splitting at `size()`
+    /// returns an empty list.
+    ///
+    /// Finding the position takes O(min(index, size - index)); the nodes are
+    /// relinked in O(1).
+    pub fn split_off(&mut self, index: usize) -> Result<LinkedList<T>> {
+        if index > self.size {
+            return Err(Error::IndexOutOfBounds {
+                index,
+                len: self.size,
+            });
+        }
+        if index == self.size {
+            return Ok(LinkedList::new());
+        }
+        let begin = self.get_node(index);
+        let end = self.end;
+        unsafe {
+            let previous = (*begin).previous;
+            (*begin).previous = ptr::null_mut();
+            if previous.is_null() {
+                self.begin = ptr::null_mut();
             } else {
-                let node_ptr = self.get_node(index);
-                unsafe {
-                    let mut node = ptr::read(node_ptr);
-                    new_node.previous = node.previous;
-                    new_node.next = node_ptr;
-                    let mut old_prev = ptr::read(node.previous);
-                    let new_ptr = Box::into_raw(Box::new(new_node));
-                    old_prev.next = new_ptr;
-                    ptr::drop_in_place(node.previous);
-                    ptr::write(node.previous, old_prev);
-                    node.previous = new_ptr;
-                    ptr::drop_in_place(node_ptr);
-                    ptr::write(node_ptr, node);
-                }
+                (*previous).next = ptr::null_mut();
             }
+            self.end = previous;
         }
+        let size = self.size - index;
+        self.size = index;
+        Ok(LinkedList {
+            size,
+            begin,
+            end,
+            _marker: PhantomData,
+        })
     }
 
-    pub fn remove_item(&mut self, item: T) -> Option<T> {
-        let mut current_node = self.begin;
-        let mut current_index = 0;
-        loop {
-            if current_index >= self.size {
-                return None;
-            }
+    /// Reverses the order of the items in place by swapping the links of
+    /// every node.
+    pub fn reverse(&mut self) {
+        let mut node = self.begin;
+        while !node.is_null() {
             unsafe {
-                if ptr::read(current_node).data == item {
-                    break;
-                }
-                current_node = ptr::read(current_node).next;
+                let item = &mut *node;
+                mem::swap(&mut item.next, &mut item.previous);
+                node = item.previous;
             }
-            current_index += 1;
         }
-        self.remove_node(current_node)
+        mem::swap(&mut self.begin, &mut self.end);
     }
 
-    pub fn remove(&mut self, index: usize) -> Option<T> {
-        if index >= self.size {
-            list_index_out_of_bounds(index, self.size);
-            None
-        } else {
-            let node = self.get_node(index);
-            self.remove_node(node)
+    pub fn iter(&self) -> LinkedListIterator<'_, T> {
+        LinkedListIterator {
+            size: self.size,
+            begin: self.begin,
+            end: self.end,
+            _marker: PhantomData,
         }
     }
 
-    fn remove_node(&mut self, node: *mut LinkedListItem<T>) -> Option<T> {
-        let out: T;
-        if self.size == 1 {
-            unsafe {
-                out = ptr::read(self.begin).data;
+    pub fn iter_mut(&mut self) -> LinkedListIteratorMut<'_, T> {
+        LinkedListIteratorMut {
+            size: self.size,
+            begin: self.begin,
+            end: self.end,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns a cursor pointing at the first item, or at the ghost position
+    /// if the list is empty.
+    pub fn cursor_front(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: 0,
+            current: self.begin,
+            list: self,
+        }
+    }
+
+    /// Returns a cursor pointing at the last item, or at the ghost position
+    /// if the list is empty.
+    pub fn cursor_back(&mut self) -> CursorMut<'_, T> {
+        CursorMut {
+            index: self.size.saturating_sub(1),
+            current: self.end,
+            list: self,
+        }
+    }
+
+    /// Allocates a node for `data` and links it in front of `next`, or at the
+    /// end of the list if `next` is null.
+    fn link_before(&mut self, next: *mut LinkedListItem<T>, data: T) -> *mut LinkedListItem<T> {
+        let previous = if next.is_null() {
+            self.end
+        } else {
+            unsafe { (*next).previous }
+        };
+        let node = Box::into_raw(Box::new(LinkedListItem {
+            data,
+            next,
+            previous,
+        }));
+        unsafe {
+            if previous.is_null() {
+                self.begin = node;
+            } else {
+                (*previous).next = node;
             }
-            self.begin = ptr::null_mut();
-            self.end = ptr::null_mut();
-        } else if node == self.begin {
-            unsafe {
-                out = ptr::read(self.begin).data;
-                self.begin = ptr::read(self.begin).next;
-                let mut begin = ptr::read(self.begin);
-                begin.previous = ptr::null_mut();
-                ptr::drop_in_place(self.begin);
-                ptr::write(self.begin, begin);
+            if next.is_null() {
+                self.end = node;
+            } else {
+                (*next).previous = node;
             }
-        } else if node == self.end {
-            unsafe {
-                out = ptr::read(self.end).data;
-                self.end = ptr::read(self.end).previous;
-                let mut end = ptr::read(self.end);
-                end.next = ptr::null_mut();
-                ptr::drop_in_place(self.end);
-                ptr::write(self.end, end);
+        }
+        self.size += 1;
+        node
+    }
+
+    /// Sorts the list with `compare`, keeping equal items in their order.
+    ///
+    /// Runs a bottom-up merge sort over the nodes: they are relinked in
+    /// sorted order without moving or copying any item, in O(n log n). If
+    /// `compare` panics, the list keeps every item in some order.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        merge_sort::sort(self, &mut |a: &T, b: &T| compare(a, b) == Ordering::Less);
+    }
+
+    /// Removes consecutive items for which `same_bucket` returns `true`.
+    ///
+    /// `same_bucket` is given an item and the last item that was kept before
+    /// it; only the first item of each run stays in the list.
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let mut kept = self.begin;
+        if kept.is_null() {
+            return;
+        }
+        let mut node = unsafe { (*kept).next };
+        while !node.is_null() {
+            let next = unsafe { (*node).next };
+            if unsafe { same_bucket(&mut (*node).data, &mut (*kept).data) } {
+                drop(self.unlink(node));
+            } else {
+                kept = node;
             }
+            node = next;
+        }
+    }
+
+    /// Links all nodes of `other` in front of `next`, or at the end of the
+    /// list if `next` is null, and leaves `other` empty.
+    fn splice_before(&mut self, next: *mut LinkedListItem<T>, other: &mut LinkedList<T>) {
+        if other.size == 0 {
+            return;
+        }
+        let previous = if next.is_null() {
+            self.end
         } else {
-            unsafe {
-                let to_remove = ptr::read(node);
-                out = to_remove.data;
-                let ptr_prev = to_remove.previous;
-                let ptr_next = to_remove.next;
-                let mut prev = ptr::read(ptr_prev);
-                prev.next = ptr_next;
-                ptr::drop_in_place(ptr_prev);
-                ptr::write(ptr_prev, prev);
-                let mut next = ptr::read(ptr_next);
-                next.previous = ptr_prev;
-                ptr::drop_in_place(ptr_next);
-                ptr::write(ptr_next, next);
+            unsafe { (*next).previous }
+        };
+        unsafe {
+            (*other.begin).previous = previous;
+            (*other.end).next = next;
+            if previous.is_null() {
+                self.begin = other.begin;
+            } else {
+                (*previous).next = other.begin;
+            }
+            if next.is_null() {
+                self.end = other.end;
+            } else {
+                (*next).previous = other.end;
             }
         }
+        self.size += mem::replace(&mut other.size, 0);
+        other.begin = ptr::null_mut();
+        other.end = ptr::null_mut();
+    }
+
+    /// Unlinks `node` from the list, frees it and returns its data.
+    fn unlink(&mut self, node: *mut LinkedListItem<T>) -> T {
+        let node = unsafe { Box::from_raw(node) };
         unsafe {
-            ptr::drop_in_place(node);
+            if node.previous.is_null() {
+                self.begin = node.next;
+            } else {
+                (*node.previous).next = node.next;
+            }
+            if node.next.is_null() {
+                self.end = node.previous;
+            } else {
+                (*node.next).previous = node.previous;
+            }
         }
         self.size -= 1;
-        Some(out)
+        node.data
     }
+}
 
-    pub fn clear(&mut self) {
-        self.size = 0;
-        let mut current = self.begin;
-        loop {
-            if current == ptr::null_mut() {
+impl<T> LinkedList<T>
+where
+    T: PartialEq,
+{
+    pub fn remove_item(&mut self, item: T) -> Result<T> {
+        let mut current_node = self.begin;
+        while !current_node.is_null() {
+            unsafe {
+                if (*current_node).data == item {
+                    return Ok(self.unlink(current_node));
+                }
+                current_node = (*current_node).next;
+            }
+        }
+        Err(Error::NotFound)
+    }
+
+    /// Removes consecutive equal items, which on a sorted list removes every
+    /// duplicate.
+    pub fn dedup(&mut self) {
+        self.dedup_by(|a, b| a == b);
+    }
+}
+
+impl<T> LinkedList<T>
+where
+    T: PartialOrd,
+{
+    /// Inserts `item` into the sorted list behind the items less than or
+    /// equal to it, keeping the list sorted.
+    pub fn insert_sorted(&mut self, item: T) {
+        let mut next = self.begin;
+        while let Some(node) = unsafe { next.as_ref() } {
+            if item < node.data {
                 break;
             }
+            next = node.next;
+        }
+        self.link_before(next, item);
+    }
+
+    /// Merges the sorted list `other` into this sorted list, leaving `other`
+    /// empty. Of equal items, the ones from this list come first.
+    ///
+    /// The nodes of `other` are relinked, not copied, in O(n + m).
+    pub fn merge_sorted(&mut self, other: &mut LinkedList<T>) {
+        let left_len = self.size;
+        self.splice_before(ptr::null_mut(), other);
+        merge_sort::merge(self, left_len, &mut |a: &T, b: &T| a < b);
+    }
+
+    /// Checks whether every item is less than or equal to the next one.
+    pub fn is_sorted(&self) -> bool {
+        self.iter().zip(self.iter().skip(1)).all(|(a, b)| a <= b)
+    }
+}
+
+impl<T> LinkedList<T>
+where
+    T: Ord,
+{
+    /// Sorts the list, keeping equal items in their order. See
+    /// [`LinkedList::sort_by`].
+    pub fn sort(&mut self) {
+        self.sort_by(T::cmp);
+    }
+
+    /// Smallest item, the first one if several are equally small.
+    ///
+    /// Not called `min`: lists are [`Ord`] themselves, so `list.min()`
+    /// would find [`Ord::min`] comparing two lists.
+    pub fn min_item(&self) -> Option<&T> {
+        self.iter().min()
+    }
+
+    /// Largest item, the last one if several are equally large.
+    pub fn max_item(&self) -> Option<&T> {
+        self.iter().max()
+    }
+}
+
+impl<T> Chain for LinkedList<T> {
+    type Item = T;
+    type Node = *mut LinkedListItem<T>;
+
+    const NIL: *mut LinkedListItem<T> = ptr::null_mut();
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn first(&self) -> *mut LinkedListItem<T> {
+        self.begin
+    }
+
+    unsafe fn next(&self, node: *mut LinkedListItem<T>) -> *mut LinkedListItem<T> {
+        (*node).next
+    }
+
+    unsafe fn set_next(&mut self, node: *mut LinkedListItem<T>, next: *mut LinkedListItem<T>) {
+        (*node).next = next;
+    }
+
+    unsafe fn item(&self, node: *mut LinkedListItem<T>) -> &T {
+        &(*node).data
+    }
+
+    fn relink(&mut self, begin: *mut LinkedListItem<T>) {
+        self.begin = begin;
+        let mut previous = ptr::null_mut();
+        let mut node = begin;
+        while !node.is_null() {
             unsafe {
-                let next = ptr::read(current).next;
-                ptr::drop_in_place(current);
-                current = next;
+                (*node).previous = previous;
+                previous = node;
+                node = (*node).next;
             }
         }
-        self.begin = ptr::null_mut();
-        self.end = ptr::null_mut();
+        self.end = previous;
     }
+}
 
-    pub fn into_iter(&self) -> LinkedListIterator<T> {
-        let begin = self.begin;
-        let end = self.end;
+impl<T> IntoIterator for LinkedList<T> {
+    type Item = T;
+    type IntoIter = LinkedListIntoIterator<T>;
 
-        LinkedListIterator {
-            begin: begin,
-            end: end,
+    fn into_iter(self) -> LinkedListIntoIterator<T> {
+        LinkedListIntoIterator { list: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a LinkedList<T> {
+    type Item = &'a T;
+    type IntoIter = LinkedListIterator<'a, T>;
+
+    fn into_iter(self) -> LinkedListIterator<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut LinkedList<T> {
+    type Item = &'a mut T;
+    type IntoIter = LinkedListIteratorMut<'a, T>;
+
+    fn into_iter(self) -> LinkedListIteratorMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+impl<T> Drop for LinkedList<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+/// Cursor over a [`LinkedList`] that can edit the list around its position.
+///
+/// Besides pointing at an item, the cursor can rest on a "ghost" position
+/// between the last and the first item. Moving past either end of the list
+/// lands on the ghost, and moving once more wraps around to the other end.
+/// Every operation takes O(1).
+pub struct CursorMut<'a, T> {
+    index: usize,
+    current: *mut LinkedListItem<T>,
+    list: &'a mut LinkedList<T>,
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    /// Index of the current item, or `None` on the ghost position.
+    pub fn index(&self) -> Option<usize> {
+        if self.current.is_null() {
+            None
+        } else {
+            Some(self.index)
         }
     }
+
+    pub fn move_next(&mut self) {
+        if self.current.is_null() {
+            self.current = self.list.begin;
+            self.index = 0;
+        } else {
+            self.current = unsafe { (*self.current).next };
+            self.index += 1;
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        if self.current.is_null() {
+            self.current = self.list.end;
+            self.index = self.list.size.saturating_sub(1);
+        } else {
+            self.current = unsafe { (*self.current).previous };
+            self.index = self.index.checked_sub(1).unwrap_or(self.list.size);
+        }
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        unsafe { self.current.as_mut().map(|node| &mut node.data) }
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = if self.current.is_null() {
+            self.list.begin
+        } else {
+            unsafe { (*self.current).next }
+        };
+        unsafe { next.as_mut().map(|node| &mut node.data) }
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        let previous = if self.current.is_null() {
+            self.list.end
+        } else {
+            unsafe { (*self.current).previous }
+        };
+        unsafe { previous.as_mut().map(|node| &mut node.data) }
+    }
+
+    /// Inserts `item` before the current item, or at the end of the list when
+    /// on the ghost position.
+    pub fn insert_before(&mut self, item: T) {
+        self.list.link_before(self.current, item);
+        self.index += 1;
+    }
+
+    /// Inserts `item` after the current item, or at the front of the list
+    /// when on the ghost position.
+    pub fn insert_after(&mut self, item: T) {
+        let next = if self.current.is_null() {
+            self.list.begin
+        } else {
+            unsafe { (*self.current).next }
+        };
+        self.list.link_before(next, item);
+        if self.current.is_null() {
+            self.index += 1;
+        }
+    }
+
+    /// Removes the current item and moves the cursor to the next one.
+    /// Does nothing on the ghost position.
+    pub fn remove_current(&mut self) -> Option<T> {
+        if self.current.is_null() {
+            return None;
+        }
+        let node = self.current;
+        self.current = unsafe { (*node).next };
+        Some(self.list.unlink(node))
+    }
+
+    /// Splits off every item after the current one into a new list.
+    /// On the ghost position the whole list is split off.
+    pub fn split_after(&mut self) -> LinkedList<T> {
+        let (begin, end, size) = if self.current.is_null() {
+            (self.list.begin, self.list.end, self.list.size)
+        } else {
+            let begin = unsafe { (*self.current).next };
+            (begin, self.list.end, self.list.size - self.index - 1)
+        };
+        if begin.is_null() {
+            return LinkedList::new();
+        }
+        unsafe {
+            let previous = (*begin).previous;
+            (*begin).previous = ptr::null_mut();
+            if previous.is_null() {
+                self.list.begin = ptr::null_mut();
+            } else {
+                (*previous).next = ptr::null_mut();
+            }
+            self.list.end = previous;
+        }
+        self.list.size -= size;
+        if self.current.is_null() {
+            self.index = 0;
+        }
+        LinkedList {
+            size,
+            begin,
+            end,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Splits off every item before the current one into a new list.
+    /// On the ghost position the whole list is split off.
+    pub fn split_before(&mut self) -> LinkedList<T> {
+        let (end, size) = if self.current.is_null() {
+            (self.list.end, self.list.size)
+        } else {
+            (unsafe { (*self.current).previous }, self.index)
+        };
+        if end.is_null() {
+            return LinkedList::new();
+        }
+        let begin = self.list.begin;
+        unsafe {
+            let next = (*end).next;
+            (*end).next = ptr::null_mut();
+            if next.is_null() {
+                self.list.end = ptr::null_mut();
+            } else {
+                (*next).previous = ptr::null_mut();
+            }
+            self.list.begin = next;
+        }
+        self.list.size -= size;
+        self.index = 0;
+        LinkedList {
+            size,
+            begin,
+            end,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for LinkedList<T>
+where
+    T: Clone,
+{
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T> fmt::Debug for LinkedList<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T> PartialEq for LinkedList<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &LinkedList<T>) -> bool {
+        self.size == other.size && self.iter().eq(other.iter())
+    }
+}
+
+impl<T> Eq for LinkedList<T> where T: Eq {}
+
+impl<T> PartialOrd for LinkedList<T>
+where
+    T: PartialOrd,
+{
+    fn partial_cmp(&self, other: &LinkedList<T>) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T> Ord for LinkedList<T>
+where
+    T: Ord,
+{
+    fn cmp(&self, other: &LinkedList<T>) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T> Hash for LinkedList<T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        self.iter().for_each(|item| item.hash(state));
+    }
+}
+
+impl<T> FromIterator<T> for LinkedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = LinkedList::new();
+        list.extend(iter);
+        list
+    }
+}
+
+impl<T> Extend<T> for LinkedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.add(item);
+        }
+    }
+}
+
+impl<'a, T> Extend<&'a T> for LinkedList<T>
+where
+    T: Copy + 'a,
+{
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied())
+    }
 }
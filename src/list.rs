@@ -0,0 +1,233 @@
+use crate::arena_list::{ArenaList, ArenaListIterator, ArenaListIteratorMut};
+use crate::array_list::{ArrayList, ArrayListIterator, ArrayListIteratorMut};
+use crate::error::Result;
+use crate::linked_list::{LinkedList, LinkedListIterator, LinkedListIteratorMut};
+
+/// Index-based sequence operations shared by [`ArrayList`], [`LinkedList`] and
+/// [`ArenaList`].
+///
+/// Code written against `List<T>` works with either implementation; the
+/// implementations only differ in cost, e.g. `get` is O(1) on an `ArrayList`
+/// but O(n) on a `LinkedList`.
+pub trait List<T> {
+    type Iter<'a>: Iterator<Item = &'a T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    type IterMut<'a>: Iterator<Item = &'a mut T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    fn size(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    fn get(&self, index: usize) -> Result<&T>;
+
+    fn get_mut(&mut self, index: usize) -> Result<&mut T>;
+
+    /// Appends `item` to the end of the list.
+    fn add(&mut self, item: T);
+
+    /// Replaces the item at `index`, dropping the old one.
+    fn set(&mut self, index: usize, item: T) -> Result<()>;
+
+    /// Inserts `item` at `index`, shifting later items towards the end.
+    /// Inserting at `size()` appends the item.
+    fn insert(&mut self, index: usize, item: T) -> Result<()>;
+
+    fn remove(&mut self, index: usize) -> Result<T>;
+
+    /// Removes the first item equal to `item`.
+    fn remove_item(&mut self, item: T) -> Result<T>
+    where
+        T: PartialEq;
+
+    fn clear(&mut self);
+
+    /// Iterates over the items from the first to the last.
+    fn iter(&self) -> Self::Iter<'_>;
+
+    fn iter_mut(&mut self) -> Self::IterMut<'_>;
+}
+
+impl<T> List<T> for ArrayList<T> {
+    type Iter<'a>
+        = ArrayListIterator<'a, T>
+    where
+        T: 'a;
+
+    type IterMut<'a>
+        = ArrayListIteratorMut<'a, T>
+    where
+        T: 'a;
+
+    fn size(&self) -> usize {
+        ArrayList::size(self)
+    }
+
+    fn get(&self, index: usize) -> Result<&T> {
+        ArrayList::get(self, index)
+    }
+
+    fn get_mut(&mut self, index: usize) -> Result<&mut T> {
+        ArrayList::get_mut(self, index)
+    }
+
+    fn add(&mut self, item: T) {
+        ArrayList::add(self, item)
+    }
+
+    fn set(&mut self, index: usize, item: T) -> Result<()> {
+        ArrayList::set(self, index, item)
+    }
+
+    fn insert(&mut self, index: usize, item: T) -> Result<()> {
+        ArrayList::insert(self, index, item)
+    }
+
+    fn remove(&mut self, index: usize) -> Result<T> {
+        ArrayList::remove(self, index)
+    }
+
+    fn remove_item(&mut self, item: T) -> Result<T>
+    where
+        T: PartialEq,
+    {
+        ArrayList::remove_item(self, item)
+    }
+
+    fn clear(&mut self) {
+        ArrayList::clear(self)
+    }
+
+    fn iter(&self) -> ArrayListIterator<'_, T> {
+        ArrayList::iter(self)
+    }
+
+    fn iter_mut(&mut self) -> ArrayListIteratorMut<'_, T> {
+        ArrayList::iter_mut(self)
+    }
+}
+
+impl<T> List<T> for LinkedList<T> {
+    type Iter<'a>
+        = LinkedListIterator<'a, T>
+    where
+        T: 'a;
+
+    type IterMut<'a>
+        = LinkedListIteratorMut<'a, T>
+    where
+        T: 'a;
+
+    fn size(&self) -> usize {
+        LinkedList::size(self)
+    }
+
+    fn get(&self, index: usize) -> Result<&T> {
+        LinkedList::get(self, index)
+    }
+
+    fn get_mut(&mut self, index: usize) -> Result<&mut T> {
+        LinkedList::get_mut(self, index)
+    }
+
+    fn add(&mut self, item: T) {
+        LinkedList::add(self, item)
+    }
+
+    fn set(&mut self, index: usize, item: T) -> Result<()> {
+        LinkedList::set(self, index, item)
+    }
+
+    fn insert(&mut self, index: usize, item: T) -> Result<()> {
+        LinkedList::insert(self, index, item)
+    }
+
+    fn remove(&mut self, index: usize) -> Result<T> {
+        LinkedList::remove(self, index)
+    }
+
+    fn remove_item(&mut self, item: T) -> Result<T>
+    where
+        T: PartialEq,
+    {
+        LinkedList::remove_item(self, item)
+    }
+
+    fn clear(&mut self) {
+        LinkedList::clear(self)
+    }
+
+    fn iter(&self) -> LinkedListIterator<'_, T> {
+        LinkedList::iter(self)
+    }
+
+    fn iter_mut(&mut self) -> LinkedListIteratorMut<'_, T> {
+        LinkedList::iter_mut(self)
+    }
+}
+
+impl<T> List<T> for ArenaList<T> {
+    type Iter<'a>
+        = ArenaListIterator<'a, T>
+    where
+        T: 'a;
+
+    type IterMut<'a>
+        = ArenaListIteratorMut<'a, T>
+    where
+        T: 'a;
+
+    fn size(&self) -> usize {
+        ArenaList::size(self)
+    }
+
+    fn get(&self, index: usize) -> Result<&T> {
+        ArenaList::get(self, index)
+    }
+
+    fn get_mut(&mut self, index: usize) -> Result<&mut T> {
+        ArenaList::get_mut(self, index)
+    }
+
+    fn add(&mut self, item: T) {
+        ArenaList::add(self, item)
+    }
+
+    fn set(&mut self, index: usize, item: T) -> Result<()> {
+        ArenaList::set(self, index, item)
+    }
+
+    fn insert(&mut self, index: usize, item: T) -> Result<()> {
+        ArenaList::insert(self, index, item)
+    }
+
+    fn remove(&mut self, index: usize) -> Result<T> {
+        ArenaList::remove(self, index)
+    }
+
+    fn remove_item(&mut self, item: T) -> Result<T>
+    where
+        T: PartialEq,
+    {
+        ArenaList::remove_item(self, item)
+    }
+
+    fn clear(&mut self) {
+        ArenaList::clear(self)
+    }
+
+    fn iter(&self) -> ArenaListIterator<'_, T> {
+        ArenaList::iter(self)
+    }
+
+    fn iter_mut(&mut self) -> ArenaListIteratorMut<'_, T> {
+        ArenaList::iter_mut(self)
+    }
+}
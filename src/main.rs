@@ -1,17 +1,13 @@
-mod array;
-mod array_list;
-mod heap;
-mod linked_list;
-
-use array::Array;
-use array_list::ArrayList;
-use heap::Heap;
-use linked_list::LinkedList;
+use data_structures::{
+    mpmc, spsc, ArenaList, Array, ArrayList, BTreeMap, Deque, Error, HashMap, HashSet, Heap,
+    LinkedList, MaxHeap, SkipList, TreeMap,
+};
+use std::thread;
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
 
-    if args.len() < 1 {
+    if args.len() < 2 {
         println!("No program argument given!");
     } else {
         match args[1].as_ref() {
@@ -19,13 +15,227 @@ fn main() {
             "array_list" => array_list(),
             "linked_list" => linked_list(),
             "heap" => heap(),
+            "deque" => deque(),
+            "queue" => queue(),
+            "hash_map" => hash_map(),
+            "hash_set" => hash_set(),
+            "tree_map" => tree_map(),
+            "btree_map" => btree_map(),
+            "skip_list" => skip_list(),
+            "arena_list" => arena_list(),
             _ => println!("Invalid program argument"),
         }
     }
 }
 
+fn queue() {
+    let mut queue = spsc::Queue::new(4);
+    let (mut producer, mut consumer) = queue.split();
+    thread::scope(|scope| {
+        scope.spawn(move || {
+            for item in 0..10 {
+                producer.push(item);
+            }
+        });
+        for item in 0..10 {
+            assert_eq!(consumer.pop(), item);
+        }
+    });
+
+    let queue = mpmc::Queue::new(2);
+    assert_eq!(queue.try_push(1), Ok(()));
+    assert_eq!(queue.try_push(2), Ok(()));
+    assert_eq!(queue.try_push(3), Err(3));
+    let popped: Vec<i32> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..2).map(|_| scope.spawn(|| queue.pop())).collect();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .collect()
+    });
+    assert_eq!(popped.iter().sum::<i32>(), 3);
+    println!("Popped {:?}", popped);
+}
+
+fn hash_map() {
+    let mut map = HashMap::new();
+
+    for word in "the quick brown fox jumps over the lazy dog".split(' ') {
+        *map.entry(word).or_insert(0) += 1;
+    }
+
+    assert_eq!(map.size(), 8);
+    assert_eq!(map["the"], 2);
+    assert_eq!(map.get("fox"), Some(&1));
+    assert_eq!(map.get("cat"), None);
+
+    assert_eq!(map.insert("cat", 3), None);
+    assert_eq!(map.remove("dog"), Some(1));
+    map.retain(|word, _| word.len() > 3);
+    assert_eq!(map.size(), 5);
+    println!("{:?}", map);
+
+    for (word, count) in &map {
+        println!("Key {}, value {}", word, count);
+    }
+}
+
+fn hash_set() {
+    let odd: HashSet<i32> = (1..10).step_by(2).collect();
+    let small: HashSet<i32> = (1..5).collect();
+
+    assert_eq!(odd.size(), 5);
+    assert!(odd.contains(&3));
+    assert!(!odd.contains(&4));
+
+    let mut common: Vec<_> = odd.intersection(&small).collect();
+    common.sort();
+    assert_eq!(common, [&1, &3]);
+    assert_eq!(odd.union(&small).count(), 7);
+    assert_eq!(small.difference(&odd).count(), 2);
+    assert_eq!(odd.symmetric_difference(&small).count(), 5);
+    assert!(!small.is_subset(&odd));
+    assert!(!small.is_disjoint(&odd));
+    println!("{:?}", odd);
+
+    for item in &small {
+        println!("Item {}", item);
+    }
+}
+
+fn tree_map() {
+    let mut map = TreeMap::new();
+
+    for (index, word) in ["pear", "apple", "fig", "plum", "kiwi"].iter().enumerate() {
+        map.insert(*word, index);
+    }
+
+    assert_eq!(map.size(), 5);
+    assert_eq!(map["fig"], 2);
+    assert_eq!(map.first(), Some((&"apple", &1)));
+    assert_eq!(map.last(), Some((&"plum", &3)));
+    assert_eq!(map.floor("grape"), Some((&"fig", &2)));
+    assert_eq!(map.ceiling("grape"), Some((&"kiwi", &4)));
+
+    let middle: Vec<_> = map.range("b".."p").map(|(word, _)| *word).collect();
+    assert_eq!(middle, ["fig", "kiwi"]);
+
+    assert_eq!(map.remove("pear"), Some(0));
+    map.check_invariants().unwrap();
+    println!("{:?}", map);
+
+    for (word, index) in &map {
+        println!("Key {}, value {}", word, index);
+    }
+}
+
+fn btree_map() {
+    let mut map = BTreeMap::with_branching_factor(4);
+
+    for key in 0..20 {
+        map.insert(key, key * key);
+    }
+
+    assert_eq!(map.size(), 20);
+    assert_eq!(map[&7], 49);
+    assert_eq!(map.height(), 3);
+
+    let middle: Vec<_> = map.range(5..8).map(|(key, _)| *key).collect();
+    assert_eq!(middle, [5, 6, 7]);
+
+    let mut upper = map.split_off(&10);
+    assert_eq!(map.size(), 10);
+    assert_eq!(upper.first(), Some((&10, &100)));
+
+    map.append(&mut upper);
+    assert!(upper.is_empty());
+    assert_eq!(map.remove(&3), Some(9));
+    map.check_invariants().unwrap();
+    println!("{:?}", map);
+
+    for (key, value) in map.range(..5) {
+        println!("Key {}, value {}", key, value);
+    }
+}
+
+fn skip_list() {
+    let mut map = SkipList::with_seed(42);
+
+    for (index, word) in ["pear", "apple", "fig", "plum", "kiwi"].iter().enumerate() {
+        map.insert(*word, index);
+    }
+
+    assert_eq!(map.size(), 5);
+    assert_eq!(map["fig"], 2);
+    assert_eq!(map.first(), Some((&"apple", &1)));
+    assert_eq!(map.last(), Some((&"plum", &3)));
+
+    let middle: Vec<_> = map.range("b".."p").map(|(word, _)| *word).collect();
+    assert_eq!(middle, ["fig", "kiwi"]);
+
+    assert_eq!(map.remove("pear"), Some(0));
+    map.check_invariants().unwrap();
+    println!("{:?}", map);
+
+    for (word, index) in &map {
+        println!("Key {}, value {}", word, index);
+    }
+}
+
+fn arena_list() {
+    let mut list = ArenaList::with_capacity(8);
+
+    for value in [5, 3, 8, 1].iter() {
+        list.add(*value);
+    }
+    list.push_front(9);
+    list.insert(2, 4).unwrap();
+
+    assert_eq!(list.size(), 6);
+    assert_eq!(list.pop_back(), Some(1));
+    assert_eq!(list.remove(0), Ok(9));
+
+    // freed slots are reused, so the slab keeps its capacity
+    list.add(7);
+    list.add(2);
+    assert_eq!(list.capacity(), 8);
+
+    list.sort();
+    println!("{:?}", list);
+
+    for value in list.iter().rev() {
+        println!("Value {}", value);
+    }
+}
+
+fn deque() {
+    let mut deque = Deque::new();
+
+    deque.push_back(2);
+    deque.push_back(3);
+    deque.push_front(1);
+    deque.push_front(0);
+    deque.push_back(4);
+
+    assert_eq!(deque.size(), 5);
+    assert_eq!(deque.front(), Some(&0));
+    assert_eq!(deque.back(), Some(&4));
+    assert_eq!(deque[2], 2);
+
+    deque.rotate_left(2).unwrap();
+    assert_eq!(deque.make_contiguous(), [2, 3, 4, 0, 1]);
+
+    assert_eq!(deque.pop_front(), Some(2));
+    assert_eq!(deque.pop_back(), Some(1));
+    println!("{:?}", deque);
+
+    for (index, item) in deque.iter().enumerate() {
+        println!("Index {}, data {}", index, item);
+    }
+}
+
 fn heap() {
-    let mut heap: Heap<i32> = Heap::new();
+    let mut heap: Heap<i64, i32> = Heap::new();
 
     // lower number -> higher priority
     heap.push(46, 1);
@@ -35,12 +245,43 @@ fn heap() {
 
     assert_eq!(heap.size(), 4);
 
-    heap.pop();
-    assert_eq!(heap.peek().unwrap(), 2);
-    assert_eq!(heap.peek_priority(), 42);
+    heap.pop().unwrap();
+    assert_eq!(*heap.peek().unwrap(), 2);
+    assert_eq!(heap.peek_priority(), Ok(&42));
+
+    let job = heap.push(50, 5);
+    let other = heap.push(48, 6);
+    assert_eq!(heap.change_priority(job, 10), Ok(50));
+    assert_eq!(*heap.peek().unwrap(), 5);
+    assert_eq!(heap.remove(job), Ok(5));
+    assert!(!heap.contains(job));
+    assert_eq!(heap.remove(job), Err(Error::NotFound));
+    assert_eq!(heap.priority(other), Ok(&48));
 
+    println!("{:?}", heap);
     heap.clear();
     assert_eq!(heap.size(), 0);
+    assert!(!heap.contains(other));
+
+    // higher number -> higher priority
+    let mut max_heap: MaxHeap<(u32, u32), &str> = MaxHeap::new();
+    max_heap.push((1, 5), "low");
+    max_heap.push((2, 0), "high");
+    max_heap.push((1, 7), "middle");
+
+    assert_eq!(max_heap.pop(), Ok("high"));
+    assert_eq!(max_heap.pop(), Ok("middle"));
+
+    // closest to zero -> higher priority
+    let mut by_distance = Heap::with_comparator(|a: &f64, b: &f64| a.abs().total_cmp(&b.abs()));
+    by_distance.push(-3.5, 'a');
+    by_distance.push(0.25, 'b');
+    by_distance.push(2.0, 'c');
+
+    assert_eq!(by_distance.pop(), Ok('b'));
+    assert_eq!(by_distance.pop(), Ok('c'));
+    assert_eq!(by_distance.pop(), Ok('a'));
+    assert_eq!(by_distance.pop(), Err(Error::Empty));
 }
 
 fn array_list() {
@@ -63,7 +304,7 @@ fn array_list() {
     {
         let item = list.get(3);
         assert_eq!(
-            item.unwrap(),
+            *item.unwrap(),
             4,
             "3rd index expected {} was {}",
             4,
@@ -71,12 +312,12 @@ fn array_list() {
         );
     }
 
-    list.insert(0, 11);
+    list.insert(0, 11).unwrap();
 
     {
         let item = list.get(0);
         assert_eq!(
-            item.unwrap(),
+            *item.unwrap(),
             11,
             "0th index expected {} was {}",
             11,
@@ -95,7 +336,7 @@ fn array_list() {
     {
         let item = list.get(5);
         assert_eq!(
-            item.unwrap(),
+            *item.unwrap(),
             5,
             "5th index expected {} was {}",
             5,
@@ -103,11 +344,11 @@ fn array_list() {
         );
     }
 
-    list.set(3, 10);
+    list.set(3, 10).unwrap();
     {
         let item = list.get(3);
         assert_eq!(
-            item.unwrap(),
+            *item.unwrap(),
             10,
             "3rd index expected {} was {}",
             10,
@@ -172,12 +413,28 @@ fn array_list() {
         list.size(),
     );
 
-    let mut index = 0;
-    for i in list.into_iter() {
+    for (index, i) in list.iter().enumerate() {
         println!("Index {}, data {}", index, i);
-        index += 1;
     }
 
+    list.add(2);
+    list.sort();
+    assert_eq!(list[..], [1, 2, 4, 5]);
+    assert_eq!(list.binary_search(&4), Ok(2));
+    assert_eq!(list[1..3], [2, 4]);
+
+    let copy: ArrayList<i32> = list.iter().copied().collect();
+    assert_eq!(copy, list);
+    println!("{:?}", copy);
+
+    list.push(5);
+    list.dedup();
+    list.retain(|&item| item != 1);
+    let removed: Vec<i32> = list.splice(..1, vec![7, 8]).unwrap().collect();
+    assert_eq!(removed, [2]);
+    assert_eq!(list.pop(), Some(5));
+    assert_eq!(list[..], [7, 8, 4]);
+
     list.clear();
 
     assert_eq!(
@@ -192,7 +449,7 @@ fn array_list() {
 fn array() {
     let mut array: Array<i32> = Array::new(10);
 
-    array.set(0, 15);
+    array.set(0, 15).unwrap();
 
     assert_eq!(
         array.size(),
@@ -202,20 +459,46 @@ fn array() {
         array.size()
     );
 
+    assert_eq!(
+        array.get(10),
+        Err(Error::IndexOutOfBounds { index: 10, len: 10 })
+    );
+    assert_eq!(array.get(1), Err(Error::Uninitialized { index: 1 }));
+
     let item = array.get(0);
     assert_eq!(
-        item.unwrap(),
+        *item.unwrap(),
         15,
         "0th index should be {} was {}",
         15,
         item.unwrap()
     );
 
-    let mut index = 0;
-    for i in array.into_iter() {
+    for (index, i) in array.iter().enumerate() {
         println!("Index {}, data {}", index, i);
-        index += 1;
     }
+
+    let mut squares: Array<u32> = Array::new(4);
+    for i in 0..4 {
+        squares.set(i, (i * i) as u32).unwrap();
+    }
+    squares.reverse();
+    assert_eq!(squares[..2], [9, 4]);
+    assert_eq!(squares, (0..4).rev().map(|i| i * i).collect());
+    println!("{:?} {:?}", squares, array);
+
+    let mut names: Array<String> = Array::new(2);
+    names.set(0, String::from("first")).unwrap();
+    names.set(1, String::from("second")).unwrap();
+
+    let old = names.replace(0, String::from("third"));
+    assert_eq!(old.unwrap().unwrap(), "first");
+
+    let taken = names.take(1);
+    assert_eq!(taken.unwrap(), "second");
+    assert!(names.get(1).is_err());
+    names.get_mut(0).unwrap().push('!');
+    assert_eq!(names.get(0).unwrap(), "third!");
 }
 
 fn linked_list() {
@@ -229,49 +512,49 @@ fn linked_list() {
 
     {
         let item = list.get(0);
-        assert_eq!(0, item.unwrap());
+        assert_eq!(0, *item.unwrap());
     }
     {
         let item = list.get(1);
         assert_eq!(
             1,
-            item.unwrap(),
+            *item.unwrap(),
             "Item expected {} was {}",
             1,
             item.unwrap()
         );
     }
 
-    list.set(0, 11);
+    list.set(0, 11).unwrap();
     {
         let item = list.get(0);
         assert_eq!(
             11,
-            item.unwrap(),
+            *item.unwrap(),
             "Item expected {} was {}",
             11,
             item.unwrap()
         );
     }
 
-    list.set(1, 12);
+    list.set(1, 12).unwrap();
     {
         let item = list.get(1);
         assert_eq!(
             12,
-            item.unwrap(),
+            *item.unwrap(),
             "Item expected {} was {}",
             12,
             item.unwrap()
         );
     }
 
-    list.insert(0, 55);
+    list.insert(0, 55).unwrap();
     {
         let item = list.get(0);
         assert_eq!(
             55,
-            item.unwrap(),
+            *item.unwrap(),
             "Item expected {} was {}",
             55,
             item.unwrap()
@@ -279,12 +562,12 @@ fn linked_list() {
         assert_eq!(4, list.size());
     }
 
-    list.insert(1, 22);
+    list.insert(1, 22).unwrap();
     {
         let item = list.get(1);
         assert_eq!(
             22,
-            item.unwrap(),
+            *item.unwrap(),
             "Item expected {} was {}",
             22,
             item.unwrap()
@@ -367,11 +650,30 @@ fn linked_list() {
         assert_eq!(2, list.size());
     }
 
-    let mut index = 0;
-    for i in list.into_iter() {
+    for (index, i) in list.iter().enumerate() {
         println!("Index {}, data {}", index, i);
-        index += 1;
     }
 
+    {
+        let mut cursor = list.cursor_front();
+        cursor.insert_after(7);
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&mut 7));
+        cursor.insert_before(6);
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.remove_current(), Some(7));
+        assert_eq!(cursor.current(), Some(&mut 4));
+
+        let front = cursor.split_before();
+        assert_eq!(front.size(), 2);
+        assert_eq!(*front.get(1).unwrap(), 6);
+        assert_eq!(cursor.index(), Some(0));
+    }
+    assert_eq!(1, list.size());
+
+    let copy = list.clone();
+    assert_eq!(copy, list);
+    println!("{:?}", copy);
+
     list.clear();
 }
@@ -0,0 +1,326 @@
+//! Iterators shared by the ordered maps.
+//!
+//! Each map walks its entries in its own way, which it describes by
+//! implementing [`Entries`]; `map_iterators!` then defines the map's public
+//! iterator types on top of that walk. [`Span`] is the walk for maps whose
+//! nodes link to their neighbours in key order.
+
+use std::ptr;
+
+/// Walks a sequence of entries from both ends, yielding each entry once.
+/// The entries are handed out as pointers, which the iterators turn into
+/// references living as long as their borrow of the map.
+pub(crate) trait Entries {
+    type Key;
+    type Value;
+
+    fn next(&mut self) -> Option<(*const Self::Key, *mut Self::Value)>;
+
+    fn next_back(&mut self) -> Option<(*const Self::Key, *mut Self::Value)>;
+}
+
+/// Node that can reach its neighbours in key order.
+pub(crate) trait Linked {
+    type Key;
+    type Value;
+
+    /// Next node in key order, or null after the last one.
+    unsafe fn next(node: *mut Self) -> *mut Self;
+
+    /// Previous node in key order, or null before the first one.
+    unsafe fn previous(node: *mut Self) -> *mut Self;
+
+    unsafe fn entry(node: *mut Self) -> (*const Self::Key, *mut Self::Value);
+}
+
+/// Nodes between `front` and `back`, both included. Both are null once the
+/// two ends met.
+pub(crate) struct Span<N> {
+    pub(crate) front: *mut N,
+    pub(crate) back: *mut N,
+}
+
+impl<N> Span<N> {
+    pub(crate) fn empty() -> Span<N> {
+        Span {
+            front: ptr::null_mut(),
+            back: ptr::null_mut(),
+        }
+    }
+}
+
+impl<N: Linked> Entries for Span<N> {
+    type Key = N::Key;
+    type Value = N::Value;
+
+    fn next(&mut self) -> Option<(*const N::Key, *mut N::Value)> {
+        if self.front.is_null() {
+            return None;
+        }
+        let node = self.front;
+        if node == self.back {
+            *self = Span::empty();
+        } else {
+            self.front = unsafe { N::next(node) };
+        }
+        Some(unsafe { N::entry(node) })
+    }
+
+    fn next_back(&mut self) -> Option<(*const N::Key, *mut N::Value)> {
+        if self.back.is_null() {
+            return None;
+        }
+        let node = self.back;
+        if node == self.front {
+            *self = Span::empty();
+        } else {
+            self.back = unsafe { N::previous(node) };
+        }
+        Some(unsafe { N::entry(node) })
+    }
+}
+
+/// Defines the iterators of an ordered map `$map<K, V>` whose entries are
+/// walked by `$walk`, which has to implement [`Entries`] with `K` and `V`.
+/// The map needs a `size` field and `pop_first` and `pop_last` methods.
+///
+/// The map creates the iterators from their fields: `remaining`, `walk` and
+/// `_marker` for the entry iterators, `walk` and `_marker` for the range
+/// iterators, `entries` for the key and value adapters and `map` for the
+/// consuming iterator.
+macro_rules! map_iterators {
+    (
+        $map:ident walked by $walk:ty;
+        $iter:ident, $iter_mut:ident, $into_iter:ident,
+        $range:ident, $range_mut:ident,
+        $keys:ident, $values:ident, $values_mut:ident $(,)?
+    ) => {
+        #[doc = concat!("Iterator over the entries of a [`", stringify!($map), "`] in key order.")]
+        pub struct $iter<'a, K, V> {
+            remaining: usize,
+            walk: $walk,
+            _marker: PhantomData<&'a (K, V)>,
+        }
+
+        impl<'a, K, V> Iterator for $iter<'a, K, V> {
+            type Item = (&'a K, &'a V);
+            fn next(&mut self) -> Option<(&'a K, &'a V)> {
+                let (key, value) = $crate::map_iter::Entries::next(&mut self.walk)?;
+                self.remaining -= 1;
+                Some(unsafe { (&*key, &*value) })
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.remaining, Some(self.remaining))
+            }
+        }
+
+        impl<'a, K, V> DoubleEndedIterator for $iter<'a, K, V> {
+            fn next_back(&mut self) -> Option<(&'a K, &'a V)> {
+                let (key, value) = $crate::map_iter::Entries::next_back(&mut self.walk)?;
+                self.remaining -= 1;
+                Some(unsafe { (&*key, &*value) })
+            }
+        }
+
+        impl<'a, K, V> ExactSizeIterator for $iter<'a, K, V> {}
+
+        impl<'a, K, V> FusedIterator for $iter<'a, K, V> {}
+
+        #[doc = concat!(
+            "Iterator over the entries of a [`", stringify!($map), "`] in key order, with mutable\n",
+            "values."
+        )]
+        pub struct $iter_mut<'a, K, V> {
+            remaining: usize,
+            walk: $walk,
+            _marker: PhantomData<&'a mut (K, V)>,
+        }
+
+        impl<'a, K, V> Iterator for $iter_mut<'a, K, V> {
+            type Item = (&'a K, &'a mut V);
+            fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
+                let (key, value) = $crate::map_iter::Entries::next(&mut self.walk)?;
+                self.remaining -= 1;
+                Some(unsafe { (&*key, &mut *value) })
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.remaining, Some(self.remaining))
+            }
+        }
+
+        impl<'a, K, V> DoubleEndedIterator for $iter_mut<'a, K, V> {
+            fn next_back(&mut self) -> Option<(&'a K, &'a mut V)> {
+                let (key, value) = $crate::map_iter::Entries::next_back(&mut self.walk)?;
+                self.remaining -= 1;
+                Some(unsafe { (&*key, &mut *value) })
+            }
+        }
+
+        impl<'a, K, V> ExactSizeIterator for $iter_mut<'a, K, V> {}
+
+        impl<'a, K, V> FusedIterator for $iter_mut<'a, K, V> {}
+
+        #[doc = concat!("Iterator moving the entries out of a [`", stringify!($map), "`] in key order.")]
+        pub struct $into_iter<K, V> {
+            map: $map<K, V>,
+        }
+
+        impl<K, V> Iterator for $into_iter<K, V> {
+            type Item = (K, V);
+            fn next(&mut self) -> Option<(K, V)> {
+                self.map.pop_first()
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.map.size, Some(self.map.size))
+            }
+        }
+
+        impl<K, V> DoubleEndedIterator for $into_iter<K, V> {
+            fn next_back(&mut self) -> Option<(K, V)> {
+                self.map.pop_last()
+            }
+        }
+
+        impl<K, V> ExactSizeIterator for $into_iter<K, V> {}
+
+        impl<K, V> FusedIterator for $into_iter<K, V> {}
+
+        #[doc = concat!(
+            "Iterator over the entries of a [`", stringify!($map), "`] whose keys lie in a range,\n",
+            "see [`", stringify!($map), "::range`]."
+        )]
+        pub struct $range<'a, K, V> {
+            walk: $walk,
+            _marker: PhantomData<&'a (K, V)>,
+        }
+
+        impl<'a, K, V> Iterator for $range<'a, K, V> {
+            type Item = (&'a K, &'a V);
+            fn next(&mut self) -> Option<(&'a K, &'a V)> {
+                let (key, value) = $crate::map_iter::Entries::next(&mut self.walk)?;
+                Some(unsafe { (&*key, &*value) })
+            }
+        }
+
+        impl<'a, K, V> DoubleEndedIterator for $range<'a, K, V> {
+            fn next_back(&mut self) -> Option<(&'a K, &'a V)> {
+                let (key, value) = $crate::map_iter::Entries::next_back(&mut self.walk)?;
+                Some(unsafe { (&*key, &*value) })
+            }
+        }
+
+        impl<'a, K, V> FusedIterator for $range<'a, K, V> {}
+
+        #[doc = concat!(
+            "Iterator over the entries of a [`", stringify!($map), "`] whose keys lie in a range,\n",
+            "with mutable values, see [`", stringify!($map), "::range_mut`]."
+        )]
+        pub struct $range_mut<'a, K, V> {
+            walk: $walk,
+            _marker: PhantomData<&'a mut (K, V)>,
+        }
+
+        impl<'a, K, V> Iterator for $range_mut<'a, K, V> {
+            type Item = (&'a K, &'a mut V);
+            fn next(&mut self) -> Option<(&'a K, &'a mut V)> {
+                let (key, value) = $crate::map_iter::Entries::next(&mut self.walk)?;
+                Some(unsafe { (&*key, &mut *value) })
+            }
+        }
+
+        impl<'a, K, V> DoubleEndedIterator for $range_mut<'a, K, V> {
+            fn next_back(&mut self) -> Option<(&'a K, &'a mut V)> {
+                let (key, value) = $crate::map_iter::Entries::next_back(&mut self.walk)?;
+                Some(unsafe { (&*key, &mut *value) })
+            }
+        }
+
+        impl<'a, K, V> FusedIterator for $range_mut<'a, K, V> {}
+
+        #[doc = concat!("Iterator over the keys of a [`", stringify!($map), "`] in order.")]
+        pub struct $keys<'a, K, V> {
+            entries: $iter<'a, K, V>,
+        }
+
+        impl<'a, K, V> Iterator for $keys<'a, K, V> {
+            type Item = &'a K;
+            fn next(&mut self) -> Option<&'a K> {
+                self.entries.next().map(|(key, _)| key)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.entries.size_hint()
+            }
+        }
+
+        impl<'a, K, V> DoubleEndedIterator for $keys<'a, K, V> {
+            fn next_back(&mut self) -> Option<&'a K> {
+                self.entries.next_back().map(|(key, _)| key)
+            }
+        }
+
+        impl<'a, K, V> ExactSizeIterator for $keys<'a, K, V> {}
+
+        impl<'a, K, V> FusedIterator for $keys<'a, K, V> {}
+
+        #[doc = concat!("Iterator over the values of a [`", stringify!($map), "`] in key order.")]
+        pub struct $values<'a, K, V> {
+            entries: $iter<'a, K, V>,
+        }
+
+        impl<'a, K, V> Iterator for $values<'a, K, V> {
+            type Item = &'a V;
+            fn next(&mut self) -> Option<&'a V> {
+                self.entries.next().map(|(_, value)| value)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.entries.size_hint()
+            }
+        }
+
+        impl<'a, K, V> DoubleEndedIterator for $values<'a, K, V> {
+            fn next_back(&mut self) -> Option<&'a V> {
+                self.entries.next_back().map(|(_, value)| value)
+            }
+        }
+
+        impl<'a, K, V> ExactSizeIterator for $values<'a, K, V> {}
+
+        impl<'a, K, V> FusedIterator for $values<'a, K, V> {}
+
+        #[doc = concat!(
+            "Iterator over mutable references to the values of a [`", stringify!($map), "`] in key\n",
+            "order."
+        )]
+        pub struct $values_mut<'a, K, V> {
+            entries: $iter_mut<'a, K, V>,
+        }
+
+        impl<'a, K, V> Iterator for $values_mut<'a, K, V> {
+            type Item = &'a mut V;
+            fn next(&mut self) -> Option<&'a mut V> {
+                self.entries.next().map(|(_, value)| value)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.entries.size_hint()
+            }
+        }
+
+        impl<'a, K, V> DoubleEndedIterator for $values_mut<'a, K, V> {
+            fn next_back(&mut self) -> Option<&'a mut V> {
+                self.entries.next_back().map(|(_, value)| value)
+            }
+        }
+
+        impl<'a, K, V> ExactSizeIterator for $values_mut<'a, K, V> {}
+
+        impl<'a, K, V> FusedIterator for $values_mut<'a, K, V> {}
+    };
+}
+
+pub(crate) use map_iterators;
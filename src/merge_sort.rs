@@ -0,0 +1,167 @@
+//! Stable merge sort over the nodes of a linked list, shared by
+//! [`LinkedList`] and [`ArenaList`]. The nodes are relinked in sorted order
+//! without moving any item, and the list keeps every node even if a
+//! comparison panics.
+//!
+//! [`LinkedList`]: crate::linked_list::LinkedList
+//! [`ArenaList`]: crate::arena_list::ArenaList
+
+/// A list as the sort sees it: a chain of nodes linked through `next`,
+/// ending in `NIL`. The `previous` links are only restored by `relink`.
+pub(crate) trait Chain {
+    type Item;
+    /// Handle of a node, a pointer or a slot index.
+    type Node: Copy + PartialEq;
+
+    const NIL: Self::Node;
+
+    fn size(&self) -> usize;
+
+    fn first(&self) -> Self::Node;
+
+    /// `node` has to be a node of the list, as for the other methods.
+    unsafe fn next(&self, node: Self::Node) -> Self::Node;
+
+    unsafe fn set_next(&mut self, node: Self::Node, next: Self::Node);
+
+    unsafe fn item(&self, node: Self::Node) -> &Self::Item;
+
+    /// Takes over the chain of nodes starting at `begin`, linked through
+    /// `next` only, and restores the `previous` links and the end.
+    fn relink(&mut self, begin: Self::Node);
+}
+
+/// Sorts the list in O(n log n) with runs of doubling length, merging them
+/// pairwise. A node goes before an earlier one only if `before` says so.
+pub(crate) fn sort<C, F>(chain: &mut C, before: &mut F)
+where
+    C: Chain,
+    F: FnMut(&C::Item, &C::Item) -> bool,
+{
+    let size = chain.size();
+    let mut sort = MergeSort::new(chain);
+    let mut width = 1;
+    while width < size {
+        let mut remaining = size;
+        while remaining > 0 {
+            let left_len = width.min(remaining);
+            let right_len = width.min(remaining - left_len);
+            unsafe { sort.merge(left_len, right_len, before) };
+            remaining -= left_len + right_len;
+        }
+        sort.next_pass();
+        width *= 2;
+    }
+}
+
+/// Merges the sorted first `left_len` nodes of the list with the sorted
+/// rest, in O(n).
+pub(crate) fn merge<C, F>(chain: &mut C, left_len: usize, before: &mut F)
+where
+    C: Chain,
+    F: FnMut(&C::Item, &C::Item) -> bool,
+{
+    let right_len = chain.size() - left_len;
+    unsafe { MergeSort::new(chain).merge(left_len, right_len, before) };
+}
+
+/// State of a sort. The nodes are split into three parts: the merged part
+/// from `begin` to `end`, the `left_len` nodes left of the run being merged
+/// from `left`, and the chain from `right` holding the rest of the other
+/// run and every node after it. Dropping the sort links the parts back into
+/// the list, whether the sort finished or a comparison panicked.
+struct MergeSort<'a, C: Chain> {
+    chain: &'a mut C,
+    begin: C::Node,
+    end: C::Node,
+    left: C::Node,
+    left_len: usize,
+    right: C::Node,
+}
+
+impl<'a, C: Chain> MergeSort<'a, C> {
+    fn new(chain: &'a mut C) -> MergeSort<'a, C> {
+        let right = chain.first();
+        MergeSort {
+            chain,
+            begin: C::NIL,
+            end: C::NIL,
+            left: C::NIL,
+            left_len: 0,
+            right,
+        }
+    }
+
+    unsafe fn push(&mut self, node: C::Node) {
+        if self.end == C::NIL {
+            self.begin = node;
+        } else {
+            self.chain.set_next(self.end, node);
+        }
+        self.end = node;
+    }
+
+    unsafe fn push_right(&mut self) {
+        let node = self.right;
+        self.right = self.chain.next(node);
+        self.push(node);
+    }
+
+    /// Merges the next `left_len` nodes with the `right_len` nodes after
+    /// them onto the merged part. A node of the right run goes first only
+    /// if `before` says it belongs before the node of the left run, which
+    /// keeps the merge stable. Both runs have to be sorted and that long.
+    unsafe fn merge<F>(&mut self, left_len: usize, mut right_len: usize, before: &mut F)
+    where
+        F: FnMut(&C::Item, &C::Item) -> bool,
+    {
+        self.left = self.right;
+        for _ in 0..left_len {
+            self.right = self.chain.next(self.right);
+        }
+        self.left_len = left_len;
+        while self.left_len > 0 {
+            // nothing changes before the comparison returns
+            if right_len > 0 && before(self.chain.item(self.right), self.chain.item(self.left)) {
+                right_len -= 1;
+                self.push_right();
+            } else {
+                let node = self.left;
+                self.left = self.chain.next(node);
+                self.left_len -= 1;
+                self.push(node);
+            }
+        }
+        for _ in 0..right_len {
+            self.push_right();
+        }
+    }
+
+    /// Makes the merged part the input of the next pass.
+    fn next_pass(&mut self) {
+        unsafe { self.chain.set_next(self.end, C::NIL) };
+        self.right = self.begin;
+        self.begin = C::NIL;
+        self.end = C::NIL;
+    }
+}
+
+impl<'a, C: Chain> Drop for MergeSort<'a, C> {
+    fn drop(&mut self) {
+        let mut rest = self.right;
+        if self.left_len > 0 {
+            let mut last = self.left;
+            for _ in 1..self.left_len {
+                last = unsafe { self.chain.next(last) };
+            }
+            unsafe { self.chain.set_next(last, rest) };
+            rest = self.left;
+        }
+        if self.end == C::NIL {
+            self.begin = rest;
+        } else {
+            unsafe { self.chain.set_next(self.end, rest) };
+        }
+        self.chain.relink(self.begin);
+    }
+}
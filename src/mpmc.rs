@@ -0,0 +1,259 @@
+//! Bounded multi-producer multi-consumer queue.
+//!
+//! Follows Dmitry Vyukov's bounded queue: every slot carries a stamp telling
+//! whether it is ready to be written or read in the current lap around the
+//! buffer, so producers and consumers only race on the head and tail
+//! positions and never take a lock.
+//!
+//! ```
+//! use data_structures::mpmc::Queue;
+//! use std::thread;
+//!
+//! let queue = Queue::new(8);
+//! thread::scope(|scope| {
+//!     for producer in 0..4 {
+//!         let queue = &queue;
+//!         scope.spawn(move || {
+//!             for item in 0..25 {
+//!                 queue.push(producer * 100 + item);
+//!             }
+//!         });
+//!     }
+//!     let total: i32 = (0..100).map(|_| queue.pop()).sum();
+//!     assert_eq!(total, (0..4).map(|p| p * 2500 + 300).sum());
+//! });
+//! ```
+
+use crate::array::Array;
+use crate::backoff::Backoff;
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::sync::atomic::{self, AtomicUsize, Ordering};
+
+/// A slot of the buffer together with its stamp.
+///
+/// The stamp equals the tail position when the slot is free for that push,
+/// and the position plus one once the pushed item can be popped.
+struct Slot<T> {
+    stamp: AtomicUsize,
+    item: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// Fixed-capacity queue shared by any number of producers and consumers.
+///
+/// Positions are made of a lap count in the high bits and a slot index in
+/// the low bits, so they can wrap around `usize` without confusing laps.
+pub struct Queue<T> {
+    /// Position of the next item to pop.
+    head: AtomicUsize,
+    /// Position of the next free slot.
+    tail: AtomicUsize,
+    slots: Array<Slot<T>>,
+    /// Value added to a position to move to the same slot in the next lap.
+    one_lap: usize,
+}
+
+// items move between threads, but every item is only accessed by one
+unsafe impl<T> Send for Queue<T> where T: Send {}
+
+unsafe impl<T> Sync for Queue<T> where T: Send {}
+
+impl<T> Queue<T> {
+    /// Creates a queue holding at most `capacity` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn new(capacity: usize) -> Queue<T> {
+        assert!(capacity > 0, "Queue capacity must not be 0");
+        let mut slots = Array::new(capacity);
+        for index in 0..capacity {
+            let slot = Slot {
+                stamp: AtomicUsize::new(index),
+                item: UnsafeCell::new(MaybeUninit::uninit()),
+            };
+            let _ = slots.set(index, slot);
+        }
+        Queue {
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            slots,
+            one_lap: (capacity + 1).next_power_of_two(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.size()
+    }
+
+    /// Number of items in the queue. Only a snapshot while the queue is
+    /// in use.
+    pub fn size(&self) -> usize {
+        loop {
+            // retry until both positions were read without the tail moving
+            let tail = self.tail.load(Ordering::SeqCst);
+            let head = self.head.load(Ordering::SeqCst);
+            if self.tail.load(Ordering::SeqCst) == tail {
+                return self.distance(head, tail);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.size() == self.capacity()
+    }
+
+    /// Pushes `item`, or hands it back if the queue is full.
+    pub fn try_push(&self, item: T) -> Result<(), T> {
+        let mut backoff = Backoff::new();
+        let mut tail = self.tail.load(Ordering::Relaxed);
+        loop {
+            let index = self.index(tail);
+            let slot = &self.slots[index];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+            if stamp == tail {
+                // the slot is free in this lap; claim it by moving the tail
+                let next = self.next(tail, index);
+                match self.tail.compare_exchange_weak(
+                    tail,
+                    next,
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        unsafe { (*slot.item.get()).write(item) };
+                        slot.stamp.store(tail.wrapping_add(1), Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => {
+                        tail = current;
+                        backoff.snooze();
+                    }
+                }
+            } else if stamp.wrapping_add(self.one_lap) == tail.wrapping_add(1) {
+                // the slot still holds the item pushed one lap ago
+                atomic::fence(Ordering::SeqCst);
+                let head = self.head.load(Ordering::Relaxed);
+                if head.wrapping_add(self.one_lap) == tail {
+                    return Err(item);
+                }
+                backoff.snooze();
+                tail = self.tail.load(Ordering::Relaxed);
+            } else {
+                // another producer got here first
+                backoff.snooze();
+                tail = self.tail.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Pops the oldest item, or returns `None` if the queue is empty.
+    pub fn try_pop(&self) -> Option<T> {
+        let mut backoff = Backoff::new();
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            let index = self.index(head);
+            let slot = &self.slots[index];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+            if stamp == head.wrapping_add(1) {
+                // the slot holds an item; claim it by moving the head
+                let next = self.next(head, index);
+                match self.head.compare_exchange_weak(
+                    head,
+                    next,
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        let item = unsafe { (*slot.item.get()).assume_init_read() };
+                        slot.stamp
+                            .store(head.wrapping_add(self.one_lap), Ordering::Release);
+                        return Some(item);
+                    }
+                    Err(current) => {
+                        head = current;
+                        backoff.snooze();
+                    }
+                }
+            } else if stamp == head {
+                // nothing has been pushed into the slot in this lap
+                atomic::fence(Ordering::SeqCst);
+                let tail = self.tail.load(Ordering::Relaxed);
+                if tail == head {
+                    return None;
+                }
+                backoff.snooze();
+                head = self.head.load(Ordering::Relaxed);
+            } else {
+                // another consumer got here first
+                backoff.snooze();
+                head = self.head.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Pushes `item`, waiting until a consumer makes room.
+    pub fn push(&self, mut item: T) {
+        let mut backoff = Backoff::new();
+        while let Err(rejected) = self.try_push(item) {
+            item = rejected;
+            backoff.snooze();
+        }
+    }
+
+    /// Pops the oldest item, waiting until a producer pushes one.
+    pub fn pop(&self) -> T {
+        let mut backoff = Backoff::new();
+        loop {
+            match self.try_pop() {
+                Some(item) => return item,
+                None => backoff.snooze(),
+            }
+        }
+    }
+
+    fn index(&self, position: usize) -> usize {
+        position & (self.one_lap - 1)
+    }
+
+    /// Position after `position`, which points at slot `index`.
+    fn next(&self, position: usize, index: usize) -> usize {
+        if index + 1 < self.capacity() {
+            position + 1
+        } else {
+            // start the next lap at slot 0
+            (position & !(self.one_lap - 1)).wrapping_add(self.one_lap)
+        }
+    }
+
+    fn distance(&self, head: usize, tail: usize) -> usize {
+        let head_index = self.index(head);
+        let tail_index = self.index(tail);
+        if head_index < tail_index {
+            tail_index - head_index
+        } else if head_index > tail_index {
+            self.capacity() - head_index + tail_index
+        } else if head == tail {
+            0
+        } else {
+            self.capacity()
+        }
+    }
+}
+
+impl<T> Drop for Queue<T> {
+    fn drop(&mut self) {
+        let head = *self.head.get_mut();
+        let tail = *self.tail.get_mut();
+        let first = self.index(head);
+        for offset in 0..self.distance(head, tail) {
+            let index = (first + offset) % self.capacity();
+            unsafe { ptr::drop_in_place((*self.slots[index].item.get()).as_mut_ptr()) };
+        }
+    }
+}
@@ -0,0 +1,724 @@
+//! Ordered map backed by a skip list.
+//!
+//! The entries form a sorted doubly linked list, and every node is also
+//! linked into a random number of express lanes above it: a node reaches
+//! level `l + 1` with probability one half if it reaches level `l`. Searches
+//! start in the top lane and drop a level whenever the next node would
+//! overshoot, which takes O(log n) steps in expectation.
+//!
+//! The node heights come from a xorshift generator. Maps created with
+//! [`SkipList::with_seed`] always build the same lanes for the same
+//! operations, which keeps tests reproducible.
+//!
+//! ```
+//! use data_structures::SkipList;
+//!
+//! let mut map = SkipList::with_seed(7);
+//! map.extend((0..100).map(|key| (key, key * key)));
+//! assert_eq!(map.get(&7), Some(&49));
+//! assert!(map.range(10..13).map(|(key, _)| *key).eq(10..13));
+//! map.check_invariants().unwrap();
+//! ```
+
+use crate::array::Array;
+use crate::error::{Error, Result};
+use crate::map_iter::{map_iterators, Linked, Span};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::iter::{FromIterator, FusedIterator};
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Bound, Index, RangeBounds};
+use std::ptr;
+
+/// Most levels a node can reach.
+pub const MAX_LEVEL: usize = 32;
+
+struct SkipListNode<K, V> {
+    key: K,
+    value: V,
+    previous: *mut SkipListNode<K, V>,
+    /// Following node on each level the node reaches, null at the end.
+    next: Array<*mut SkipListNode<K, V>>,
+}
+
+/// Map from keys of type `K` to values of type `V`, sorted by key.
+pub struct SkipList<K, V> {
+    size: usize,
+    /// Number of levels at least one node reaches.
+    height: usize,
+    /// First node on each level, null where no node reaches.
+    head: [*mut SkipListNode<K, V>; MAX_LEVEL],
+    tail: *mut SkipListNode<K, V>,
+    /// State of the xorshift generator for node heights, never zero.
+    state: u64,
+    _marker: PhantomData<Box<SkipListNode<K, V>>>,
+}
+
+unsafe impl<K, V> Send for SkipList<K, V>
+where
+    K: Send,
+    V: Send,
+{
+}
+
+unsafe impl<K, V> Sync for SkipList<K, V>
+where
+    K: Sync,
+    V: Sync,
+{
+}
+
+impl<K, V> SkipListNode<K, V> {
+    fn height(&self) -> usize {
+        self.next.size()
+    }
+
+    fn next(&self, level: usize) -> *mut SkipListNode<K, V> {
+        *self.next.get(level).unwrap()
+    }
+}
+
+impl<K, V> Linked for SkipListNode<K, V> {
+    type Key = K;
+    type Value = V;
+
+    unsafe fn next(node: *mut Self) -> *mut Self {
+        (*node).next(0)
+    }
+
+    unsafe fn previous(node: *mut Self) -> *mut Self {
+        (*node).previous
+    }
+
+    unsafe fn entry(node: *mut Self) -> (*const K, *mut V) {
+        (ptr::addr_of!((*node).key), ptr::addr_of_mut!((*node).value))
+    }
+}
+
+map_iterators! {
+    SkipList walked by Span<SkipListNode<K, V>>;
+    SkipListIterator, SkipListIteratorMut, SkipListIntoIterator,
+    SkipListRange, SkipListRangeMut,
+    SkipListKeys, SkipListValues, SkipListValuesMut,
+}
+
+impl<K, V> Default for SkipList<K, V> {
+    fn default() -> Self {
+        SkipList::new()
+    }
+}
+
+impl<K, V> SkipList<K, V> {
+    /// Creates an empty map whose node heights are seeded randomly.
+    pub fn new() -> SkipList<K, V> {
+        SkipList::with_seed(RandomState::new().build_hasher().finish())
+    }
+
+    /// Creates an empty map whose node heights are drawn from a generator
+    /// seeded with `seed`, so the same operations build the same lanes.
+    pub fn with_seed(seed: u64) -> SkipList<K, V> {
+        SkipList {
+            size: 0,
+            height: 0,
+            head: [ptr::null_mut(); MAX_LEVEL],
+            tail: ptr::null_mut(),
+            // xorshift gets stuck at zero
+            state: if seed == 0 {
+                0x9e37_79b9_7f4a_7c15
+            } else {
+                seed
+            },
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Number of levels at least one node reaches, 0 for an empty map.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn clear(&mut self) {
+        let mut node = self.head[0];
+        self.head = [ptr::null_mut(); MAX_LEVEL];
+        self.tail = ptr::null_mut();
+        self.height = 0;
+        self.size = 0;
+        while !node.is_null() {
+            let item = unsafe { Box::from_raw(node) };
+            node = item.next(0);
+        }
+    }
+
+    /// Entry with the smallest key.
+    pub fn first(&self) -> Option<(&K, &V)> {
+        unsafe { self.head[0].as_ref() }.map(|node| (&node.key, &node.value))
+    }
+
+    /// Entry with the largest key.
+    pub fn last(&self) -> Option<(&K, &V)> {
+        unsafe { self.tail.as_ref() }.map(|node| (&node.key, &node.value))
+    }
+
+    /// Removes and returns the entry with the smallest key.
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        let node = self.head[0];
+        if node.is_null() {
+            return None;
+        }
+        // the first node is first on every level it reaches
+        Some(self.unlink(&[ptr::null_mut(); MAX_LEVEL], node))
+    }
+
+    /// Removes and returns the entry with the largest key.
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        let node = self.tail;
+        if node.is_null() {
+            return None;
+        }
+        let path = self.path(|next| next != node);
+        Some(self.unlink(&path, node))
+    }
+
+    pub fn iter(&self) -> SkipListIterator<'_, K, V> {
+        SkipListIterator {
+            remaining: self.size,
+            walk: self.full_span(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Iterates over the entries in key order, allowing to change the
+    /// values.
+    pub fn iter_mut(&mut self) -> SkipListIteratorMut<'_, K, V> {
+        SkipListIteratorMut {
+            remaining: self.size,
+            walk: self.full_span(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn keys(&self) -> SkipListKeys<'_, K, V> {
+        SkipListKeys {
+            entries: self.iter(),
+        }
+    }
+
+    pub fn values(&self) -> SkipListValues<'_, K, V> {
+        SkipListValues {
+            entries: self.iter(),
+        }
+    }
+
+    pub fn values_mut(&mut self) -> SkipListValuesMut<'_, K, V> {
+        SkipListValuesMut {
+            entries: self.iter_mut(),
+        }
+    }
+
+    fn full_span(&self) -> Span<SkipListNode<K, V>> {
+        Span {
+            front: self.head[0],
+            back: self.tail,
+        }
+    }
+
+    /// Node after `node` on `level`, where a null `node` stands for the head.
+    fn next_of(&self, node: *mut SkipListNode<K, V>, level: usize) -> *mut SkipListNode<K, V> {
+        if node.is_null() {
+            self.head[level]
+        } else {
+            unsafe { (*node).next(level) }
+        }
+    }
+
+    fn set_next(
+        &mut self,
+        node: *mut SkipListNode<K, V>,
+        level: usize,
+        next: *mut SkipListNode<K, V>,
+    ) {
+        if node.is_null() {
+            self.head[level] = next;
+        } else {
+            let _ = unsafe { (*node).next.set(level, next) };
+        }
+    }
+
+    /// Last node on each level for which `before` holds, null for the
+    /// head. `before` must hold for a prefix of the nodes.
+    fn path<F>(&self, before: F) -> [*mut SkipListNode<K, V>; MAX_LEVEL]
+    where
+        F: Fn(*mut SkipListNode<K, V>) -> bool,
+    {
+        let mut path = [ptr::null_mut(); MAX_LEVEL];
+        let mut node = ptr::null_mut();
+        for level in (0..self.height).rev() {
+            loop {
+                let next = self.next_of(node, level);
+                if next.is_null() || !before(next) {
+                    break;
+                }
+                node = next;
+            }
+            path[level] = node;
+        }
+        path
+    }
+
+    /// Height for a new node: every level is reached with half the
+    /// probability of the one below.
+    fn random_height(&mut self) -> usize {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        (self.state.trailing_ones() as usize + 1).min(MAX_LEVEL)
+    }
+
+    /// Links a new node in after the nodes on `path`.
+    fn link(&mut self, path: &[*mut SkipListNode<K, V>; MAX_LEVEL], key: K, value: V) {
+        let height = self.random_height();
+        let node = Box::into_raw(Box::new(SkipListNode {
+            key,
+            value,
+            previous: path[0],
+            next: Array::new(height),
+        }));
+        // levels above the current height are empty, so the path holds the
+        // head there
+        for (level, &before) in path.iter().enumerate().take(height) {
+            let _ = unsafe { (*node).next.set(level, self.next_of(before, level)) };
+            self.set_next(before, level, node);
+        }
+        self.height = self.height.max(height);
+        match unsafe { (*node).next(0).as_mut() } {
+            Some(next) => next.previous = node,
+            None => self.tail = node,
+        }
+        self.size += 1;
+    }
+
+    /// Takes `node` out of the lists, where `path` holds the node before it
+    /// on each level it reaches.
+    fn unlink(
+        &mut self,
+        path: &[*mut SkipListNode<K, V>; MAX_LEVEL],
+        node: *mut SkipListNode<K, V>,
+    ) -> (K, V) {
+        let node = unsafe { Box::from_raw(node) };
+        for (level, &before) in path.iter().enumerate().take(node.height()) {
+            self.set_next(before, level, node.next(level));
+        }
+        match unsafe { node.next(0).as_mut() } {
+            Some(next) => next.previous = node.previous,
+            None => self.tail = node.previous,
+        }
+        while self.height > 0 && self.head[self.height - 1].is_null() {
+            self.height -= 1;
+        }
+        self.size -= 1;
+        let SkipListNode { key, value, .. } = *node;
+        (key, value)
+    }
+}
+
+impl<K, V> SkipList<K, V>
+where
+    K: Ord,
+{
+    /// Inserts `value` under `key` and returns the value it replaced.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let path = self.path(|next| unsafe { (*next).key < key });
+        if let Some(node) = unsafe { self.next_of(path[0], 0).as_mut() } {
+            if node.key == key {
+                return Some(mem::replace(&mut node.value, value));
+            }
+        }
+        self.link(&path, key, value);
+        None
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let node = unsafe { &*self.find(key)? };
+        Some((&node.key, &node.value))
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let node = unsafe { &mut *self.find(key)? };
+        Some(&mut node.value)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Number of levels the node of `key` reaches, `None` if the key is
+    /// missing. Together with the iteration order this fixes the lanes.
+    pub fn node_height<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.find(key).map(|node| unsafe { (*node).height() })
+    }
+
+    /// Unlinks the node of `key` from every lane it reaches and returns its
+    /// value. The height shrinks if the node was alone on the top lanes.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let path = self.path(|next| unsafe { (*next).key.borrow() < key });
+        let node = self.next_of(path[0], 0);
+        if node.is_null() || unsafe { (*node).key.borrow() != key } {
+            return None;
+        }
+        Some(self.unlink(&path, node))
+    }
+
+    /// Entries with keys in `range`, in key order. Both ends are found by
+    /// dropping down the lanes, O(log n) expected, and the iterator then
+    /// follows the bottom lane. A reversed range yields nothing.
+    pub fn range<Q, R>(&self, range: R) -> SkipListRange<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        SkipListRange {
+            walk: self.range_span(range),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Like [`SkipList::range`], with mutable values.
+    pub fn range_mut<Q, R>(&mut self, range: R) -> SkipListRangeMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        SkipListRangeMut {
+            walk: self.range_span(range),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Checks the structure of the lists: that the bottom level holds every
+    /// entry in key order with matching back links, that each higher level
+    /// holds exactly the nodes reaching it in the same order, and the size
+    /// and height. Meant for tests; takes O(n * height).
+    pub fn check_invariants(&self) -> Result<()> {
+        let broken = |reason| Err(Error::BrokenInvariant { reason });
+        if self.height > MAX_LEVEL
+            || (self.height > 0 && self.head[self.height - 1].is_null())
+            || self.head[self.height..].iter().any(|node| !node.is_null())
+        {
+            return broken("the height does not match the levels in use");
+        }
+        let mut count = 0;
+        let mut previous: *mut SkipListNode<K, V> = ptr::null_mut();
+        let mut node = self.head[0];
+        while let Some(item) = unsafe { node.as_ref() } {
+            if item.height() == 0 || item.height() > self.height {
+                return broken("a node reaches no level or more than the height");
+            }
+            if (0..item.height()).any(|level| !item.next.is_initialized(level)) {
+                return broken("a node misses a link");
+            }
+            if item.previous != previous {
+                return broken("a back link does not point to the previous node");
+            }
+            if unsafe { previous.as_ref() }.is_some_and(|previous| previous.key >= item.key) {
+                return broken("keys are out of order");
+            }
+            count += 1;
+            previous = node;
+            node = item.next(0);
+        }
+        if previous != self.tail {
+            return broken("the tail is not the last node");
+        }
+        if count != self.size {
+            return broken("the size does not match the number of entries");
+        }
+        for level in 1..self.height {
+            // walk the level and the bottom level side by side
+            let mut expected = self.head[0];
+            let mut node = self.head[level];
+            loop {
+                while let Some(item) = unsafe { expected.as_ref() } {
+                    if item.height() > level {
+                        break;
+                    }
+                    expected = item.next(0);
+                }
+                if node != expected {
+                    return broken("a level skips a node reaching it or holds a lower one");
+                }
+                if node.is_null() {
+                    break;
+                }
+                node = unsafe { (*node).next(level) };
+                expected = unsafe { (*expected).next(0) };
+            }
+        }
+        Ok(())
+    }
+
+    fn find<Q>(&self, key: &Q) -> Option<*mut SkipListNode<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let path = self.path(|next| unsafe { (*next).key.borrow() < key });
+        let node = self.next_of(path[0], 0);
+        if node.is_null() || unsafe { (*node).key.borrow() != key } {
+            None
+        } else {
+            Some(node)
+        }
+    }
+
+    fn range_span<Q, R>(&self, range: R) -> Span<SkipListNode<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let before_start = self.path(|next| {
+            let key = unsafe { (*next).key.borrow() };
+            match range.start_bound() {
+                Bound::Included(start) => key < start,
+                Bound::Excluded(start) => key <= start,
+                Bound::Unbounded => false,
+            }
+        });
+        let up_to_end = self.path(|next| {
+            let key = unsafe { (*next).key.borrow() };
+            match range.end_bound() {
+                Bound::Included(end) => key <= end,
+                Bound::Excluded(end) => key < end,
+                Bound::Unbounded => true,
+            }
+        });
+        let front = self.next_of(before_start[0], 0);
+        let back = up_to_end[0];
+        if front.is_null() || back.is_null() || unsafe { (*front).key > (*back).key } {
+            Span::empty()
+        } else {
+            Span { front, back }
+        }
+    }
+}
+
+impl<K, V> Drop for SkipList<K, V> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<K, V> IntoIterator for SkipList<K, V> {
+    type Item = (K, V);
+    type IntoIter = SkipListIntoIterator<K, V>;
+
+    fn into_iter(self) -> SkipListIntoIterator<K, V> {
+        SkipListIntoIterator { map: self }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a SkipList<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = SkipListIterator<'a, K, V>;
+
+    fn into_iter(self) -> SkipListIterator<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut SkipList<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = SkipListIteratorMut<'a, K, V>;
+
+    fn into_iter(self) -> SkipListIteratorMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+/// `map[&key]` drops down the lanes like [`SkipList::get`] and panics if
+/// the bottom lane does not hold `key`.
+impl<K, Q, V> Index<&Q> for SkipList<K, V>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("Key does not exist in this map")
+    }
+}
+
+/// Clones keep the height of every node and the generator state, so no key
+/// is compared and both maps grow the same lanes afterwards.
+impl<K, V> Clone for SkipList<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        let mut copy = SkipList::with_seed(self.state);
+        // last copied node on each level, null while a level is empty
+        let mut last = [ptr::null_mut(); MAX_LEVEL];
+        let mut node = self.head[0];
+        while let Some(original) = unsafe { node.as_ref() } {
+            let item = Box::into_raw(Box::new(SkipListNode {
+                key: original.key.clone(),
+                value: original.value.clone(),
+                previous: copy.tail,
+                next: Array::new(original.height()),
+            }));
+            for (level, last) in last.iter_mut().enumerate().take(original.height()) {
+                let _ = unsafe { (*item).next.set(level, ptr::null_mut()) };
+                copy.set_next(*last, level, item);
+                *last = item;
+            }
+            copy.tail = item;
+            copy.size += 1;
+            node = original.next(0);
+        }
+        copy.height = self.height;
+        copy
+    }
+}
+
+impl<K, V> fmt::Debug for SkipList<K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Maps are equal if they hold the same entries, whatever their lanes.
+impl<K, V> PartialEq for SkipList<K, V>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &SkipList<K, V>) -> bool {
+        self.size == other.size && self.iter().eq(other.iter())
+    }
+}
+
+impl<K, V> Eq for SkipList<K, V>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<K, V> PartialOrd for SkipList<K, V>
+where
+    K: PartialOrd,
+    V: PartialOrd,
+{
+    fn partial_cmp(&self, other: &SkipList<K, V>) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<K, V> Ord for SkipList<K, V>
+where
+    K: Ord,
+    V: Ord,
+{
+    fn cmp(&self, other: &SkipList<K, V>) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<K, V> Hash for SkipList<K, V>
+where
+    K: Hash,
+    V: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        self.iter().for_each(|entry| entry.hash(state));
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for SkipList<K, V>
+where
+    K: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = SkipList::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V> Extend<(K, V)> for SkipList<K, V>
+where
+    K: Ord,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a, K, V> Extend<(&'a K, &'a V)> for SkipList<K, V>
+where
+    K: Ord + Copy + 'a,
+    V: Copy + 'a,
+{
+    fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().map(|(&key, &value)| (key, value)))
+    }
+}
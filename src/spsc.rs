@@ -0,0 +1,219 @@
+//! Bounded single-producer single-consumer queue.
+//!
+//! The queue is split into a [`Producer`] and a [`Consumer`] which can be
+//! moved to different threads. Neither side takes a lock: they only
+//! synchronize through the atomic head and tail positions.
+//!
+//! ```
+//! use data_structures::spsc::Queue;
+//! use std::thread;
+//!
+//! let mut queue = Queue::new(16);
+//! let (mut producer, mut consumer) = queue.split();
+//! thread::scope(|scope| {
+//!     scope.spawn(move || {
+//!         for item in 0..100 {
+//!             producer.push(item);
+//!         }
+//!     });
+//!     for item in 0..100 {
+//!         assert_eq!(consumer.pop(), item);
+//!     }
+//! });
+//! ```
+
+use crate::array::Array;
+use crate::backoff::Backoff;
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Fixed-capacity queue passing items from one thread to another.
+///
+/// Positions run from 0 to twice the capacity, so a full queue can be told
+/// apart from an empty one without wasting a slot.
+pub struct Queue<T> {
+    /// Position of the next item to pop, only written by the consumer.
+    head: AtomicUsize,
+    /// Position of the next free slot, only written by the producer.
+    tail: AtomicUsize,
+    slots: Array<UnsafeCell<MaybeUninit<T>>>,
+}
+
+/// Pushing half of a split [`Queue`].
+pub struct Producer<'a, T> {
+    queue: &'a Queue<T>,
+}
+
+/// Popping half of a split [`Queue`].
+pub struct Consumer<'a, T> {
+    queue: &'a Queue<T>,
+}
+
+// items only move from the producer's thread to the consumer's
+unsafe impl<T> Send for Queue<T> where T: Send {}
+
+unsafe impl<T> Sync for Queue<T> where T: Send {}
+
+impl<T> Queue<T> {
+    /// Creates a queue holding at most `capacity` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    pub fn new(capacity: usize) -> Queue<T> {
+        assert!(capacity > 0, "Queue capacity must not be 0");
+        let mut slots = Array::new(capacity);
+        for index in 0..capacity {
+            let _ = slots.set(index, UnsafeCell::new(MaybeUninit::uninit()));
+        }
+        Queue {
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            slots,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.size()
+    }
+
+    /// Number of items in the queue. Only a snapshot while the queue is
+    /// in use.
+    pub fn size(&self) -> usize {
+        loop {
+            // retry until both positions were read without the tail moving
+            let tail = self.tail.load(Ordering::Acquire);
+            let head = self.head.load(Ordering::Acquire);
+            if self.tail.load(Ordering::Acquire) == tail {
+                return self.distance(head, tail);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.size() == self.capacity()
+    }
+
+    /// Splits the queue into its producer and consumer. The queue stays
+    /// borrowed until both are gone, so only one of each can exist.
+    pub fn split(&mut self) -> (Producer<'_, T>, Consumer<'_, T>) {
+        let queue = &*self;
+        (Producer { queue }, Consumer { queue })
+    }
+
+    fn distance(&self, from: usize, to: usize) -> usize {
+        if to >= from {
+            to - from
+        } else {
+            to + 2 * self.capacity() - from
+        }
+    }
+
+    fn next(&self, position: usize) -> usize {
+        if position + 1 == 2 * self.capacity() {
+            0
+        } else {
+            position + 1
+        }
+    }
+
+    fn slot(&self, position: usize) -> *mut MaybeUninit<T> {
+        let index = if position >= self.capacity() {
+            position - self.capacity()
+        } else {
+            position
+        };
+        self.slots[index].get()
+    }
+
+    fn try_push(&self, item: T) -> Result<(), T> {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+        if self.distance(head, tail) == self.capacity() {
+            return Err(item);
+        }
+        // the consumer does not touch the slot until `tail` moves past it
+        unsafe { (*self.slot(tail)).write(item) };
+        self.tail.store(self.next(tail), Ordering::Release);
+        Ok(())
+    }
+
+    fn try_pop(&self) -> Option<T> {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        // the producer does not touch the slot until `head` moves past it
+        let item = unsafe { (*self.slot(head)).assume_init_read() };
+        self.head.store(self.next(head), Ordering::Release);
+        Some(item)
+    }
+}
+
+impl<T> Drop for Queue<T> {
+    fn drop(&mut self) {
+        let mut head = *self.head.get_mut();
+        let tail = *self.tail.get_mut();
+        while head != tail {
+            unsafe { ptr::drop_in_place((*self.slot(head)).as_mut_ptr()) };
+            head = self.next(head);
+        }
+    }
+}
+
+impl<'a, T> Producer<'a, T> {
+    /// Pushes `item`, or hands it back if the queue is full.
+    pub fn try_push(&mut self, item: T) -> Result<(), T> {
+        self.queue.try_push(item)
+    }
+
+    /// Pushes `item`, waiting until the consumer makes room.
+    pub fn push(&mut self, mut item: T) {
+        let mut backoff = Backoff::new();
+        while let Err(rejected) = self.queue.try_push(item) {
+            item = rejected;
+            backoff.snooze();
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+
+    pub fn size(&self) -> usize {
+        self.queue.size()
+    }
+}
+
+impl<'a, T> Consumer<'a, T> {
+    /// Pops the oldest item, or returns `None` if the queue is empty.
+    pub fn try_pop(&mut self) -> Option<T> {
+        self.queue.try_pop()
+    }
+
+    /// Pops the oldest item, waiting until the producer pushes one.
+    pub fn pop(&mut self) -> T {
+        let mut backoff = Backoff::new();
+        loop {
+            match self.queue.try_pop() {
+                Some(item) => return item,
+                None => backoff.snooze(),
+            }
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+
+    pub fn size(&self) -> usize {
+        self.queue.size()
+    }
+}
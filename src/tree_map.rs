@@ -0,0 +1,833 @@
+//! Ordered map backed by an AVL tree.
+//!
+//! Every node knows its parent, so iterators walk from node to node in
+//! order without keeping a stack, and the heights of the two subtrees of any
+//! node differ by at most one, which keeps lookups, inserts and removals at
+//! O(log n).
+
+use crate::error::{Error, Result};
+use crate::map_iter::{map_iterators, Linked, Span};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::{FromIterator, FusedIterator};
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Bound, Index, RangeBounds};
+use std::ptr;
+
+struct TreeMapNode<K, V> {
+    key: K,
+    value: V,
+    /// Height of the subtree rooted here, 1 for a leaf.
+    height: usize,
+    parent: *mut TreeMapNode<K, V>,
+    left: *mut TreeMapNode<K, V>,
+    right: *mut TreeMapNode<K, V>,
+}
+
+/// Map from keys of type `K` to values of type `V`, sorted by key.
+pub struct TreeMap<K, V> {
+    size: usize,
+    root: *mut TreeMapNode<K, V>,
+    _marker: PhantomData<Box<TreeMapNode<K, V>>>,
+}
+
+unsafe impl<K, V> Send for TreeMap<K, V>
+where
+    K: Send,
+    V: Send,
+{
+}
+
+unsafe impl<K, V> Sync for TreeMap<K, V>
+where
+    K: Sync,
+    V: Sync,
+{
+}
+
+impl<K, V> Linked for TreeMapNode<K, V> {
+    type Key = K;
+    type Value = V;
+
+    unsafe fn next(node: *mut Self) -> *mut Self {
+        successor(node)
+    }
+
+    unsafe fn previous(node: *mut Self) -> *mut Self {
+        predecessor(node)
+    }
+
+    unsafe fn entry(node: *mut Self) -> (*const K, *mut V) {
+        (ptr::addr_of!((*node).key), ptr::addr_of_mut!((*node).value))
+    }
+}
+
+map_iterators! {
+    TreeMap walked by Span<TreeMapNode<K, V>>;
+    TreeMapIterator, TreeMapIteratorMut, TreeMapIntoIterator,
+    TreeMapRange, TreeMapRangeMut,
+    TreeMapKeys, TreeMapValues, TreeMapValuesMut,
+}
+
+impl<K, V> Default for TreeMap<K, V> {
+    fn default() -> Self {
+        TreeMap::new()
+    }
+}
+
+impl<K, V> TreeMap<K, V> {
+    pub fn new() -> TreeMap<K, V> {
+        TreeMap {
+            size: 0,
+            root: ptr::null_mut(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Height of the tree, 0 if it is empty.
+    pub fn height(&self) -> usize {
+        unsafe { height(self.root) }
+    }
+
+    pub fn clear(&mut self) {
+        // rotate left children up until the node to free has none, so the
+        // nodes are freed in order without recursing
+        let mut node = mem::replace(&mut self.root, ptr::null_mut());
+        self.size = 0;
+        while !node.is_null() {
+            unsafe {
+                let left = (*node).left;
+                if left.is_null() {
+                    let right = (*node).right;
+                    drop(Box::from_raw(node));
+                    node = right;
+                } else {
+                    (*node).left = (*left).right;
+                    (*left).right = node;
+                    node = left;
+                }
+            }
+        }
+    }
+
+    /// Entry with the smallest key.
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.entry_at(self.first_node())
+    }
+
+    /// Entry with the largest key.
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.entry_at(self.last_node())
+    }
+
+    /// Removes and returns the entry with the smallest key.
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        let node = self.first_node();
+        if node.is_null() {
+            None
+        } else {
+            Some(self.unlink(node))
+        }
+    }
+
+    /// Removes and returns the entry with the largest key.
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        let node = self.last_node();
+        if node.is_null() {
+            None
+        } else {
+            Some(self.unlink(node))
+        }
+    }
+
+    pub fn iter(&self) -> TreeMapIterator<'_, K, V> {
+        TreeMapIterator {
+            remaining: self.size,
+            walk: self.full_span(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Iterates over the entries in key order, allowing to change the
+    /// values.
+    pub fn iter_mut(&mut self) -> TreeMapIteratorMut<'_, K, V> {
+        TreeMapIteratorMut {
+            remaining: self.size,
+            walk: self.full_span(),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn keys(&self) -> TreeMapKeys<'_, K, V> {
+        TreeMapKeys {
+            entries: self.iter(),
+        }
+    }
+
+    pub fn values(&self) -> TreeMapValues<'_, K, V> {
+        TreeMapValues {
+            entries: self.iter(),
+        }
+    }
+
+    pub fn values_mut(&mut self) -> TreeMapValuesMut<'_, K, V> {
+        TreeMapValuesMut {
+            entries: self.iter_mut(),
+        }
+    }
+
+    fn first_node(&self) -> *mut TreeMapNode<K, V> {
+        if self.root.is_null() {
+            self.root
+        } else {
+            unsafe { leftmost(self.root) }
+        }
+    }
+
+    fn last_node(&self) -> *mut TreeMapNode<K, V> {
+        if self.root.is_null() {
+            self.root
+        } else {
+            unsafe { rightmost(self.root) }
+        }
+    }
+
+    fn full_span(&self) -> Span<TreeMapNode<K, V>> {
+        Span {
+            front: self.first_node(),
+            back: self.last_node(),
+        }
+    }
+
+    fn entry_at(&self, node: *mut TreeMapNode<K, V>) -> Option<(&K, &V)> {
+        unsafe { node.as_ref().map(|node| (&node.key, &node.value)) }
+    }
+
+    /// Makes `new` take the place of `old` under `parent`, or at the root
+    /// if `parent` is null. Leaves the parent link of `new` alone.
+    fn replace_child(
+        &mut self,
+        parent: *mut TreeMapNode<K, V>,
+        old: *mut TreeMapNode<K, V>,
+        new: *mut TreeMapNode<K, V>,
+    ) {
+        unsafe {
+            if parent.is_null() {
+                self.root = new;
+            } else if (*parent).left == old {
+                (*parent).left = new;
+            } else {
+                (*parent).right = new;
+            }
+        }
+    }
+
+    /// Rotates the right child of `node` up into its place and returns it.
+    fn rotate_left(&mut self, node: *mut TreeMapNode<K, V>) -> *mut TreeMapNode<K, V> {
+        unsafe {
+            let pivot = (*node).right;
+            (*node).right = (*pivot).left;
+            if !(*pivot).left.is_null() {
+                (*(*pivot).left).parent = node;
+            }
+            (*pivot).parent = (*node).parent;
+            self.replace_child((*node).parent, node, pivot);
+            (*pivot).left = node;
+            (*node).parent = pivot;
+            update_height(node);
+            update_height(pivot);
+            pivot
+        }
+    }
+
+    /// Rotates the left child of `node` up into its place and returns it.
+    fn rotate_right(&mut self, node: *mut TreeMapNode<K, V>) -> *mut TreeMapNode<K, V> {
+        unsafe {
+            let pivot = (*node).left;
+            (*node).left = (*pivot).right;
+            if !(*pivot).right.is_null() {
+                (*(*pivot).right).parent = node;
+            }
+            (*pivot).parent = (*node).parent;
+            self.replace_child((*node).parent, node, pivot);
+            (*pivot).right = node;
+            (*node).parent = pivot;
+            update_height(node);
+            update_height(pivot);
+            pivot
+        }
+    }
+
+    /// Fixes the heights from `node` up to the root, rotating wherever the
+    /// subtrees of a node differ in height by two.
+    fn rebalance(&mut self, mut node: *mut TreeMapNode<K, V>) {
+        while !node.is_null() {
+            unsafe {
+                update_height(node);
+                let left = (*node).left;
+                let right = (*node).right;
+                if height(left) > height(right) + 1 {
+                    if height((*left).left) < height((*left).right) {
+                        self.rotate_left(left);
+                    }
+                    node = self.rotate_right(node);
+                } else if height(right) > height(left) + 1 {
+                    if height((*right).right) < height((*right).left) {
+                        self.rotate_right(right);
+                    }
+                    node = self.rotate_left(node);
+                }
+                node = (*node).parent;
+            }
+        }
+    }
+
+    /// Removes `node` from the tree, frees it and returns its entry.
+    fn unlink(&mut self, node: *mut TreeMapNode<K, V>) -> (K, V) {
+        unsafe {
+            let mut node = node;
+            if !(*node).left.is_null() && !(*node).right.is_null() {
+                // move the entry into the next node, which has no left child,
+                // and remove that node instead
+                let next = leftmost((*node).right);
+                mem::swap(&mut (*node).key, &mut (*next).key);
+                mem::swap(&mut (*node).value, &mut (*next).value);
+                node = next;
+            }
+            let child = if (*node).left.is_null() {
+                (*node).right
+            } else {
+                (*node).left
+            };
+            let parent = (*node).parent;
+            if !child.is_null() {
+                (*child).parent = parent;
+            }
+            self.replace_child(parent, node, child);
+            self.rebalance(parent);
+            self.size -= 1;
+            let node = Box::from_raw(node);
+            (node.key, node.value)
+        }
+    }
+}
+
+impl<K, V> TreeMap<K, V>
+where
+    K: Ord,
+{
+    /// Inserts `value` under `key` and returns the value it replaced.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let mut parent = ptr::null_mut();
+        let mut node = self.root;
+        let mut ordering = Ordering::Equal;
+        while !node.is_null() {
+            unsafe {
+                ordering = key.cmp(&(*node).key);
+                parent = node;
+                node = match ordering {
+                    Ordering::Less => (*node).left,
+                    Ordering::Greater => (*node).right,
+                    Ordering::Equal => return Some(mem::replace(&mut (*node).value, value)),
+                };
+            }
+        }
+        let node = Box::into_raw(Box::new(TreeMapNode {
+            key,
+            value,
+            height: 1,
+            parent,
+            left: ptr::null_mut(),
+            right: ptr::null_mut(),
+        }));
+        unsafe {
+            if parent.is_null() {
+                self.root = node;
+            } else if ordering == Ordering::Less {
+                (*parent).left = node;
+            } else {
+                (*parent).right = node;
+            }
+        }
+        self.size += 1;
+        self.rebalance(parent);
+        None
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.entry_at(self.find(key))
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        unsafe { self.find(key).as_mut().map(|node| &mut node.value) }
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        !self.find(key).is_null()
+    }
+
+    /// Unlinks the node holding `key` and returns its value, rebalancing
+    /// the ancestors of the node on the way back to the root.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let node = self.find(key);
+        if node.is_null() {
+            None
+        } else {
+            Some(self.unlink(node))
+        }
+    }
+
+    /// Entry with the largest key less than or equal to `key`.
+    pub fn floor<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.entry_at(self.below(key, true))
+    }
+
+    /// Entry with the smallest key greater than or equal to `key`.
+    pub fn ceiling<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.entry_at(self.above(key, true))
+    }
+
+    /// Entries with keys in `range`, in key order. Two descents find the
+    /// first and the last node in O(log n), and the iterator follows the
+    /// parent links between them. A reversed range yields nothing.
+    pub fn range<Q, R>(&self, range: R) -> TreeMapRange<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        TreeMapRange {
+            walk: self.range_span(range),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Like [`TreeMap::range`], with mutable values.
+    pub fn range_mut<Q, R>(&mut self, range: R) -> TreeMapRangeMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        TreeMapRangeMut {
+            walk: self.range_span(range),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Checks the structure of the tree: parent links, stored heights, the
+    /// AVL balance, the key order and the size. Meant for tests; takes O(n).
+    pub fn check_invariants(&self) -> Result<()> {
+        unsafe {
+            if !self.root.is_null() && !(*self.root).parent.is_null() {
+                return Err(Error::BrokenInvariant {
+                    reason: "the root has a parent",
+                });
+            }
+            let (_, count) = check_subtree(self.root, None, None)?;
+            if count != self.size {
+                return Err(Error::BrokenInvariant {
+                    reason: "the size does not match the number of nodes",
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn find<Q>(&self, key: &Q) -> *mut TreeMapNode<K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut node = self.root;
+        while !node.is_null() {
+            unsafe {
+                node = match key.cmp((*node).key.borrow()) {
+                    Ordering::Less => (*node).left,
+                    Ordering::Greater => (*node).right,
+                    Ordering::Equal => return node,
+                };
+            }
+        }
+        node
+    }
+
+    /// Node with the largest key below `key`, or equal to it if `inclusive`.
+    fn below<Q>(&self, key: &Q, inclusive: bool) -> *mut TreeMapNode<K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut found = ptr::null_mut();
+        let mut node = self.root;
+        while !node.is_null() {
+            unsafe {
+                let ordering = (*node).key.borrow().cmp(key);
+                if ordering == Ordering::Less || inclusive && ordering == Ordering::Equal {
+                    found = node;
+                    node = (*node).right;
+                } else {
+                    node = (*node).left;
+                }
+            }
+        }
+        found
+    }
+
+    /// Node with the smallest key above `key`, or equal to it if
+    /// `inclusive`.
+    fn above<Q>(&self, key: &Q, inclusive: bool) -> *mut TreeMapNode<K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut found = ptr::null_mut();
+        let mut node = self.root;
+        while !node.is_null() {
+            unsafe {
+                let ordering = (*node).key.borrow().cmp(key);
+                if ordering == Ordering::Greater || inclusive && ordering == Ordering::Equal {
+                    found = node;
+                    node = (*node).left;
+                } else {
+                    node = (*node).right;
+                }
+            }
+        }
+        found
+    }
+
+    fn range_span<Q, R>(&self, range: R) -> Span<TreeMapNode<K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        let front = match range.start_bound() {
+            Bound::Included(start) => self.above(start, true),
+            Bound::Excluded(start) => self.above(start, false),
+            Bound::Unbounded => self.first_node(),
+        };
+        let back = match range.end_bound() {
+            Bound::Included(end) => self.below(end, true),
+            Bound::Excluded(end) => self.below(end, false),
+            Bound::Unbounded => self.last_node(),
+        };
+        if front.is_null() || back.is_null() || unsafe { (*front).key > (*back).key } {
+            return Span::empty();
+        }
+        Span { front, back }
+    }
+}
+
+unsafe fn height<K, V>(node: *const TreeMapNode<K, V>) -> usize {
+    if node.is_null() {
+        0
+    } else {
+        (*node).height
+    }
+}
+
+unsafe fn update_height<K, V>(node: *mut TreeMapNode<K, V>) {
+    (*node).height = 1 + height((*node).left).max(height((*node).right));
+}
+
+unsafe fn leftmost<K, V>(mut node: *mut TreeMapNode<K, V>) -> *mut TreeMapNode<K, V> {
+    while !(*node).left.is_null() {
+        node = (*node).left;
+    }
+    node
+}
+
+unsafe fn rightmost<K, V>(mut node: *mut TreeMapNode<K, V>) -> *mut TreeMapNode<K, V> {
+    while !(*node).right.is_null() {
+        node = (*node).right;
+    }
+    node
+}
+
+/// Next node in key order, or null after the last one.
+unsafe fn successor<K, V>(mut node: *mut TreeMapNode<K, V>) -> *mut TreeMapNode<K, V> {
+    if !(*node).right.is_null() {
+        return leftmost((*node).right);
+    }
+    // climb until coming up from a left child
+    while !(*node).parent.is_null() && (*(*node).parent).right == node {
+        node = (*node).parent;
+    }
+    (*node).parent
+}
+
+/// Previous node in key order, or null before the first one.
+unsafe fn predecessor<K, V>(mut node: *mut TreeMapNode<K, V>) -> *mut TreeMapNode<K, V> {
+    if !(*node).left.is_null() {
+        return rightmost((*node).left);
+    }
+    while !(*node).parent.is_null() && (*(*node).parent).left == node {
+        node = (*node).parent;
+    }
+    (*node).parent
+}
+
+/// Checks the subtree under `node`, whose keys must lie strictly between
+/// `lower` and `upper`, and returns its height and number of nodes.
+unsafe fn check_subtree<K, V>(
+    node: *const TreeMapNode<K, V>,
+    lower: Option<&K>,
+    upper: Option<&K>,
+) -> Result<(usize, usize)>
+where
+    K: Ord,
+{
+    if node.is_null() {
+        return Ok((0, 0));
+    }
+    let node = &*node;
+    let broken = |reason| Err(Error::BrokenInvariant { reason });
+    if lower.is_some_and(|lower| node.key <= *lower)
+        || upper.is_some_and(|upper| node.key >= *upper)
+    {
+        return broken("keys are out of order");
+    }
+    for child in [node.left, node.right] {
+        if !child.is_null() && !ptr::eq((*child).parent, node) {
+            return broken("a child does not link back to its parent");
+        }
+    }
+    let (left_height, left_count) = check_subtree(node.left, lower, Some(&node.key))?;
+    let (right_height, right_count) = check_subtree(node.right, Some(&node.key), upper)?;
+    if node.height != 1 + left_height.max(right_height) {
+        return broken("a stored height is wrong");
+    }
+    if left_height.abs_diff(right_height) > 1 {
+        return broken("a node is out of balance");
+    }
+    Ok((node.height, left_count + right_count + 1))
+}
+
+impl<K, V> Drop for TreeMap<K, V> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<K, V> IntoIterator for TreeMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = TreeMapIntoIterator<K, V>;
+
+    fn into_iter(self) -> TreeMapIntoIterator<K, V> {
+        TreeMapIntoIterator { map: self }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a TreeMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = TreeMapIterator<'a, K, V>;
+
+    fn into_iter(self) -> TreeMapIterator<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut TreeMap<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = TreeMapIteratorMut<'a, K, V>;
+
+    fn into_iter(self) -> TreeMapIteratorMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+/// `map[&key]` descends like [`TreeMap::get`] and panics when it falls off
+/// the tree without meeting `key`.
+impl<K, Q, V> Index<&Q> for TreeMap<K, V>
+where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("Key does not exist in this map")
+    }
+}
+
+/// Clones keep the shape of the tree, so no key is compared.
+impl<K, V> Clone for TreeMap<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        TreeMap {
+            size: self.size,
+            root: unsafe { clone_subtree(self.root, ptr::null_mut()) },
+            _marker: PhantomData,
+        }
+    }
+}
+
+unsafe fn clone_subtree<K, V>(
+    node: *const TreeMapNode<K, V>,
+    parent: *mut TreeMapNode<K, V>,
+) -> *mut TreeMapNode<K, V>
+where
+    K: Clone,
+    V: Clone,
+{
+    if node.is_null() {
+        return ptr::null_mut();
+    }
+    let copy = Box::into_raw(Box::new(TreeMapNode {
+        key: (*node).key.clone(),
+        value: (*node).value.clone(),
+        height: (*node).height,
+        parent,
+        left: ptr::null_mut(),
+        right: ptr::null_mut(),
+    }));
+    (*copy).left = clone_subtree((*node).left, copy);
+    (*copy).right = clone_subtree((*node).right, copy);
+    copy
+}
+
+impl<K, V> fmt::Debug for TreeMap<K, V>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> PartialEq for TreeMap<K, V>
+where
+    K: PartialEq,
+    V: PartialEq,
+{
+    fn eq(&self, other: &TreeMap<K, V>) -> bool {
+        self.size == other.size && self.iter().eq(other.iter())
+    }
+}
+
+impl<K, V> Eq for TreeMap<K, V>
+where
+    K: Eq,
+    V: Eq,
+{
+}
+
+impl<K, V> PartialOrd for TreeMap<K, V>
+where
+    K: PartialOrd,
+    V: PartialOrd,
+{
+    fn partial_cmp(&self, other: &TreeMap<K, V>) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<K, V> Ord for TreeMap<K, V>
+where
+    K: Ord,
+    V: Ord,
+{
+    fn cmp(&self, other: &TreeMap<K, V>) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<K, V> Hash for TreeMap<K, V>
+where
+    K: Hash,
+    V: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        self.iter().for_each(|entry| entry.hash(state));
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for TreeMap<K, V>
+where
+    K: Ord,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = TreeMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V> Extend<(K, V)> for TreeMap<K, V>
+where
+    K: Ord,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<'a, K, V> Extend<(&'a K, &'a V)> for TreeMap<K, V>
+where
+    K: Ord + Copy + 'a,
+    V: Copy + 'a,
+{
+    fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().map(|(&key, &value)| (key, value)))
+    }
+}
@@ -0,0 +1,533 @@
+//! Tests for `ArenaList`. Random operation sequences are checked against
+//! `std::collections::VecDeque` and cursor edits against a `Vec`; the shared
+//! `List` scenarios live in `tests/list.rs`.
+
+mod common;
+
+use common::{Rng, Tracked, Tracker};
+use data_structures::{ArenaList, Error};
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::rc::Rc;
+
+/// Checks that walking backwards visits the same items as walking forwards.
+fn assert_linked<T: PartialEq + std::fmt::Debug>(list: &ArenaList<T>) {
+    let forward: Vec<_> = list.iter().collect();
+    let mut backward: Vec<_> = list.iter().rev().collect();
+    backward.reverse();
+    assert_eq!(forward, backward);
+    assert_eq!(forward.len(), list.size());
+}
+
+#[test]
+fn random_operations_match_vec_deque() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let mut list = ArenaList::new();
+    let mut expected = VecDeque::new();
+    for step in 0..5000 {
+        let index = rng.below(expected.len() as u64 + 1) as usize;
+        match rng.below(7) {
+            0 => {
+                list.add(step);
+                expected.push_back(step);
+            }
+            1 => {
+                list.push_front(step);
+                expected.push_front(step);
+            }
+            2 => {
+                list.insert(index, step).unwrap();
+                expected.insert(index, step);
+            }
+            3 => assert_eq!(list.pop_front(), expected.pop_front()),
+            4 => assert_eq!(list.pop_back(), expected.pop_back()),
+            5 => assert_eq!(list.remove(index).ok(), expected.remove(index)),
+            _ => {
+                if let Ok(item) = list.get_mut(index) {
+                    *item += 1;
+                    expected[index] += 1;
+                }
+            }
+        }
+        assert!(list.iter().eq(expected.iter()));
+    }
+    assert_linked(&list);
+    assert!(list.iter().rev().eq(expected.iter().rev()));
+}
+
+#[test]
+fn freed_slots_are_reused() {
+    let mut list = ArenaList::with_capacity(10);
+    assert_eq!(list.capacity(), 10);
+    for value in 0..10 {
+        list.add(value);
+    }
+    // churn through the list without ever holding more than 10 items
+    for value in 10..1000 {
+        list.pop_front();
+        list.add(value);
+        list.remove(5).unwrap();
+        list.insert(3, value).unwrap();
+    }
+    assert_eq!(list.capacity(), 10);
+    assert_eq!(list.size(), 10);
+    list.add(0);
+    assert_eq!(list.capacity(), 20);
+
+    let mut empty: ArenaList<u32> = ArenaList::new();
+    assert_eq!(empty.capacity(), 0);
+    empty.add(1);
+    assert_eq!(empty.capacity(), 4);
+    empty.clear();
+    assert_eq!(empty.capacity(), 0);
+}
+
+#[test]
+fn every_item_is_dropped_once() {
+    let drops = Rc::new(Cell::new(0));
+    let item = || Tracked(Rc::clone(&drops));
+    let mut list = ArenaList::new();
+    for _ in 0..10 {
+        list.add(item());
+    }
+    drop(list.remove(3).unwrap());
+    list.set(0, item()).unwrap();
+    assert_eq!(drops.get(), 2);
+    list.clear();
+    assert_eq!(drops.get(), 11);
+
+    list.extend((0..10).map(|_| item()));
+    let mut items = list.into_iter();
+    items.next();
+    items.next_back();
+    drop(items);
+    assert_eq!(drops.get(), 21);
+}
+
+#[test]
+fn append_split_off_and_splice_at() {
+    for size in 0..6 {
+        for index in 0..=size {
+            let mut list: ArenaList<u32> = (0..size).collect();
+            let mut tail = list.split_off(index as usize).unwrap();
+            assert!(list.iter().copied().eq(0..index));
+            assert!(tail.iter().copied().eq(index..size));
+            assert_linked(&list);
+            assert_linked(&tail);
+
+            list.append(&mut tail);
+            assert!(list.iter().copied().eq(0..size));
+            assert_eq!(tail.size(), 0);
+
+            let mut inserted: ArenaList<u32> = vec![10, 11].into_iter().collect();
+            list.splice_at(index as usize, &mut inserted).unwrap();
+            let mut expected: Vec<u32> = (0..size).collect();
+            expected.splice(index as usize..index as usize, vec![10, 11]);
+            assert!(list.iter().eq(expected.iter()));
+            assert_linked(&list);
+            assert_eq!(inserted.size(), 0);
+            inserted.add(1);
+            assert_linked(&inserted);
+        }
+    }
+    let mut list: ArenaList<u32> = (0..2).collect();
+    assert_eq!(
+        list.split_off(3).unwrap_err(),
+        Error::IndexOutOfBounds { index: 3, len: 2 }
+    );
+    assert_eq!(
+        list.splice_at(3, &mut ArenaList::new()),
+        Err(Error::IndexOutOfBounds { index: 3, len: 2 })
+    );
+}
+
+#[test]
+fn reverse_swaps_the_links() {
+    for size in 0..5 {
+        let mut list: ArenaList<i32> = (0..size).collect();
+        list.reverse();
+        assert!(list.iter().copied().eq((0..size).rev()));
+        assert_linked(&list);
+        list.push_front(-1);
+        list.add(size);
+        assert_eq!(list.get(0), Ok(&-1));
+        assert_eq!(list.get(list.size() - 1), Ok(&size));
+    }
+}
+
+#[test]
+fn sort_is_stable() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for size in 0..60 {
+        let items: Vec<(u32, u32)> = (0..size).map(|index| (rng.below(8), index)).collect();
+        let mut list: ArenaList<(u32, u32)> = items.iter().copied().collect();
+        // sort by key only, the second half of each pair checks stability
+        list.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut expected = items.clone();
+        expected.sort_by_key(|item| item.0);
+        assert!(list.iter().eq(expected.iter()));
+        assert_linked(&list);
+
+        list.sort();
+        expected.sort();
+        assert!(list.iter().eq(expected.iter()));
+        assert!(list.is_sorted());
+    }
+}
+
+#[test]
+fn sorted_insertion_and_merging() {
+    let mut rng = Rng(0x1234_5678_9abc_def1);
+    for round in 0..50 {
+        let mut left: Vec<(u32, char)> = (0..round % 9).map(|_| (rng.below(10), 'l')).collect();
+        let right: Vec<(u32, char)> = (0..round % 13).map(|_| (rng.below(10), 'r')).collect();
+        left.sort_unstable();
+        let mut list: ArenaList<_> = left.iter().copied().collect();
+        let mut other = ArenaList::new();
+        for item in right.iter() {
+            other.insert_sorted(*item);
+        }
+        assert!(other.is_sorted());
+
+        list.merge_sorted(&mut other);
+        // a stable sort of the concatenation puts equal keys from the left
+        // list first, like the merge does
+        let mut expected = left.clone();
+        let mut sorted_right = right.clone();
+        sorted_right.sort();
+        expected.extend(sorted_right);
+        expected.sort_by_key(|item| item.0);
+        assert!(list.iter().eq(expected.iter()));
+        assert_linked(&list);
+        assert_eq!(other.size(), 0);
+    }
+}
+
+/// Item counting its drops, whose comparison panics if either value is
+/// `FRAGILE`.
+struct Fragile {
+    value: u32,
+    _drops: Tracked,
+}
+
+const FRAGILE: u32 = 99;
+
+impl PartialEq for Fragile {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl PartialOrd for Fragile {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        assert!(
+            self.value != FRAGILE && other.value != FRAGILE,
+            "fragile item"
+        );
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+/// Checks that the list still holds every value of `expected`, in any
+/// order, with consistent links.
+fn assert_permutation(list: &ArenaList<Fragile>, expected: &[u32]) {
+    let values: Vec<u32> = list.iter().map(|item| item.value).collect();
+    assert!(list
+        .iter()
+        .rev()
+        .map(|item| item.value)
+        .eq(values.iter().rev().copied()));
+    let mut held = values;
+    held.sort_unstable();
+    let mut expected = expected.to_vec();
+    expected.sort_unstable();
+    assert_eq!(held, expected);
+}
+
+#[test]
+fn sort_by_keeps_every_item_when_compare_panics() {
+    let mut rng = Rng(0x0fed_cba9_8765_4321);
+    for size in [2, 3, 5, 8, 13, 33].iter().copied() {
+        let items: Vec<u32> = (0..size).map(|_| rng.below(10)).collect();
+        let mut sorted: ArenaList<u32> = items.iter().copied().collect();
+        let mut needed = 0;
+        sorted.sort_by(|a, b| {
+            needed += 1;
+            a.cmp(b)
+        });
+        // panic on every comparison the sort makes, one at a time
+        for limit in 0..needed {
+            let drops = Rc::new(Cell::new(0));
+            let mut list: ArenaList<Fragile> = items
+                .iter()
+                .map(|&value| Fragile {
+                    value,
+                    _drops: Tracked(Rc::clone(&drops)),
+                })
+                .collect();
+            let mut comparisons = 0;
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                list.sort_by(|a, b| {
+                    assert!(comparisons < limit, "comparison limit");
+                    comparisons += 1;
+                    a.value.cmp(&b.value)
+                })
+            }));
+            assert!(result.is_err());
+            assert_permutation(&list, &items);
+            assert_eq!(drops.get(), 0);
+            drop(list);
+            assert_eq!(drops.get(), items.len());
+        }
+    }
+}
+
+#[test]
+fn merge_sorted_keeps_every_item_when_compare_panics() {
+    let drops = Rc::new(Cell::new(0));
+    let item = |value| Fragile {
+        value,
+        _drops: Tracked(Rc::clone(&drops)),
+    };
+    let mut list: ArenaList<Fragile> = [1, 3, 5, 7].iter().map(|&value| item(value)).collect();
+    let mut other: ArenaList<Fragile> = [2, 4, FRAGILE, 6]
+        .iter()
+        .map(|&value| item(value))
+        .collect();
+    let result = catch_unwind(AssertUnwindSafe(|| list.merge_sorted(&mut other)));
+    assert!(result.is_err());
+    assert_permutation(&list, &[1, 2, 3, 4, 5, 6, 7, FRAGILE]);
+    assert_eq!(other.size(), 0);
+    drop(list);
+    drop(other);
+    assert_eq!(drops.get(), 8);
+}
+
+#[test]
+fn dedup_min_and_max() {
+    let mut list: ArenaList<u32> = vec![1, 1, 2, 3, 3, 3, 1, 4, 4].into_iter().collect();
+    assert_eq!(list.min_item(), Some(&1));
+    assert_eq!(list.max_item(), Some(&4));
+    // on an owned list `min` is still `Ord::min`, comparing whole lists
+    let shorter: ArenaList<u32> = vec![1, 1].into_iter().collect();
+    assert!(list.clone().min(shorter.clone()) == shorter);
+    list.dedup();
+    assert!(list.iter().copied().eq([1, 2, 3, 1, 4]));
+    list.dedup_by(|a, b| *a / 2 == *b / 2);
+    assert!(list.iter().copied().eq([1, 2, 1, 4]));
+    assert_linked(&list);
+    assert_eq!(list.remove_item(2), Ok(2));
+    assert_eq!(list.remove_item(2), Err(Error::NotFound));
+
+    // pushed to the front, so later items sit in lower slots
+    let mut list = ArenaList::new();
+    for &value in [4, 4, 1, 3, 3].iter() {
+        list.push_front(value);
+    }
+    list.dedup();
+    assert!(list.iter().copied().eq([3, 1, 4]));
+    assert_linked(&list);
+}
+
+#[test]
+fn cursor_edits_keep_ownership_intact() {
+    let tracker = Tracker::default();
+    let mut list = ArenaList::new();
+    for value in 0..4 {
+        list.add(tracker.item(value));
+    }
+
+    let mut back = {
+        let mut cursor = list.cursor_front();
+        cursor.move_next();
+        assert_eq!(cursor.remove_current().unwrap().value, 1);
+        cursor.insert_after(tracker.item(5));
+        cursor.split_after()
+    };
+    assert_eq!(tracker.drops(), 1);
+    assert!(list.iter().map(|item| item.value).eq([0, 2]));
+    assert!(back.iter().map(|item| item.value).eq([5, 3]));
+
+    back.add(tracker.item(6));
+    drop(list);
+    assert_eq!(tracker.drops(), 3);
+    drop(back);
+    assert_eq!(tracker.drops(), 6);
+}
+
+#[test]
+fn cursor_wraps_around_through_the_ghost() {
+    let mut list = numbers(3);
+    let mut cursor = list.cursor_front();
+    assert_eq!(cursor.index(), Some(0));
+
+    // backwards from the front onto the ghost, then on to the back
+    cursor.move_prev();
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.peek_next(), Some(&mut 0));
+    assert_eq!(cursor.peek_prev(), Some(&mut 2));
+    cursor.move_prev();
+    assert_eq!(cursor.index(), Some(2));
+    assert_eq!(cursor.current(), Some(&mut 2));
+    assert_eq!(cursor.peek_next(), None);
+    assert_eq!(cursor.peek_prev(), Some(&mut 1));
+
+    // forwards from the back onto the ghost, then on to the front
+    cursor.move_next();
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.current(), None);
+    cursor.move_next();
+    assert_eq!(cursor.index(), Some(0));
+    assert_eq!(cursor.peek_prev(), None);
+    assert_eq!(cursor.peek_next(), Some(&mut 1));
+
+    // inserting on the ghost adds to the ends of the list
+    cursor.move_prev();
+    cursor.insert_before(3);
+    cursor.insert_after(-1);
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.peek_next(), Some(&mut -1));
+    assert_eq!(cursor.peek_prev(), Some(&mut 3));
+    cursor.move_prev();
+    assert_eq!(cursor.index(), Some(4));
+    assert_eq!(cursor.current(), Some(&mut 3));
+    assert!(list.iter().copied().eq(-1..4));
+
+    let mut empty: ArenaList<i32> = ArenaList::new();
+    let mut cursor = empty.cursor_back();
+    assert_eq!(cursor.index(), None);
+    cursor.move_next();
+    assert_eq!(cursor.index(), None);
+    cursor.move_prev();
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.peek_next(), None);
+    assert_eq!(cursor.remove_current(), None);
+    assert_eq!(cursor.split_after().size(), 0);
+    assert_eq!(cursor.split_before().size(), 0);
+}
+
+#[test]
+fn cursor_edits_match_a_vec_model() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for round in 0..20 {
+        let mut list = numbers(round % 6);
+        let mut model: Vec<i32> = (0..round % 6).collect();
+        // position of the cursor in the model, `None` for the ghost
+        let mut position = if model.is_empty() { None } else { Some(0) };
+        let mut cursor = list.cursor_front();
+        for step in 0..200 {
+            let value = 100 + step;
+            match rng.below(10) {
+                0 | 1 => {
+                    cursor.move_next();
+                    position = match position {
+                        None if model.is_empty() => None,
+                        None => Some(0),
+                        Some(index) if index + 1 < model.len() => Some(index + 1),
+                        Some(_) => None,
+                    };
+                }
+                2 | 3 => {
+                    cursor.move_prev();
+                    position = match position {
+                        None => model.len().checked_sub(1),
+                        Some(index) => index.checked_sub(1),
+                    };
+                }
+                4 => {
+                    cursor.insert_before(value);
+                    match position {
+                        None => model.push(value),
+                        Some(index) => {
+                            model.insert(index, value);
+                            position = Some(index + 1);
+                        }
+                    }
+                }
+                5 => {
+                    cursor.insert_after(value);
+                    let index = position.map_or(0, |index| index + 1);
+                    model.insert(index, value);
+                }
+                6 | 7 => {
+                    let removed = position.map(|index| model.remove(index));
+                    assert_eq!(cursor.remove_current(), removed);
+                    position = position.filter(|&index| index < model.len());
+                }
+                8 => {
+                    let tail = match position {
+                        None => model.split_off(0),
+                        Some(index) => model.split_off(index + 1),
+                    };
+                    assert!(cursor.split_after().iter().eq(tail.iter()));
+                }
+                _ => {
+                    let head: Vec<i32> = match position {
+                        None => std::mem::take(&mut model),
+                        Some(index) => {
+                            position = Some(0);
+                            model.drain(..index).collect()
+                        }
+                    };
+                    assert!(cursor.split_before().iter().eq(head.iter()));
+                }
+            }
+            let previous = match position {
+                None => model.last(),
+                Some(index) => index.checked_sub(1).map(|index| &model[index]),
+            };
+            let next = match position {
+                None => model.first(),
+                Some(index) => model.get(index + 1),
+            };
+            assert_eq!(cursor.index(), position);
+            assert_eq!(
+                cursor.current().copied(),
+                position.map(|index| model[index])
+            );
+            assert_eq!(cursor.peek_prev().copied(), previous.copied());
+            assert_eq!(cursor.peek_next().copied(), next.copied());
+        }
+        assert!(list.iter().eq(model.iter()));
+        assert!(list.iter().rev().eq(model.iter().rev()));
+        assert_eq!(list.size(), model.len());
+    }
+}
+
+fn numbers(count: i32) -> ArenaList<i32> {
+    let mut list = ArenaList::new();
+    for value in 0..count {
+        list.add(value);
+    }
+    list
+}
+
+#[test]
+fn iter_mut_from_both_ends() {
+    let mut list: ArenaList<u32> = (0..5).collect();
+    let mut items = list.iter_mut();
+    *items.next().unwrap() += 10;
+    *items.next_back().unwrap() += 10;
+    assert_eq!(items.len(), 3);
+    for item in items {
+        *item *= 2;
+    }
+    assert!(list.iter().copied().eq([10, 2, 4, 6, 14]));
+}
+
+#[test]
+fn standard_traits() {
+    let list: ArenaList<u32> = vec![3, 1, 2].into_iter().collect();
+    assert_eq!(format!("{:?}", list), "[3, 1, 2]");
+    let copy = list.clone();
+    assert_eq!(copy, list);
+    assert_eq!(copy.capacity(), 4);
+    let mut other = ArenaList::new();
+    other.extend(&[3, 1]);
+    assert!(other < list);
+    other.add(2);
+    assert_eq!(other, list);
+}
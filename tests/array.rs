@@ -0,0 +1,281 @@
+//! Tests for `Array`. Items are counted as they are dropped, to check that
+//! every initialized slot is dropped exactly once whichever way it leaves the
+//! array. The indexing tests use an array with an empty slot, which only
+//! indexes touching that slot may panic on. The standard trait tests check
+//! that empty slots survive cloning and comparisons, and that full arrays
+//! hash like their slice.
+
+mod common;
+
+use common::Tracked;
+use data_structures::{Array, Error};
+use std::cell::Cell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::ops::Bound;
+use std::rc::Rc;
+
+#[test]
+fn drop_only_drops_filled_slots() {
+    let drops = Rc::new(Cell::new(0));
+    let mut array = Array::new(10);
+    for index in (0..10).step_by(3) {
+        array.set(index, Tracked(Rc::clone(&drops))).unwrap();
+    }
+    assert!(!array.is_full());
+    drop(array);
+    assert_eq!(drops.get(), 4);
+
+    let empty: Array<Tracked> = Array::new(5);
+    drop(empty);
+    assert_eq!(drops.get(), 4);
+}
+
+#[test]
+fn take_and_replace_hand_over_ownership() {
+    let drops = Rc::new(Cell::new(0));
+    let item = || Tracked(Rc::clone(&drops));
+    let mut array = Array::new(3);
+
+    assert_eq!(
+        array.take(1).unwrap_err(),
+        Error::Uninitialized { index: 1 }
+    );
+    assert!(array.replace(1, item()).unwrap().is_none());
+    assert_eq!(drops.get(), 0);
+
+    let old = array.replace(1, item()).unwrap().unwrap();
+    assert_eq!(drops.get(), 0);
+    drop(old);
+    assert_eq!(drops.get(), 1);
+
+    // set drops the item it overwrites
+    array.set(1, item()).unwrap();
+    assert_eq!(drops.get(), 2);
+
+    let taken = array.take(1).unwrap();
+    assert!(!array.is_initialized(1));
+    assert!(array.take(1).is_err());
+    drop(taken);
+    assert_eq!(drops.get(), 3);
+
+    assert_eq!(
+        array.replace(3, item()).unwrap_err(),
+        Error::IndexOutOfBounds { index: 3, len: 3 }
+    );
+    // the rejected item is dropped, not leaked
+    assert_eq!(drops.get(), 4);
+    drop(array);
+    assert_eq!(drops.get(), 4);
+}
+
+#[test]
+fn shrinking_resize_drops_the_cut_slots() {
+    let drops = Rc::new(Cell::new(0));
+    let mut array = Array::new(8);
+    for index in 0..8 {
+        if index != 6 {
+            array.set(index, Tracked(Rc::clone(&drops))).unwrap();
+        }
+    }
+    array.resize(5);
+    assert_eq!(array.size(), 5);
+    assert_eq!(drops.get(), 2);
+
+    array.resize(7);
+    assert!(!array.is_initialized(5));
+    assert!(!array.is_initialized(6));
+    assert_eq!(drops.get(), 2);
+
+    array.resize(0);
+    assert_eq!(drops.get(), 7);
+    drop(array);
+    assert_eq!(drops.get(), 7);
+}
+
+#[test]
+fn shift_drops_overwritten_items() {
+    let drops = Rc::new(Cell::new(0));
+    let mut array = Array::new(6);
+    for index in 0..6 {
+        array
+            .set(index, (index, Tracked(Rc::clone(&drops))))
+            .unwrap();
+    }
+    // slots 1 and 2 are overwritten, slots 4 and 5 are left empty
+    array.shift(3, 1, 3).unwrap();
+    assert_eq!(drops.get(), 2);
+    let moved: Vec<usize> = array.iter().map(|item| item.0).collect();
+    assert_eq!(moved, [0, 3, 4, 5]);
+    assert!(!array.is_initialized(4));
+    assert!(!array.is_initialized(5));
+
+    assert!(array.shift(0, 4, 3).is_err());
+    assert_eq!(drops.get(), 2);
+    drop(array);
+    assert_eq!(drops.get(), 6);
+}
+
+#[test]
+fn into_iter_drops_what_it_does_not_yield() {
+    let drops = Rc::new(Cell::new(0));
+    let mut array = Array::new(6);
+    for index in 1..6 {
+        array.set(index, Tracked(Rc::clone(&drops))).unwrap();
+    }
+    let mut items = array.into_iter();
+    drop(items.next());
+    drop(items.next_back());
+    assert_eq!(drops.get(), 2);
+    drop(items);
+    assert_eq!(drops.get(), 5);
+}
+
+/// Slots 0, 1, 2 and 4 hold their index, slot 3 is empty.
+fn with_gap() -> Array<usize> {
+    let mut array = Array::new(5);
+    for index in [0, 1, 2, 4].iter().copied() {
+        array.set(index, index).unwrap();
+    }
+    array
+}
+
+#[test]
+fn indexing_only_needs_the_indexed_slots() {
+    let mut array = with_gap();
+    assert_eq!(array[0], 0);
+    assert_eq!(array[4], 4);
+    array[1] += 10;
+    assert_eq!(array[1], 11);
+
+    assert_eq!(array[..3], [0, 11, 2]);
+    assert_eq!(array[1..=2], [11, 2]);
+    assert_eq!(array[4..], [4]);
+    assert_eq!(array[3..3], []);
+    array[..2].swap(0, 1);
+    assert_eq!(array.get_range(0..3), Ok(&[11, 0, 2][..]));
+
+    assert_eq!(
+        array.get_range(2..5),
+        Err(Error::Uninitialized { index: 3 })
+    );
+    assert_eq!(
+        array.get_range(4..6),
+        Err(Error::IndexOutOfBounds { index: 6, len: 5 })
+    );
+    assert_eq!(
+        array.get_range((Bound::Excluded(3), Bound::Included(2))),
+        Err(Error::InvalidRange { from: 4, to: 3 })
+    );
+    assert!(array.try_as_slice().is_err());
+
+    array.set(3, 3).unwrap();
+    assert_eq!(array[..], [11, 0, 2, 3, 4]);
+    assert_eq!(array.len(), 5);
+}
+
+#[test]
+#[should_panic(expected = "Slot 3 is not initialized")]
+fn indexing_an_empty_slot_panics() {
+    let array = with_gap();
+    let _ = array[3];
+}
+
+#[test]
+#[should_panic(expected = "Index out of bounds! Index 5, length 5")]
+fn indexing_past_the_end_panics() {
+    let mut array = with_gap();
+    array[5] = 5;
+}
+
+#[test]
+#[should_panic(expected = "Slot 3 is not initialized")]
+fn indexing_a_range_with_an_empty_slot_panics() {
+    let array = with_gap();
+    let _ = &array[2..];
+}
+
+#[test]
+#[should_panic(expected = "Slot 3 is not initialized")]
+fn slice_methods_panic_on_empty_slots() {
+    let array = with_gap();
+    let _ = array.len();
+}
+
+fn hash_of<H: Hash + ?Sized>(value: &H) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn full_arrays_hash_like_their_slice() {
+    let array: Array<u32> = vec![3, 1, 2].into_iter().collect();
+    assert!(array.is_full());
+    assert_eq!(hash_of(&array), hash_of(&[3, 1, 2][..]));
+    assert_eq!(hash_of(&array), hash_of(&vec![3, 1, 2]));
+    // Borrow<[T]> lets a std HashSet of arrays be queried with slices
+    let mut set = std::collections::HashSet::new();
+    set.insert(array);
+    assert!(set.contains(&[3, 1, 2][..]));
+
+    let empty: Array<u32> = Array::new(0);
+    assert_eq!(hash_of(&empty), hash_of(&[0u32; 0][..]));
+    assert_ne!(hash_of(&with_gap()), hash_of(&[0, 1, 2, 4][..]));
+}
+
+#[test]
+fn clone_keeps_the_empty_slots() {
+    let drops = Rc::new(Cell::new(0));
+    let mut array = Array::new(4);
+    array.set(1, Rc::new(Tracked(Rc::clone(&drops)))).unwrap();
+    array.set(3, Rc::new(Tracked(Rc::clone(&drops)))).unwrap();
+    let copy = array.clone();
+    assert_eq!(copy.size(), 4);
+    assert!(!copy.is_initialized(0));
+    assert!(!copy.is_initialized(2));
+    assert!(Rc::ptr_eq(&copy[1], &array[1]));
+    assert_eq!(Rc::strong_count(&array[3]), 2);
+
+    drop(array);
+    assert_eq!(drops.get(), 0);
+    drop(copy);
+    assert_eq!(drops.get(), 2);
+
+    let gap = with_gap();
+    assert_eq!(gap.clone(), gap);
+    assert_eq!(format!("{:?}", gap.clone()), "[0, 1, 2, <empty>, 4]");
+}
+
+#[test]
+fn equality_and_ordering_compare_slot_by_slot() {
+    let gap = with_gap();
+    let mut filled = with_gap();
+    filled.set(3, 3).unwrap();
+    assert_ne!(gap, filled);
+    // an empty slot orders before any item
+    assert!(gap < filled);
+    assert!(Array::<usize>::new(4) < gap);
+    filled.take(3).unwrap();
+    assert_eq!(gap, filled);
+    assert_eq!(gap.cmp(&filled), std::cmp::Ordering::Equal);
+    assert_ne!(Array::<u32>::new(2), Array::new(3));
+    assert_eq!(Array::<u32>::default(), Array::new(0));
+}
+
+#[test]
+fn extend_appends_filled_slots() {
+    let mut array = with_gap();
+    array.extend(vec![5, 6]);
+    assert_eq!(array.size(), 7);
+    assert_eq!(array[5..], [5, 6]);
+    assert!(!array.is_initialized(3));
+
+    array.extend(Vec::new());
+    assert_eq!(array.size(), 7);
+
+    let mut collected: Array<String> = (0..3).map(|index| index.to_string()).collect();
+    assert!(collected.is_full());
+    collected.extend(vec!["3".to_string()]);
+    assert_eq!(collected[..], ["0", "1", "2", "3"]);
+}
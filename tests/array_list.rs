@@ -0,0 +1,379 @@
+//! Tests for the `Vec`-compatible part of `ArrayList`. Every operation is
+//! run on an `ArrayList` and a `Vec` side by side and the results compared.
+//! The growth policy tests at the end check the capacities a list goes
+//! through.
+
+mod common;
+
+use common::Rng;
+use data_structures::{ArrayList, Error, GrowthPolicy};
+
+fn both(items: &[i32]) -> (ArrayList<i32>, Vec<i32>) {
+    (items.iter().copied().collect(), items.to_vec())
+}
+
+fn assert_same(list: &ArrayList<i32>, vec: &[i32]) {
+    assert_eq!(&list[..], vec);
+    assert_eq!(list.size(), vec.len());
+    assert!(list.capacity() >= list.size());
+}
+
+#[test]
+fn push_and_pop() {
+    let (mut list, mut vec) = both(&[]);
+    assert_eq!(list.pop(), vec.pop());
+    for value in 0..100 {
+        list.push(value);
+        vec.push(value);
+    }
+    assert_same(&list, &vec);
+    while !vec.is_empty() {
+        assert_eq!(list.pop(), vec.pop());
+        assert_same(&list, &vec);
+    }
+    assert_eq!(list.pop(), None);
+}
+
+#[test]
+fn with_capacity_reserve_and_shrink_to_fit() {
+    let mut list = ArrayList::with_capacity(0);
+    assert_eq!(list.capacity(), 0);
+    list.push(1);
+    assert!(list.capacity() >= 1);
+
+    let mut list: ArrayList<i32> = ArrayList::with_capacity(10);
+    assert_eq!(list.capacity(), 10);
+    list.extend(0..3);
+    list.reserve(50);
+    assert!(list.capacity() >= 53);
+    list.shrink_to_fit();
+    assert_eq!(list.capacity(), 3);
+    assert_same(&list, &[0, 1, 2]);
+    list.push(3);
+    assert_same(&list, &[0, 1, 2, 3]);
+}
+
+#[test]
+fn truncate() {
+    for len in 0..8 {
+        let (mut list, mut vec) = both(&[1, 2, 3, 4, 5]);
+        list.truncate(len);
+        vec.truncate(len);
+        assert_same(&list, &vec);
+    }
+}
+
+#[test]
+fn extend_from_slice_and_append() {
+    let (mut list, mut vec) = both(&[1, 2]);
+    list.extend_from_slice(&[3, 4, 5]);
+    vec.extend_from_slice(&[3, 4, 5]);
+    assert_same(&list, &vec);
+
+    let (mut other_list, mut other_vec) = both(&[6, 7]);
+    list.append(&mut other_list);
+    vec.append(&mut other_vec);
+    assert_same(&list, &vec);
+    assert_same(&other_list, &other_vec);
+    other_list.push(8);
+    assert_same(&other_list, &[8]);
+
+    // like Vec, the emptied list keeps its capacity
+    let mut list: ArrayList<i32> = ArrayList::new();
+    let mut other: ArrayList<i32> = ArrayList::with_capacity(50);
+    other.extend(0..40);
+    list.append(&mut other);
+    assert_same(&list, &(0..40).collect::<Vec<_>>());
+    assert_eq!(other.size(), 0);
+    assert_eq!(other.capacity(), 50);
+    other.extend(0..50);
+    assert_eq!(other.capacity(), 50);
+}
+
+#[test]
+fn split_off() {
+    for at in 0..=5 {
+        let (mut list, mut vec) = both(&[1, 2, 3, 4, 5]);
+        let tail = list.split_off(at).unwrap();
+        let vec_tail = vec.split_off(at);
+        assert_same(&list, &vec);
+        assert_same(&tail, &vec_tail);
+    }
+    let mut list: ArrayList<i32> = (0..3).collect();
+    assert_eq!(
+        list.split_off(4).unwrap_err(),
+        Error::IndexOutOfBounds { index: 4, len: 3 }
+    );
+}
+
+#[test]
+fn drain() {
+    let (mut list, mut vec) = both(&[1, 2, 3, 4, 5, 6]);
+    let drained: Vec<_> = list.drain(1..3).unwrap().collect();
+    assert_eq!(drained, vec.drain(1..3).collect::<Vec<_>>());
+    assert_same(&list, &vec);
+
+    let drained: Vec<_> = list.drain(..=1).unwrap().rev().collect();
+    assert_eq!(drained, vec.drain(..=1).rev().collect::<Vec<_>>());
+    assert_same(&list, &vec);
+
+    let drained: Vec<_> = list.drain(..).unwrap().collect();
+    assert_eq!(drained, std::mem::take(&mut vec));
+    assert_same(&list, &vec);
+}
+
+#[test]
+fn drain_rejects_bad_ranges() {
+    let mut list: ArrayList<i32> = (0..4).collect();
+    let (from, to) = (3, 2);
+    assert_eq!(
+        list.drain(from..to).err(),
+        Some(Error::InvalidRange { from: 3, to: 2 })
+    );
+    assert_eq!(
+        list.drain(2..=4).err(),
+        Some(Error::IndexOutOfBounds { index: 5, len: 4 })
+    );
+    assert_same(&list, &[0, 1, 2, 3]);
+}
+
+#[test]
+fn unfinished_drain_still_removes_the_range() {
+    let mut list: ArrayList<String> = ["a", "b", "c", "d"].iter().map(|s| s.to_string()).collect();
+    let mut drained = list.drain(1..3).unwrap();
+    assert_eq!(drained.next().unwrap(), "b");
+    drop(drained);
+    assert_eq!(list, ["a", "d"].iter().map(|s| s.to_string()).collect());
+}
+
+#[test]
+fn splice() {
+    let (mut list, mut vec) = both(&[1, 2, 3, 4, 5]);
+    let removed: Vec<_> = list.splice(1..3, vec![10, 11, 12]).unwrap().collect();
+    assert_eq!(
+        removed,
+        vec.splice(1..3, vec![10, 11, 12]).collect::<Vec<_>>()
+    );
+    assert_same(&list, &vec);
+
+    let removed: Vec<_> = list.splice(2.., None).unwrap().collect();
+    assert_eq!(removed, vec.splice(2.., None).collect::<Vec<_>>());
+    assert_same(&list, &vec);
+
+    let removed: Vec<_> = list.splice(..0, 20..30).unwrap().collect();
+    assert_eq!(removed, vec.splice(..0, 20..30).collect::<Vec<_>>());
+    assert_same(&list, &vec);
+}
+
+#[test]
+fn retain() {
+    let (mut list, mut vec) = both(&[1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    list.retain(|&item| item % 3 != 0);
+    vec.retain(|&item| item % 3 != 0);
+    assert_same(&list, &vec);
+    list.retain(|_| false);
+    vec.retain(|_| false);
+    assert_same(&list, &vec);
+}
+
+#[test]
+fn retain_survives_a_panic() {
+    let mut list: ArrayList<String> = (0..5).map(|i| i.to_string()).collect();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        list.retain(|item| {
+            if item == "3" {
+                panic!("stop");
+            }
+            item != "1"
+        })
+    }));
+    assert!(result.is_err());
+    list.push("x".to_string());
+    assert!(list.iter().all(|item| !item.is_empty()));
+}
+
+#[test]
+fn dedup() {
+    let (mut list, mut vec) = both(&[1, 1, 2, 3, 3, 3, 1, 4, 4]);
+    list.dedup();
+    vec.dedup();
+    assert_same(&list, &vec);
+
+    let (mut list, mut vec) = both(&[10, 11, 20, 25, 31, 12, 13]);
+    list.dedup_by_key(|item| *item / 10);
+    vec.dedup_by_key(|item| *item / 10);
+    assert_same(&list, &vec);
+
+    let (mut list, mut vec) = both(&[1, 2, 4, 3, 7, 8]);
+    list.dedup_by(|a, b| *a > *b);
+    vec.dedup_by(|a, b| *a > *b);
+    assert_same(&list, &vec);
+}
+
+#[test]
+fn swap_remove() {
+    let (mut list, mut vec) = both(&[1, 2, 3, 4, 5]);
+    for index in &[1, 3, 0, 1, 0] {
+        assert_eq!(list.swap_remove(*index).unwrap(), vec.swap_remove(*index));
+        assert_same(&list, &vec);
+    }
+    assert_eq!(
+        list.swap_remove(0).unwrap_err(),
+        Error::IndexOutOfBounds { index: 0, len: 0 }
+    );
+}
+
+#[test]
+fn contains() {
+    let (list, vec) = both(&[1, 2, 3]);
+    for value in 0..5 {
+        assert_eq!(list.contains(&value), vec.contains(&value));
+    }
+}
+
+#[test]
+fn random_operations_match_vec() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let (mut list, mut vec) = both(&[]);
+    for value in 0..5000 {
+        match rng.index(10) {
+            0..=2 => {
+                list.push(value);
+                vec.push(value);
+            }
+            3 => assert_eq!(list.pop(), vec.pop()),
+            4 if !vec.is_empty() => {
+                let index = rng.index(vec.len());
+                assert_eq!(list.swap_remove(index).unwrap(), vec.swap_remove(index));
+            }
+            5 => {
+                let from = rng.index(vec.len() + 1);
+                let to = from + rng.index(vec.len() - from + 1);
+                let removed: Vec<_> = list.drain(from..to).unwrap().collect();
+                assert_eq!(removed, vec.drain(from..to).collect::<Vec<_>>());
+            }
+            6 => {
+                let from = rng.index(vec.len() + 1);
+                let to = from + rng.index(vec.len() - from + 1);
+                let items = vec![value; rng.index(4)];
+                let removed: Vec<_> = list.splice(from..to, items.clone()).unwrap().collect();
+                assert_eq!(removed, vec.splice(from..to, items).collect::<Vec<_>>());
+            }
+            7 => {
+                let modulus = rng.index(5) as i32 + 2;
+                list.retain(|item| item % modulus != 0);
+                vec.retain(|item| item % modulus != 0);
+            }
+            8 => {
+                let items = vec![value; rng.index(6)];
+                list.extend_from_slice(&items);
+                vec.extend_from_slice(&items);
+                list.dedup();
+                vec.dedup();
+            }
+            _ => {
+                let len = rng.index(vec.len() + 2);
+                list.truncate(len);
+                vec.truncate(len);
+            }
+        }
+        assert_same(&list, &vec);
+    }
+}
+
+/// Capacities seen while pushing `count` items and then popping them all.
+fn capacities(policy: GrowthPolicy, count: i32) -> (Vec<usize>, Vec<usize>) {
+    let mut list = ArrayList::with_policy(policy);
+    let mut grown = vec![list.capacity()];
+    for value in 0..count {
+        list.push(value);
+        if grown.last() != Some(&list.capacity()) {
+            grown.push(list.capacity());
+        }
+    }
+    let mut shrunk = vec![list.capacity()];
+    while list.pop().is_some() {
+        if shrunk.last() != Some(&list.capacity()) {
+            shrunk.push(list.capacity());
+        }
+    }
+    (grown, shrunk)
+}
+
+#[test]
+fn default_policy_doubles_and_halves() {
+    let (grown, shrunk) = capacities(GrowthPolicy::default(), 32);
+    assert_eq!(grown, [4, 8, 16, 32]);
+    assert_eq!(shrunk, [32, 16, 8, 4]);
+}
+
+#[test]
+fn one_and_a_half_policy() {
+    let (grown, shrunk) = capacities(GrowthPolicy::one_and_a_half(), 20);
+    assert_eq!(grown, [4, 6, 9, 13, 19, 28]);
+    assert_eq!(shrunk, [28, 18, 12, 8, 5, 4]);
+}
+
+#[test]
+fn increment_policy() {
+    let (grown, shrunk) = capacities(GrowthPolicy::increment(10).with_initial_capacity(0), 25);
+    assert_eq!(grown, [0, 10, 20, 30]);
+    assert_eq!(shrunk, [30, 20, 10]);
+
+    let mut list: ArrayList<i32> = ArrayList::with_policy(GrowthPolicy::increment(10));
+    list.reserve(45);
+    assert_eq!(list.capacity(), 54);
+}
+
+#[test]
+fn never_shrink_policy() {
+    let (grown, shrunk) = capacities(GrowthPolicy::doubling().never_shrink(), 9);
+    assert_eq!(grown, [4, 8, 16]);
+    assert_eq!(shrunk, [16]);
+
+    let mut list = ArrayList::with_policy(GrowthPolicy::doubling().never_shrink());
+    list.extend(0..100);
+    list.retain(|_| false);
+    assert_eq!(list.capacity(), 128);
+    list.clear();
+    assert_eq!(list.capacity(), 4);
+}
+
+#[test]
+fn custom_policy() {
+    // grow to the next multiple of 8 and give back everything unused
+    let policy = GrowthPolicy::custom(|_, len| len.div_ceil(8) * 8).with_initial_capacity(0);
+    let (grown, shrunk) = capacities(policy, 17);
+    assert_eq!(grown, [0, 8, 16, 24]);
+    assert_eq!(shrunk, [24, 16, 8, 0]);
+
+    // results that are too small are raised to the number of items
+    let mut list = ArrayList::with_policy(GrowthPolicy::custom(|_, _| 0));
+    list.extend(0..10);
+    assert_eq!(&list[..], &(0..10).collect::<Vec<_>>()[..]);
+}
+
+#[test]
+fn policy_survives_clone_split_and_append() {
+    let policy = GrowthPolicy::increment(3).with_initial_capacity(3);
+    let mut list = ArrayList::with_policy(policy);
+    list.extend(0..5);
+    assert_eq!(list.capacity(), 6);
+
+    let mut copy = list.clone();
+    assert_eq!(copy.capacity(), 5);
+    copy.push(5);
+    assert_eq!(copy.capacity(), 8);
+
+    let mut tail = list.split_off(2).unwrap();
+    tail.extend(0..4);
+    assert_eq!(tail.capacity(), 9);
+
+    let mut other = ArrayList::with_policy(GrowthPolicy::increment(3).with_initial_capacity(3));
+    other.extend(0..2);
+    list.append(&mut other);
+    assert_eq!(other.capacity(), 3);
+    other.extend(0..4);
+    assert_eq!(other.capacity(), 6);
+    assert_eq!(other.policy().initial_capacity(), 3);
+}
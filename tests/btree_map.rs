@@ -0,0 +1,273 @@
+//! Tests for `BTreeMap`. The scenarios shared by all ordered maps live in
+//! `tests/common/ordered_map.rs` and run here for several branching factors.
+//! This file adds the B-tree specific checks: where nodes split and merge for
+//! the smallest branching factor, and `append` and `split_off` against
+//! `std::collections::BTreeMap`.
+
+mod common;
+
+use common::ordered_map::assert_same;
+use common::{ordered_map, Rng, Tracked};
+use data_structures::BTreeMap;
+use std::cell::Cell;
+use std::collections::BTreeMap as StdBTreeMap;
+use std::ops::Bound;
+use std::rc::Rc;
+
+const BRANCHING_FACTORS: [usize; 4] = [3, 4, 5, 16];
+
+/// Runs `scenario` once for every branching factor.
+fn for_each_branching_factor(scenario: impl Fn(&dyn Fn() -> BTreeMap<u32, u32>)) {
+    for &branching_factor in BRANCHING_FACTORS.iter() {
+        scenario(&|| BTreeMap::with_branching_factor(branching_factor));
+    }
+}
+
+fn random_map(rng: &mut Rng, branching_factor: usize, size: u32, range: u64) -> BTreeMap<u32, u32> {
+    let mut map = BTreeMap::with_branching_factor(branching_factor);
+    for step in 0..size {
+        map.insert(rng.below(range), step);
+    }
+    map
+}
+
+/// A map with the keys `0..size`, inserted in order.
+fn sequential(branching_factor: usize, size: u32) -> BTreeMap<u32, u32> {
+    let mut map = BTreeMap::with_branching_factor(branching_factor);
+    for key in 0..size {
+        map.insert(key, key);
+    }
+    map
+}
+
+#[test]
+fn new_map_is_empty() {
+    for_each_branching_factor(|new| ordered_map::new_map_is_empty(new));
+    let map: BTreeMap<u32, u32> = BTreeMap::new();
+    assert_eq!(map.branching_factor(), 16);
+    assert_eq!(map.height(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Branching factor must be at least 3")]
+fn branching_factor_below_three_panics() {
+    let _: BTreeMap<u32, u32> = BTreeMap::with_branching_factor(2);
+}
+
+#[test]
+fn random_operations_match_btree_map() {
+    for_each_branching_factor(|new| ordered_map::random_operations_match_std(new, |_| ()));
+}
+
+#[test]
+fn a_full_root_splits_on_the_next_insert() {
+    for &branching_factor in BRANCHING_FACTORS.iter() {
+        // a node holds at most b - 1 keys
+        let size = branching_factor as u32 - 1;
+        let mut map = sequential(branching_factor, size);
+        assert_eq!(map.height(), 1);
+        map.insert(size, size);
+        map.check_invariants().unwrap();
+        assert_eq!(map.height(), 2);
+    }
+}
+
+#[test]
+fn smallest_branching_factor_splits_and_merges_at_the_boundaries() {
+    // with b = 3 every node holds one or two keys, so sorted inserts split
+    // whenever the size reaches 2^h - 1 and the tree grows a level there
+    let mut map = BTreeMap::with_branching_factor(3);
+    let mut heights = Vec::new();
+    for key in 0..16 {
+        map.insert(key, key);
+        map.check_invariants().unwrap();
+        heights.push(map.height());
+    }
+    assert_eq!(heights, [1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 3, 3, 3, 3, 4, 4]);
+
+    // root [1] over [0] and [2]: taking a leaf's only key merges the leaves
+    let mut map = sequential(3, 3);
+    map.remove(&0);
+    map.check_invariants().unwrap();
+    assert_eq!(map.height(), 1);
+    assert!(map.keys().copied().eq([1, 2]));
+
+    // root [1] over [0] and [2, 3]: the sibling can spare a key, so the
+    // entries rotate and the height stays
+    let mut map = sequential(3, 4);
+    map.remove(&0);
+    map.check_invariants().unwrap();
+    assert_eq!(map.height(), 2);
+    assert!(map.keys().copied().eq([1, 2, 3]));
+}
+
+#[test]
+fn removing_any_key_keeps_the_tree_valid() {
+    for &branching_factor in BRANCHING_FACTORS.iter() {
+        for size in 0..=3 * branching_factor as u32 {
+            for key in 0..size {
+                let mut map = sequential(branching_factor, size);
+                assert_eq!(map.remove(&key), Some(key));
+                map.check_invariants().unwrap();
+                assert!(map
+                    .keys()
+                    .copied()
+                    .eq((0..size).filter(|&other| other != key)));
+            }
+        }
+    }
+}
+
+#[test]
+fn sorted_inserts_and_removes_keep_the_tree_shallow() {
+    for &branching_factor in BRANCHING_FACTORS.iter() {
+        let mut map = BTreeMap::with_branching_factor(branching_factor);
+        for key in 0..2000 {
+            map.insert(key, ());
+        }
+        map.check_invariants().unwrap();
+        // every node but the root has at least (b - 1) / 2 entries
+        let min_children = ((branching_factor - 1) / 2 + 1) as f64;
+        let max_height = |size: usize| ((size + 1) as f64).log(min_children) as usize + 2;
+        assert!(map.height() <= max_height(2000));
+        for key in (0..2000).rev().step_by(2) {
+            map.remove(&key);
+        }
+        map.check_invariants().unwrap();
+        assert!(map.height() <= max_height(1000));
+        assert!(map.keys().copied().eq((0..2000).step_by(2)));
+        while map.pop_first().is_some() {}
+        map.check_invariants().unwrap();
+        assert_eq!(map.height(), 1);
+    }
+}
+
+#[test]
+fn append_matches_btree_map() {
+    let mut rng = Rng(0x1234_5678_9abc_def1);
+    for round in 0..40 {
+        let branching_factor = BRANCHING_FACTORS[round % BRANCHING_FACTORS.len()];
+        let size = (round * 13 % 300) as u32;
+        let mut map = random_map(&mut rng, branching_factor, size, 400);
+        let mut other = random_map(&mut rng, 5, 300 - size, 400);
+        let mut expected: StdBTreeMap<u32, u32> = map.iter().map(|(&k, &v)| (k, v)).collect();
+        let mut expected_other: StdBTreeMap<u32, u32> =
+            other.iter().map(|(&k, &v)| (k, v)).collect();
+        map.append(&mut other);
+        expected.append(&mut expected_other);
+        assert_same(&map, &expected);
+        assert_eq!(map.branching_factor(), branching_factor);
+        assert!(other.is_empty());
+        other.check_invariants().unwrap();
+    }
+}
+
+#[test]
+fn split_off_matches_btree_map() {
+    let mut rng = Rng(0x0123_4567_89ab_cdef);
+    for round in 0..40 {
+        let branching_factor = BRANCHING_FACTORS[round % BRANCHING_FACTORS.len()];
+        let mut map = random_map(&mut rng, branching_factor, (round * 17 % 400) as u32, 500);
+        let mut expected: StdBTreeMap<u32, u32> = map.iter().map(|(&k, &v)| (k, v)).collect();
+        let key = rng.below(520);
+        let upper = map.split_off(&key);
+        let expected_upper = expected.split_off(&key);
+        assert_same(&map, &expected);
+        assert_same(&upper, &expected_upper);
+        assert_eq!(upper.branching_factor(), branching_factor);
+    }
+}
+
+#[test]
+fn splitting_at_any_key_keeps_both_trees_valid() {
+    let mut rng = Rng(0x0f1e_2d3c_4b5a_6978);
+    for &branching_factor in BRANCHING_FACTORS.iter() {
+        let size = 4 * branching_factor as u32 * branching_factor as u32;
+        for key in 0..=size {
+            let mut map = sequential(branching_factor, size);
+            for _ in 0..size / 3 {
+                map.remove(&rng.below(size as u64));
+            }
+            let expected: Vec<u32> = map.keys().copied().collect();
+            let upper = map.split_off(&key);
+            map.check_invariants().unwrap();
+            upper.check_invariants().unwrap();
+            let at = expected.partition_point(|&other| other < key);
+            assert!(map.keys().eq(expected[..at].iter()));
+            assert!(upper.keys().eq(expected[at..].iter()));
+        }
+    }
+}
+
+#[test]
+fn borrowed_lookups_and_index() {
+    let mut map = BTreeMap::with_branching_factor(3);
+    for word in ["d", "b", "a", "c", "e"].iter() {
+        map.insert(word.to_string(), word.len());
+    }
+    assert_eq!(map["a"], 1);
+    assert_eq!(map.get("b"), Some(&1));
+    assert!(map.contains_key("c"));
+    assert_eq!(map.get_key_value("d"), Some((&"d".to_string(), &1)));
+    let range = (Bound::Included("b"), Bound::Excluded("d"));
+    assert!(map
+        .range::<str, _>(range)
+        .map(|(key, _)| key)
+        .eq(["b", "c"]));
+    assert_eq!(map.remove_entry("a"), Some(("a".to_string(), 1)));
+    assert!(!map.contains_key("a"));
+    let upper = map.split_off("c");
+    assert!(upper.keys().eq(["c", "d", "e"]));
+}
+
+#[test]
+fn ranges_match_btree_map() {
+    for_each_branching_factor(|new| ordered_map::ranges_match_std(new));
+}
+
+#[test]
+#[should_panic(expected = "Key does not exist")]
+fn index_missing_key_panics() {
+    ordered_map::index_missing_key_panics(|| BTreeMap::with_branching_factor(3));
+}
+
+#[test]
+fn mutable_iterators() {
+    for_each_branching_factor(|new| ordered_map::mutable_iterators(new));
+}
+
+#[test]
+fn standard_traits() {
+    for &branching_factor in BRANCHING_FACTORS.iter() {
+        ordered_map::standard_traits(|| BTreeMap::with_branching_factor(branching_factor));
+    }
+}
+
+#[test]
+fn entries_are_dropped_once() {
+    for &branching_factor in BRANCHING_FACTORS.iter() {
+        ordered_map::entries_are_dropped_once(|| BTreeMap::with_branching_factor(branching_factor));
+    }
+}
+
+#[test]
+fn append_and_split_off_drop_every_entry_once() {
+    let drops = Rc::new(Cell::new(0));
+    let item = || Tracked(Rc::clone(&drops));
+    let mut map = BTreeMap::with_branching_factor(4);
+    for key in 0..100 {
+        map.insert(key, item());
+    }
+    let mut other = BTreeMap::with_branching_factor(4);
+    for key in 50..150 {
+        other.insert(key, item());
+    }
+    map.append(&mut other);
+    // the 50 overlapping keys take the values of `other`
+    assert_eq!(drops.get(), 50);
+    let upper = map.split_off(&75);
+    drop(upper);
+    assert_eq!(drops.get(), 125);
+    drop(map);
+    assert_eq!(drops.get(), 200);
+}
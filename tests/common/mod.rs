@@ -0,0 +1,84 @@
+//! Helpers shared by the integration tests. Each test file only uses some of
+//! them, hence the `dead_code` allowance.
+
+#![allow(dead_code)]
+
+pub mod ordered_map;
+
+use std::cell::Cell;
+use std::ops::Bound;
+use std::rc::Rc;
+
+/// Deterministic xorshift generator so failures can be reproduced.
+pub struct Rng(pub u64);
+
+impl Rng {
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A value in `0..bound`, for keys and items.
+    pub fn below(&mut self, bound: u64) -> u32 {
+        (self.next() % bound) as u32
+    }
+
+    /// A value in `0..bound`, for positions and lengths.
+    pub fn index(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    /// A random start or end bound for range queries over `0..range`.
+    pub fn bound(&mut self, range: u64) -> Bound<u32> {
+        match self.below(3) {
+            0 => Bound::Included(self.below(range)),
+            1 => Bound::Excluded(self.below(range)),
+            _ => Bound::Unbounded,
+        }
+    }
+}
+
+/// Counts how many times it has been dropped.
+#[derive(Debug)]
+pub struct Tracked(pub Rc<Cell<usize>>);
+
+impl Drop for Tracked {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+/// Hands out [`TrackedValue`]s and counts how many of them were dropped.
+#[derive(Default)]
+pub struct Tracker {
+    drops: Rc<Cell<usize>>,
+}
+
+impl Tracker {
+    pub fn item(&self, value: u32) -> TrackedValue {
+        TrackedValue {
+            value,
+            tracked: Tracked(Rc::clone(&self.drops)),
+        }
+    }
+
+    pub fn drops(&self) -> usize {
+        self.drops.get()
+    }
+}
+
+/// A [`Tracked`] carrying a value, for tests that need to tell the items
+/// apart. Compares by value only.
+#[derive(Debug)]
+pub struct TrackedValue {
+    pub value: u32,
+    tracked: Tracked,
+}
+
+impl PartialEq for TrackedValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
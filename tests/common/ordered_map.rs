@@ -0,0 +1,333 @@
+//! Scenarios every ordered map has to pass, written once against
+//! `OrderedMap` with `std::collections::BTreeMap` as the model. Each map's
+//! test file runs them on maps it creates, so settings such as the branching
+//! factor or the seed stay with that file.
+
+use super::{Rng, Tracked};
+use data_structures::{BTreeMap, Result, SkipList, TreeMap};
+use std::cell::Cell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap as Model;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::ops::{Bound, Index};
+use std::rc::Rc;
+
+type Entries<'a, V> = Box<dyn DoubleEndedIterator<Item = (&'a u32, &'a V)> + 'a>;
+type EntriesMut<'a, V> = Box<dyn DoubleEndedIterator<Item = (&'a u32, &'a mut V)> + 'a>;
+type Range = (Bound<u32>, Bound<u32>);
+
+const ALL: Range = (Bound::Unbounded, Bound::Unbounded);
+
+/// The part of the ordered map API the scenarios use, with `u32` keys.
+pub trait OrderedMap<V: 'static>: Sized {
+    fn size(&self) -> usize;
+    fn insert(&mut self, key: u32, value: V) -> Option<V>;
+    fn remove(&mut self, key: &u32) -> Option<V>;
+    fn get(&self, key: &u32) -> Option<&V>;
+    fn get_mut(&mut self, key: &u32) -> Option<&mut V>;
+    fn first(&self) -> Option<(&u32, &V)>;
+    fn last(&self) -> Option<(&u32, &V)>;
+    fn pop_first(&mut self) -> Option<(u32, V)>;
+    fn pop_last(&mut self) -> Option<(u32, V)>;
+    fn clear(&mut self);
+    fn check_invariants(&self) -> Result<()>;
+    fn entries(&self) -> Entries<'_, V>;
+    fn entries_mut(&mut self) -> EntriesMut<'_, V>;
+    fn values_mut(&mut self) -> Box<dyn DoubleEndedIterator<Item = &mut V> + '_>;
+    fn range(&self, range: Range) -> Entries<'_, V>;
+    fn range_mut(&mut self, range: Range) -> EntriesMut<'_, V>;
+    fn into_entries(self) -> Box<dyn DoubleEndedIterator<Item = (u32, V)>>;
+}
+
+macro_rules! ordered_map {
+    ($($map:ident),*) => {
+        $(
+            impl<V: 'static> OrderedMap<V> for $map<u32, V> {
+                fn size(&self) -> usize {
+                    $map::size(self)
+                }
+
+                fn insert(&mut self, key: u32, value: V) -> Option<V> {
+                    $map::insert(self, key, value)
+                }
+
+                fn remove(&mut self, key: &u32) -> Option<V> {
+                    $map::remove(self, key)
+                }
+
+                fn get(&self, key: &u32) -> Option<&V> {
+                    $map::get(self, key)
+                }
+
+                fn get_mut(&mut self, key: &u32) -> Option<&mut V> {
+                    $map::get_mut(self, key)
+                }
+
+                fn first(&self) -> Option<(&u32, &V)> {
+                    $map::first(self)
+                }
+
+                fn last(&self) -> Option<(&u32, &V)> {
+                    $map::last(self)
+                }
+
+                fn pop_first(&mut self) -> Option<(u32, V)> {
+                    $map::pop_first(self)
+                }
+
+                fn pop_last(&mut self) -> Option<(u32, V)> {
+                    $map::pop_last(self)
+                }
+
+                fn clear(&mut self) {
+                    $map::clear(self)
+                }
+
+                fn check_invariants(&self) -> Result<()> {
+                    $map::check_invariants(self)
+                }
+
+                fn entries(&self) -> Entries<'_, V> {
+                    Box::new($map::iter(self))
+                }
+
+                fn entries_mut(&mut self) -> EntriesMut<'_, V> {
+                    Box::new($map::iter_mut(self))
+                }
+
+                fn values_mut(&mut self) -> Box<dyn DoubleEndedIterator<Item = &mut V> + '_> {
+                    Box::new($map::values_mut(self))
+                }
+
+                fn range(&self, range: Range) -> Entries<'_, V> {
+                    Box::new($map::range(self, range))
+                }
+
+                fn range_mut(&mut self, range: Range) -> EntriesMut<'_, V> {
+                    Box::new($map::range_mut(self, range))
+                }
+
+                fn into_entries(self) -> Box<dyn DoubleEndedIterator<Item = (u32, V)>> {
+                    Box::new(self.into_iter())
+                }
+            }
+        )*
+    };
+}
+
+ordered_map!(TreeMap, BTreeMap, SkipList);
+
+/// Checks the invariants and compares every entry with the model, walking
+/// from both ends.
+pub fn assert_same<M: OrderedMap<u32>>(map: &M, expected: &Model<u32, u32>) {
+    map.check_invariants().unwrap();
+    assert_eq!(map.size(), expected.len());
+    assert!(map.entries().eq(expected.iter()));
+    assert!(map.entries().rev().eq(expected.iter().rev()));
+    assert_eq!(map.first(), expected.iter().next());
+    assert_eq!(map.last(), expected.iter().next_back());
+}
+
+pub fn new_map_is_empty<M: OrderedMap<u32>>(new: impl Fn() -> M) {
+    let mut map = new();
+    assert_eq!(map.size(), 0);
+    assert_eq!(map.first(), None);
+    assert_eq!(map.last(), None);
+    assert_eq!(map.pop_first(), None);
+    assert_eq!(map.pop_last(), None);
+    assert_eq!(map.get(&1), None);
+    assert_eq!(map.remove(&1), None);
+    assert_eq!(map.entries().next_back(), None);
+    assert_eq!(map.range(ALL).next(), None);
+    map.check_invariants().unwrap();
+}
+
+/// Runs random inserts, removes, pops and updates on a map and the model
+/// and compares them after every step. `check` gets the map after every
+/// step as well, for properties only that map has.
+pub fn random_operations_match_std<M, F>(new: impl Fn() -> M, check: F)
+where
+    M: OrderedMap<u32>,
+    F: Fn(&M),
+{
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let mut map = new();
+    let mut expected = Model::new();
+    for step in 0..4000 {
+        let key = rng.below(300);
+        match rng.below(8) {
+            0..=3 => assert_eq!(map.insert(key, step), expected.insert(key, step)),
+            4 | 5 => assert_eq!(map.remove(&key), expected.remove(&key)),
+            6 => {
+                if rng.below(2) == 0 {
+                    assert_eq!(map.pop_first(), expected.pop_first());
+                } else {
+                    assert_eq!(map.pop_last(), expected.pop_last());
+                }
+            }
+            _ => {
+                if let Some(value) = map.get_mut(&key) {
+                    *value += 1;
+                }
+                if let Some(value) = expected.get_mut(&key) {
+                    *value += 1;
+                }
+                assert_eq!(map.get(&key), expected.get(&key));
+            }
+        }
+        assert_same(&map, &expected);
+        check(&map);
+    }
+}
+
+/// Whether std rejects `range` because its start lies past its end.
+fn is_reversed(range: Range) -> bool {
+    match range {
+        (Bound::Included(start), Bound::Included(end)) => start > end,
+        (Bound::Included(start), Bound::Excluded(end))
+        | (Bound::Excluded(start), Bound::Included(end)) => start >= end,
+        (Bound::Excluded(start), Bound::Excluded(end)) => start >= end,
+        _ => false,
+    }
+}
+
+/// Compares random ranges over the even keys below 1000 with the model.
+pub fn ranges_match_std<M: OrderedMap<u32>>(new: impl Fn() -> M) {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    let mut map = new();
+    let mut expected = Model::new();
+    for _ in 0..200 {
+        let key = rng.below(500) * 2;
+        map.insert(key, key);
+        expected.insert(key, key);
+    }
+    for _ in 0..1000 {
+        let range = (rng.bound(1002), rng.bound(1002));
+        if is_reversed(range) {
+            // std panics on these, the maps yield nothing
+            assert_eq!(map.range(range).next(), None);
+            continue;
+        }
+        assert!(map.range(range).eq(expected.range(range)));
+        assert!(map.range(range).rev().eq(expected.range(range).rev()));
+        // walking from both ends must meet in the middle
+        let mut items = map.range(range);
+        let mut count = 0;
+        while items.next().is_some() {
+            count += 1;
+            if items.next_back().is_some() {
+                count += 1;
+            }
+        }
+        assert_eq!(count, expected.range(range).count());
+    }
+}
+
+pub fn mutable_iterators<M: OrderedMap<u32>>(new: impl Fn() -> M) {
+    let mut map = new();
+    for key in 0..10 {
+        map.insert(key, 0);
+    }
+    for (key, value) in map.range_mut((Bound::Included(3), Bound::Excluded(7))) {
+        *value = *key;
+    }
+    for value in map.values_mut().rev().take(2) {
+        *value = 100;
+    }
+    for (_, value) in map.entries_mut() {
+        *value += 1;
+    }
+    let values: Vec<u32> = map.entries().map(|(_, value)| *value).collect();
+    assert_eq!(values, [1, 1, 1, 4, 5, 6, 7, 1, 101, 101]);
+    map.check_invariants().unwrap();
+
+    let mut items = map.into_entries();
+    assert_eq!(items.next(), Some((0, 1)));
+    assert_eq!(items.next_back(), Some((9, 101)));
+    assert_eq!(items.count(), 8);
+}
+
+/// Checks Debug, Clone, comparisons, Hash and Extend.
+pub fn standard_traits<M>(new: impl Fn() -> M)
+where
+    M: OrderedMap<&'static str> + Clone + Debug + PartialEq + PartialOrd + Hash,
+    M: for<'a> Extend<(&'a u32, &'a &'static str)>,
+{
+    let mut map = new();
+    map.insert(2, "two");
+    map.insert(1, "one");
+    assert_eq!(format!("{:?}", map), r#"{1: "one", 2: "two"}"#);
+    let copy = map.clone();
+    copy.check_invariants().unwrap();
+    assert_eq!(copy, map);
+
+    let mut other = new();
+    other.extend(map.entries());
+    assert_eq!(other, map);
+    other.insert(3, "three");
+    assert!(map < other);
+    assert_ne!(map, other);
+
+    let hash = |map: &M| {
+        let mut hasher = DefaultHasher::new();
+        map.hash(&mut hasher);
+        hasher.finish()
+    };
+    assert_eq!(hash(&map), hash(&copy));
+    assert_ne!(hash(&map), hash(&other));
+
+    let mut big = new();
+    for key in 0..500 {
+        big.insert(key, "big");
+    }
+    let copy = big.clone();
+    copy.check_invariants().unwrap();
+    assert!(copy.entries().eq(big.entries()));
+}
+
+pub fn index_missing_key_panics<M>(new: impl Fn() -> M)
+where
+    M: OrderedMap<u32> + for<'a> Index<&'a u32, Output = u32>,
+{
+    let mut map = new();
+    map.insert(1, 10);
+    assert_eq!(map[&1], 10);
+    let _ = map[&3];
+}
+
+/// Checks that every value handed to the map is dropped exactly once,
+/// whichever way it leaves.
+pub fn entries_are_dropped_once<M: OrderedMap<Tracked>>(new: impl Fn() -> M) {
+    let drops = Rc::new(Cell::new(0));
+    let item = || Tracked(Rc::clone(&drops));
+    let mut map = new();
+    for key in 0..100 {
+        map.insert(key, item());
+    }
+    drop(map.insert(7, item()));
+    drop(map.remove(&8));
+    drop(map.pop_first());
+    drop(map.pop_last());
+    assert_eq!(drops.get(), 4);
+    map.clear();
+    assert_eq!(map.size(), 0);
+    assert_eq!(drops.get(), 101);
+
+    for key in 0..100 {
+        map.insert(key, item());
+    }
+    drop(map);
+    assert_eq!(drops.get(), 201);
+
+    let mut map = new();
+    for key in 0..100 {
+        map.insert(key, item());
+    }
+    let mut items = map.into_entries();
+    drop(items.next());
+    drop(items.next_back());
+    assert_eq!(drops.get(), 203);
+    drop(items);
+    assert_eq!(drops.get(), 301);
+}
@@ -0,0 +1,289 @@
+//! Tests for `Deque`. Most of them run the same operations on a `Deque` and
+//! a `std::collections::VecDeque` and compare the results, including after
+//! the items have wrapped around the end of the buffer.
+
+mod common;
+
+use common::Rng;
+use data_structures::{Deque, Error};
+use std::collections::VecDeque;
+use std::iter::FusedIterator;
+
+fn assert_same(deque: &Deque<i32>, expected: &VecDeque<i32>) {
+    assert_eq!(deque.size(), expected.len());
+    assert!(deque.iter().eq(expected.iter()));
+    assert!(deque.iter().rev().eq(expected.iter().rev()));
+    for (index, item) in expected.iter().enumerate() {
+        assert_eq!(deque.get(index), Ok(item));
+    }
+    let (first, second) = deque.as_slices();
+    assert_eq!(first.len() + second.len(), expected.len());
+    assert_eq!(deque.front(), expected.front());
+    assert_eq!(deque.back(), expected.back());
+}
+
+/// Deque of `0..count` whose items wrap around the end of the buffer.
+fn wrapped(count: i32) -> (Deque<i32>, VecDeque<i32>) {
+    let mut deque = Deque::with_capacity(count as usize + 2);
+    let mut expected = VecDeque::new();
+    for value in (0..count / 2).rev() {
+        deque.push_front(value);
+        expected.push_front(value);
+    }
+    for value in count / 2..count {
+        deque.push_back(value);
+        expected.push_back(value);
+    }
+    assert!(!deque.as_slices().1.is_empty() || count < 2);
+    (deque, expected)
+}
+
+#[test]
+fn new_deque_is_empty() {
+    let mut deque: Deque<i32> = Deque::new();
+    assert!(deque.is_empty());
+    assert_eq!(deque.pop_front(), None);
+    assert_eq!(deque.pop_back(), None);
+    assert_eq!(deque.front(), None);
+    assert_eq!(deque.back(), None);
+    assert_eq!(
+        deque.get(0),
+        Err(Error::IndexOutOfBounds { index: 0, len: 0 })
+    );
+}
+
+#[test]
+fn push_and_pop_at_both_ends() {
+    let mut deque = Deque::with_capacity(0);
+    let mut expected = VecDeque::new();
+    for value in 0..50 {
+        if value % 3 == 0 {
+            deque.push_front(value);
+            expected.push_front(value);
+        } else {
+            deque.push_back(value);
+            expected.push_back(value);
+        }
+        assert_same(&deque, &expected);
+    }
+    while !expected.is_empty() {
+        assert_eq!(deque.pop_front(), expected.pop_front());
+        assert_eq!(deque.pop_back(), expected.pop_back());
+        assert_same(&deque, &expected);
+    }
+}
+
+#[test]
+fn growing_keeps_wrapped_items_in_order() {
+    for count in 2..12 {
+        let (mut deque, mut expected) = wrapped(count);
+        for value in 100..120 {
+            deque.push_back(value);
+            expected.push_back(value);
+            assert_same(&deque, &expected);
+        }
+    }
+    for count in 2..12 {
+        let (mut deque, mut expected) = wrapped(count);
+        deque.reserve(100);
+        assert!(deque.capacity() >= count as usize + 100);
+        assert_same(&deque, &expected);
+        deque.push_front(-1);
+        expected.push_front(-1);
+        assert_same(&deque, &expected);
+    }
+}
+
+#[test]
+fn index_and_modify() {
+    let (mut deque, mut expected) = wrapped(8);
+    deque[3] = 30;
+    expected[3] = 30;
+    *deque.get_mut(7).unwrap() += 1;
+    expected[7] += 1;
+    *deque.front_mut().unwrap() = -5;
+    *expected.front_mut().unwrap() = -5;
+    *deque.back_mut().unwrap() = 50;
+    *expected.back_mut().unwrap() = 50;
+    for item in &mut deque {
+        *item *= 2;
+    }
+    for item in &mut expected {
+        *item *= 2;
+    }
+    deque.swap(0, 6).unwrap();
+    expected.swap(0, 6);
+    assert_same(&deque, &expected);
+    assert_eq!(deque[1], expected[1]);
+    assert_eq!(
+        deque.swap(0, 8),
+        Err(Error::IndexOutOfBounds { index: 8, len: 8 })
+    );
+}
+
+#[test]
+#[should_panic(expected = "Index out of bounds")]
+fn index_out_of_bounds_panics() {
+    let deque: Deque<i32> = (0..3).collect();
+    let _ = deque[3];
+}
+
+#[test]
+fn rotate() {
+    for count in 0..10 {
+        for by in 0..=count as usize {
+            let (mut deque, mut expected) = wrapped(count);
+            deque.rotate_left(by).unwrap();
+            expected.rotate_left(by);
+            assert_same(&deque, &expected);
+            deque.rotate_right(by).unwrap();
+            expected.rotate_right(by);
+            assert_same(&deque, &expected);
+        }
+    }
+    let mut full: Deque<i32> = Deque::with_capacity(4);
+    full.extend(0..4);
+    full.rotate_right(1).unwrap();
+    assert!(full.iter().eq([3, 0, 1, 2].iter()));
+    assert_eq!(
+        full.rotate_left(5),
+        Err(Error::IndexOutOfBounds { index: 5, len: 4 })
+    );
+}
+
+#[test]
+fn make_contiguous() {
+    for count in 0..10 {
+        let (mut deque, mut expected) = wrapped(count);
+        assert_eq!(deque.make_contiguous(), expected.make_contiguous());
+        assert!(deque.as_slices().1.is_empty());
+        assert_same(&deque, &expected);
+        deque.make_contiguous().sort_by(|a, b| b.cmp(a));
+        expected.make_contiguous().sort_by(|a, b| b.cmp(a));
+        assert_same(&deque, &expected);
+    }
+}
+
+#[test]
+fn shrink_to_fit_and_clear() {
+    let (mut deque, expected) = wrapped(9);
+    deque.shrink_to_fit();
+    assert_eq!(deque.capacity(), 9);
+    assert_same(&deque, &expected);
+
+    let mut deque: Deque<i32> = (0..6).collect();
+    deque.pop_front();
+    deque.pop_front();
+    deque.shrink_to_fit();
+    assert_eq!(deque.capacity(), 4);
+    assert!(deque.iter().eq([2, 3, 4, 5].iter()));
+
+    deque.clear();
+    assert!(deque.is_empty());
+    deque.push_back(1);
+    assert_eq!(deque.front(), Some(&1));
+}
+
+#[test]
+fn iterators_are_double_ended() {
+    let (deque, expected) = wrapped(7);
+    let mut items = deque.iter();
+    assert_eq!(items.len(), 7);
+    assert_eq!(items.next(), expected.front());
+    assert_eq!(items.next_back(), expected.back());
+    assert_eq!(items.len(), 5);
+
+    let mut items = deque.clone().into_iter();
+    assert_eq!(items.next_back(), Some(6));
+    assert_eq!(items.next(), Some(0));
+    assert_eq!(items.collect::<Vec<_>>(), [1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn iterators_are_fused() {
+    fn fused<I: FusedIterator>(items: I) -> I {
+        items
+    }
+    let (mut deque, _) = wrapped(3);
+    let mut items = fused(deque.iter());
+    assert_eq!(items.by_ref().count(), 3);
+    assert_eq!(items.next(), None);
+    assert_eq!(items.next_back(), None);
+
+    let mut items = fused(deque.iter_mut());
+    assert_eq!(items.by_ref().count(), 3);
+    assert_eq!(items.next(), None);
+
+    let mut items = fused(deque.into_iter());
+    assert_eq!(items.by_ref().count(), 3);
+    assert_eq!(items.next(), None);
+    assert_eq!(items.next_back(), None);
+}
+
+#[test]
+fn into_iter_drops_remaining_items() {
+    let mut deque = Deque::new();
+    for value in 0..10 {
+        deque.push_front(value.to_string());
+    }
+    let mut items = deque.into_iter();
+    assert_eq!(items.next().unwrap(), "9");
+    assert_eq!(items.next_back().unwrap(), "0");
+}
+
+#[test]
+fn standard_traits() {
+    let (deque, _) = wrapped(6);
+    let contiguous: Deque<i32> = (0..6).collect();
+    assert_eq!(deque, contiguous);
+    assert_eq!(format!("{:?}", deque), "[0, 1, 2, 3, 4, 5]");
+    assert_eq!(deque.clone(), deque);
+
+    let hash = |deque: &Deque<i32>| {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+        let mut hasher = DefaultHasher::new();
+        deque.hash(&mut hasher);
+        hasher.finish()
+    };
+    assert_eq!(hash(&deque), hash(&contiguous));
+    assert_ne!(hash(&deque), hash(&(1..6).collect()));
+
+    let mut bigger = contiguous.clone();
+    bigger.extend(&[1]);
+    assert!(deque < bigger);
+}
+
+#[test]
+fn random_operations_match_vec_deque() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    let mut deque = Deque::new();
+    let mut expected = VecDeque::new();
+    for value in 0..5000 {
+        match rng.index(8) {
+            0 | 1 => {
+                deque.push_back(value);
+                expected.push_back(value);
+            }
+            2 | 3 => {
+                deque.push_front(value);
+                expected.push_front(value);
+            }
+            4 => assert_eq!(deque.pop_back(), expected.pop_back()),
+            5 => assert_eq!(deque.pop_front(), expected.pop_front()),
+            6 => {
+                let by = rng.index(expected.len() + 1);
+                deque.rotate_left(by).unwrap();
+                expected.rotate_left(by);
+            }
+            _ => {
+                if rng.index(10) == 0 {
+                    deque.shrink_to_fit();
+                } else {
+                    deque.make_contiguous();
+                }
+            }
+        }
+        assert_same(&deque, &expected);
+    }
+}
@@ -0,0 +1,239 @@
+//! Tests for `HashMap`. Random operation sequences are checked against
+//! `std::collections::HashMap`, once with the default hasher and once with
+//! hashers that make keys collide so long probe sequences get exercised.
+
+mod common;
+
+use common::{Rng, Tracker};
+use data_structures::hash_map::Entry;
+use data_structures::HashMap;
+use std::collections::HashMap as StdHashMap;
+use std::hash::{BuildHasher, BuildHasherDefault, Hasher};
+
+/// Hashes every key to the same value.
+#[derive(Default)]
+struct ConstantHasher;
+
+impl Hasher for ConstantHasher {
+    fn finish(&self) -> u64 {
+        42
+    }
+
+    fn write(&mut self, _: &[u8]) {}
+}
+
+/// Keeps only the lowest bits of the key, so neighbours share buckets.
+#[derive(Default)]
+struct LowBitsHasher(u64);
+
+impl Hasher for LowBitsHasher {
+    fn finish(&self) -> u64 {
+        self.0 & 0b11
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 << 8) | u64::from(byte);
+        }
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.0 = u64::from(value);
+    }
+}
+
+fn assert_same<S: BuildHasher>(map: &HashMap<u32, u32, S>, expected: &StdHashMap<u32, u32>) {
+    assert_eq!(map.size(), expected.len());
+    assert!(map.capacity() >= map.size());
+    for (key, value) in expected {
+        assert_eq!(map.get(key), Some(value));
+    }
+    let mut entries: Vec<_> = map.iter().map(|(&key, &value)| (key, value)).collect();
+    entries.sort_unstable();
+    let mut expected: Vec<_> = expected.iter().map(|(&key, &value)| (key, value)).collect();
+    expected.sort_unstable();
+    assert_eq!(entries, expected);
+}
+
+fn random_operations<S: BuildHasher + Default>(seed: u64, keys: u64) {
+    let mut rng = Rng(seed);
+    let mut map: HashMap<u32, u32, S> = HashMap::default();
+    let mut expected = StdHashMap::new();
+    for step in 0..4000 {
+        let key = rng.below(keys);
+        match rng.below(8) {
+            0..=2 => assert_eq!(map.insert(key, step), expected.insert(key, step)),
+            3 | 4 => assert_eq!(map.remove(&key), expected.remove(&key)),
+            5 => {
+                *map.entry(key).or_insert(0) += step;
+                *expected.entry(key).or_insert(0) += step;
+            }
+            6 => {
+                let modulus = rng.below(5) + 2;
+                map.retain(|key, value| {
+                    *value += 1;
+                    key % modulus != 0
+                });
+                expected.retain(|key, value| {
+                    *value += 1;
+                    key % modulus != 0
+                });
+            }
+            _ => {
+                if rng.below(4) == 0 {
+                    map.shrink_to_fit();
+                }
+                assert_eq!(map.get(&key), expected.get(&key));
+                assert_eq!(map.contains_key(&key), expected.contains_key(&key));
+            }
+        }
+        assert_same(&map, &expected);
+    }
+}
+
+#[test]
+fn random_operations_match_std() {
+    random_operations::<std::collections::hash_map::RandomState>(0x2545_f491_4f6c_dd1d, 200);
+}
+
+#[test]
+fn random_operations_with_colliding_hashes() {
+    random_operations::<BuildHasherDefault<LowBitsHasher>>(0x9e37_79b9_7f4a_7c15, 100);
+    random_operations::<BuildHasherDefault<ConstantHasher>>(0x1234_5678_9abc_def1, 40);
+}
+
+#[test]
+fn new_map_is_empty_and_unallocated() {
+    let map: HashMap<u32, u32> = HashMap::new();
+    assert!(map.is_empty());
+    assert_eq!(map.capacity(), 0);
+    assert_eq!(map.get(&1), None);
+    assert_eq!(map.iter().next(), None);
+}
+
+#[test]
+fn grows_with_the_load_factor() {
+    let mut map = HashMap::new();
+    let mut capacities = vec![map.capacity()];
+    for key in 0..100 {
+        map.insert(key, key);
+        if capacities.last() != Some(&map.capacity()) {
+            capacities.push(map.capacity());
+        }
+    }
+    assert_eq!(capacities, [0, 7, 14, 28, 56, 112]);
+    map.retain(|key, _| *key < 5);
+    assert_eq!(map.capacity(), 112);
+    map.shrink_to_fit();
+    assert_eq!(map.capacity(), 7);
+    assert!((0..5).all(|key| map[&key] == key));
+
+    let map: HashMap<u32, u32> = HashMap::with_capacity(20);
+    assert_eq!(map.capacity(), 28);
+}
+
+#[test]
+fn borrowed_lookups() {
+    let mut map = HashMap::new();
+    map.insert("one".to_string(), 1);
+    map.insert("two".to_string(), 2);
+    assert_eq!(map.get("one"), Some(&1));
+    assert_eq!(map["two"], 2);
+    *map.get_mut("two").unwrap() += 10;
+    assert_eq!(map.get_key_value("two"), Some((&"two".to_string(), &12)));
+    assert_eq!(map.remove_entry("one"), Some(("one".to_string(), 1)));
+    assert!(!map.contains_key("one"));
+}
+
+#[test]
+#[should_panic(expected = "Key does not exist")]
+fn index_missing_key_panics() {
+    let map: HashMap<u32, u32> = HashMap::new();
+    let _ = map[&3];
+}
+
+#[test]
+fn entry_api() {
+    let mut map: HashMap<&str, u32> = HashMap::new();
+    for word in "a b a c b a".split(' ') {
+        map.entry(word).and_modify(|count| *count += 1).or_insert(1);
+    }
+    assert_eq!((map["a"], map["b"], map["c"]), (3, 2, 1));
+
+    match map.entry("a") {
+        Entry::Occupied(mut entry) => {
+            assert_eq!(entry.key(), &"a");
+            assert_eq!(entry.insert(10), 3);
+            assert_eq!(entry.remove(), 10);
+        }
+        Entry::Vacant(_) => panic!("a is in the map"),
+    }
+    match map.entry("d") {
+        Entry::Occupied(_) => panic!("d is not in the map"),
+        Entry::Vacant(entry) => {
+            assert_eq!(entry.key(), &"d");
+            *entry.insert(4) += 1;
+        }
+    }
+    assert_eq!(map.get("d"), Some(&5));
+    assert_eq!(map.get("a"), None);
+    *map.entry("e").or_default() += 7;
+    assert_eq!(map["e"], 7);
+    assert_eq!(map.entry("f").or_insert_with(|| 8), &8);
+    assert_eq!(map.size(), 5);
+}
+
+#[test]
+fn iterators() {
+    let mut map: HashMap<u32, u32> = (0..10).map(|key| (key, key * 10)).collect();
+    assert_eq!(map.iter().len(), 10);
+    assert_eq!(map.keys().sum::<u32>(), 45);
+    assert_eq!(map.values().sum::<u32>(), 450);
+    for value in map.values_mut() {
+        *value += 1;
+    }
+    for (key, value) in &mut map {
+        *value += key;
+    }
+    let mut entries: Vec<_> = map.into_iter().collect();
+    entries.sort_unstable();
+    assert_eq!(
+        entries,
+        (0..10).map(|key| (key, key * 11 + 1)).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn standard_traits() {
+    let map: HashMap<u32, &str> = vec![(1, "one"), (2, "two")].into_iter().collect();
+    let mut other = HashMap::new();
+    other.extend(map.iter().filter(|(&key, _)| key == 2));
+    other.extend(vec![(1, "one")]);
+    assert_eq!(map, other);
+    assert_eq!(map.clone(), map);
+    other.insert(3, "three");
+    assert_ne!(map, other);
+    let single: HashMap<u32, u32> = vec![(1, 2)].into_iter().collect();
+    assert_eq!(format!("{:?}", single), "{1: 2}");
+}
+
+#[test]
+fn every_value_is_dropped_once() {
+    let tracker = Tracker::default();
+    {
+        let mut map = HashMap::new();
+        for key in 0..50 {
+            map.insert(key, tracker.item(0));
+        }
+        assert_eq!(tracker.drops(), 0);
+        map.insert(0, tracker.item(0));
+        assert_eq!(tracker.drops(), 1);
+        drop(map.remove(&1));
+        map.retain(|key, _| key % 2 == 0);
+        assert_eq!(tracker.drops(), 26);
+        let mut items = map.into_iter();
+        drop(items.next());
+        assert_eq!(tracker.drops(), 27);
+    }
+    assert_eq!(tracker.drops(), 51);
+}
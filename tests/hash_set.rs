@@ -0,0 +1,157 @@
+//! Tests for `HashSet`. The set operations are checked against
+//! `std::collections::HashSet` on randomly filled sets.
+
+mod common;
+
+use common::Rng;
+use data_structures::HashSet;
+use std::collections::HashSet as StdHashSet;
+
+fn sorted<'a, I: Iterator<Item = &'a u32>>(items: I) -> Vec<u32> {
+    let mut items: Vec<u32> = items.copied().collect();
+    items.sort_unstable();
+    items
+}
+
+/// The same random set built twice, once as a `HashSet` and once as std's.
+fn random_set(rng: &mut Rng, size: u32, range: u64) -> (HashSet<u32>, StdHashSet<u32>) {
+    let items: Vec<u32> = (0..size).map(|_| rng.below(range)).collect();
+    (items.iter().copied().collect(), items.into_iter().collect())
+}
+
+#[test]
+fn insert_remove_and_contains() {
+    let mut set = HashSet::new();
+    assert!(set.is_empty());
+    assert!(set.insert(3));
+    assert!(set.insert(5));
+    assert!(!set.insert(3));
+    assert_eq!(set.size(), 2);
+    assert!(set.contains(&3));
+    assert!(!set.contains(&4));
+    assert!(set.remove(&3));
+    assert!(!set.remove(&3));
+    assert_eq!(set.take(&5), Some(5));
+    assert!(set.is_empty());
+}
+
+#[test]
+fn borrowed_lookups() {
+    let mut set = HashSet::new();
+    set.insert("one".to_string());
+    assert!(set.contains("one"));
+    assert_eq!(set.get("one").map(String::as_str), Some("one"));
+    assert_eq!(set.get("two"), None);
+    assert!(set.remove("one"));
+}
+
+#[test]
+fn random_operations_match_std() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let mut set = HashSet::new();
+    let mut expected = StdHashSet::new();
+    for _ in 0..3000 {
+        let item = rng.below(150);
+        match rng.below(4) {
+            0 | 1 => assert_eq!(set.insert(item), expected.insert(item)),
+            2 => assert_eq!(set.remove(&item), expected.remove(&item)),
+            _ => assert_eq!(set.contains(&item), expected.contains(&item)),
+        }
+        assert_eq!(set.size(), expected.len());
+    }
+    assert_eq!(sorted(set.iter()), sorted(expected.iter()));
+    set.retain(|item| item % 3 == 0);
+    expected.retain(|item| item % 3 == 0);
+    assert_eq!(
+        sorted(set.into_iter().collect::<Vec<_>>().iter()),
+        sorted(expected.iter())
+    );
+}
+
+#[test]
+fn set_operations_match_std() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for round in 0..50 {
+        let (a, expected_a) = random_set(&mut rng, round % 20, 30);
+        let (b, expected_b) = random_set(&mut rng, round % 7 * 3, 30);
+
+        assert_eq!(sorted(a.union(&b)), sorted(expected_a.union(&expected_b)));
+        assert_eq!(
+            sorted(a.intersection(&b)),
+            sorted(expected_a.intersection(&expected_b))
+        );
+        assert_eq!(
+            sorted(a.difference(&b)),
+            sorted(expected_a.difference(&expected_b))
+        );
+        assert_eq!(
+            sorted(b.difference(&a)),
+            sorted(expected_b.difference(&expected_a))
+        );
+        assert_eq!(
+            sorted(a.symmetric_difference(&b)),
+            sorted(expected_a.symmetric_difference(&expected_b))
+        );
+        assert_eq!(a.is_subset(&b), expected_a.is_subset(&expected_b));
+        assert_eq!(a.is_superset(&b), expected_a.is_superset(&expected_b));
+        assert_eq!(a.is_disjoint(&b), expected_a.is_disjoint(&expected_b));
+    }
+}
+
+#[test]
+fn subsets_and_disjoint_sets() {
+    let small: HashSet<u32> = (0..5).collect();
+    let big: HashSet<u32> = (0..10).collect();
+    let other: HashSet<u32> = (10..15).collect();
+    let empty = HashSet::new();
+    assert!(small.is_subset(&big));
+    assert!(!big.is_subset(&small));
+    assert!(big.is_superset(&small));
+    assert!(empty.is_subset(&small));
+    assert!(small.is_subset(&small));
+    assert!(big.is_disjoint(&other));
+    assert!(!big.is_disjoint(&small));
+    assert!(empty.is_disjoint(&empty));
+}
+
+#[test]
+fn set_operations_are_lazy() {
+    let a: HashSet<u32> = (0..1000).collect();
+    let b: HashSet<u32> = (500..1500).collect();
+    let mut common = a.intersection(&b);
+    let first = *common.next().unwrap();
+    assert!((500..1000).contains(&first));
+    assert_eq!(a.union(&b).take(3).count(), 3);
+    assert_eq!(a.union(&b).size_hint().0, 1000);
+    assert_eq!(a.difference(&b).size_hint(), (0, Some(1000)));
+    assert_eq!(a.symmetric_difference(&b).count(), 1000);
+}
+
+#[test]
+fn standard_traits() {
+    let set: HashSet<u32> = vec![1, 2, 3, 2].into_iter().collect();
+    assert_eq!(set.size(), 3);
+    let mut other = HashSet::new();
+    other.extend(&[3, 2]);
+    other.extend(vec![1]);
+    assert_eq!(set, other);
+    assert_eq!(set.clone(), set);
+    other.insert(4);
+    assert_ne!(set, other);
+    let single: HashSet<u32> = vec![7].into_iter().collect();
+    assert_eq!(format!("{:?}", single), "{7}");
+    assert_eq!(sorted((&set).into_iter()), [1, 2, 3]);
+}
+
+#[test]
+fn capacity_follows_the_map() {
+    let mut set: HashSet<u32> = HashSet::with_capacity(20);
+    assert_eq!(set.capacity(), 28);
+    set.extend(0..5);
+    set.shrink_to_fit();
+    assert_eq!(set.capacity(), 7);
+    set.reserve(100);
+    assert!(set.capacity() >= 105);
+    set.clear();
+    assert_eq!(set.capacity(), 0);
+}
@@ -0,0 +1,311 @@
+//! Tests for `Heap`. Shuffled priorities with duplicates are pushed and the
+//! popped order is compared with a sorted copy, for each way of ordering the
+//! heap. The handle tests check that reprioritizing and removing keep the
+//! heap ordered, and that handles of items which left the heap stay invalid
+//! even after their slot is reused. Equality, ordering and hashing are
+//! checked to depend only on the items, not on the heap's layout.
+
+mod common;
+
+use common::Rng;
+use data_structures::{Comparator, Error, Handle, Heap, MaxHeap, MinHeap};
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+/// 200 priorities in `0..50`, so most of them appear several times.
+fn shuffled(seed: u64) -> Vec<u32> {
+    let mut rng = Rng(seed);
+    let mut items: Vec<u32> = (0..200).map(|index| index % 50).collect();
+    for index in (1..items.len()).rev() {
+        items.swap(index, rng.index(index + 1));
+    }
+    items
+}
+
+/// Pops every item; the items carry their own priority.
+fn drain<C: Comparator<u32>>(heap: &mut Heap<u32, u32, C>) -> Vec<u32> {
+    let mut items = Vec::new();
+    while let Ok(priority) = heap.peek_priority() {
+        let priority = *priority;
+        assert_eq!(heap.pop(), Ok(priority));
+        items.push(priority);
+    }
+    assert_eq!(heap.size(), 0);
+    assert_eq!(heap.pop(), Err(Error::Empty));
+    items
+}
+
+#[test]
+fn min_heap_pops_in_ascending_order() {
+    let items = shuffled(0x2545_f491_4f6c_dd1d);
+    let mut heap: MinHeap<u32, u32> = items.iter().map(|&item| (item, item)).collect();
+    assert_eq!(heap.size(), items.len());
+    let mut expected = items.clone();
+    expected.sort_unstable();
+    assert_eq!(drain(&mut heap), expected);
+}
+
+#[test]
+fn max_heap_pops_in_descending_order() {
+    let items = shuffled(0x9e37_79b9_7f4a_7c15);
+    let mut heap: MaxHeap<u32, u32> = MaxHeap::new();
+    heap.extend(items.iter().map(|&item| (item, item)));
+    let mut expected = items.clone();
+    expected.sort_unstable_by(|a, b| b.cmp(a));
+    assert_eq!(drain(&mut heap), expected);
+}
+
+#[test]
+fn closure_comparator_decides_the_order() {
+    // even priorities first, each group in descending order
+    let by_parity = |a: &u32, b: &u32| (a % 2).cmp(&(b % 2)).then(b.cmp(a));
+    let items = shuffled(0x1234_5678_9abc_def1);
+    let mut heap = Heap::with_comparator(by_parity);
+    for &item in &items {
+        heap.push(item, item);
+    }
+    let mut expected = items.clone();
+    expected.sort_unstable_by(by_parity);
+    assert_eq!(drain(&mut heap), expected);
+}
+
+#[test]
+fn custom_comparator_type() {
+    /// Orders by distance to `self.0`.
+    struct Closest(u32);
+
+    impl Comparator<u32> for Closest {
+        fn compare(&self, a: &u32, b: &u32) -> Ordering {
+            let distance = |item: &u32| (*item as i64 - self.0 as i64).abs();
+            distance(a).cmp(&distance(b))
+        }
+    }
+
+    let mut heap = Heap::with_comparator(Closest(20));
+    for &item in &shuffled(7) {
+        heap.push(item, item);
+    }
+    let popped = drain(&mut heap);
+    assert_eq!(popped[..4], [20, 20, 20, 20]);
+    let distances: Vec<i64> = popped
+        .iter()
+        .map(|&item| (item as i64 - 20).abs())
+        .collect();
+    assert!(distances.windows(2).all(|pair| pair[0] <= pair[1]));
+}
+
+/// Pushes priorities `0..count` in a scrambled order, with the priority as
+/// data, and returns the handles indexed by priority.
+fn numbered(count: u32) -> (MinHeap<u32, u32>, Vec<Handle>) {
+    let mut heap = MinHeap::new();
+    let mut handles = vec![None; count as usize];
+    for index in 0..count {
+        let priority = (index * 7) % count;
+        handles[priority as usize] = Some(heap.push(priority, priority));
+    }
+    (heap, handles.into_iter().map(Option::unwrap).collect())
+}
+
+#[test]
+fn change_priority_raises_and_lowers() {
+    let (mut heap, handles) = numbered(20);
+    // raising the root's key moves it to the bottom
+    assert_eq!(heap.change_priority(handles[0], 100), Ok(0));
+    assert_eq!(heap.peek(), Ok(&1));
+
+    // lowering a key moves the item to the top
+    assert_eq!(heap.change_priority(handles[15], 0), Ok(15));
+    assert_eq!(heap.peek(), Ok(&15));
+    assert_eq!(heap.priority(handles[15]), Ok(&0));
+    assert_eq!(heap.change_priority(handles[15], 30), Ok(0));
+    assert_eq!(heap.peek(), Ok(&1));
+
+    // keeping the key leaves the order alone
+    assert_eq!(heap.change_priority(handles[5], 5), Ok(5));
+
+    let mut expected: Vec<u32> = (1..20).filter(|&item| item != 15).collect();
+    expected.push(15);
+    expected.push(0);
+    let mut popped = Vec::new();
+    while let Ok(item) = heap.pop() {
+        popped.push(item);
+    }
+    assert_eq!(popped, expected);
+}
+
+#[test]
+fn remove_root_middle_and_last() {
+    let (mut heap, handles) = numbered(15);
+    let root = *heap.peek().unwrap();
+    assert_eq!(heap.remove(handles[root as usize]), Ok(root));
+
+    let last = *heap.iter().last().unwrap().1;
+    assert_eq!(heap.remove(handles[last as usize]), Ok(last));
+
+    let middle = *heap.iter().nth(heap.size() / 2).unwrap().1;
+    assert_eq!(heap.remove(handles[middle as usize]), Ok(middle));
+
+    assert_eq!(heap.size(), 12);
+    let mut popped = Vec::new();
+    while let Ok(item) = heap.pop() {
+        popped.push(item);
+    }
+    let expected: Vec<u32> = (0..15)
+        .filter(|item| ![root, last, middle].contains(item))
+        .collect();
+    assert_eq!(popped, expected);
+}
+
+#[test]
+fn live_and_dead_handles() {
+    let mut heap: MinHeap<i32, &str> = MinHeap::new();
+    let first = heap.push(2, "first");
+    let second = heap.push(1, "second");
+    assert!(heap.contains(first));
+    assert_eq!(heap.get(first), Ok(&"first"));
+    assert_eq!(heap.priority(first), Ok(&2));
+
+    assert_eq!(heap.pop(), Ok("second"));
+    assert!(!heap.contains(second));
+    assert_eq!(heap.get(second), Err(Error::NotFound));
+    assert_eq!(heap.priority(second), Err(Error::NotFound));
+    assert_eq!(heap.change_priority(second, 0), Err(Error::NotFound));
+    assert_eq!(heap.remove(second), Err(Error::NotFound));
+    assert_eq!(heap.get(first), Ok(&"first"));
+}
+
+#[test]
+fn stale_handles_stay_dead_after_their_slot_is_reused() {
+    let mut heap: MinHeap<i32, &str> = MinHeap::new();
+    let popped = heap.push(1, "popped");
+    let removed = heap.push(2, "removed");
+    let kept = heap.push(3, "kept");
+    assert_eq!(heap.pop(), Ok("popped"));
+    assert_eq!(heap.remove(removed), Ok("removed"));
+
+    // both new items take the freed slots
+    let reused = [heap.push(0, "new"), heap.push(4, "newer")];
+    for stale in [popped, removed].iter().copied() {
+        assert_ne!(reused[0], stale);
+        assert_ne!(reused[1], stale);
+        assert!(!heap.contains(stale));
+        assert_eq!(heap.get(stale), Err(Error::NotFound));
+        assert_eq!(heap.priority(stale), Err(Error::NotFound));
+        assert_eq!(heap.change_priority(stale, 10), Err(Error::NotFound));
+        assert_eq!(heap.remove(stale), Err(Error::NotFound));
+    }
+    assert_eq!(heap.size(), 3);
+    assert_eq!(heap.get(reused[0]), Ok(&"new"));
+    assert_eq!(heap.get(reused[1]), Ok(&"newer"));
+    assert_eq!(heap.get(kept), Ok(&"kept"));
+    assert_eq!(heap.pop(), Ok("new"));
+}
+
+#[test]
+fn clear_invalidates_every_handle() {
+    let (mut heap, handles) = numbered(10);
+    heap.clear();
+    assert_eq!(heap.size(), 0);
+    assert_eq!(heap.peek(), Err(Error::Empty));
+    assert!(handles.iter().all(|&handle| !heap.contains(handle)));
+
+    let handle = heap.push(5, 5);
+    assert!(handles.iter().all(|&old| old != handle));
+    assert!(handles.iter().all(|&old| heap.get(old).is_err()));
+    assert_eq!(heap.pop(), Ok(5));
+}
+
+#[test]
+fn random_handle_operations_match_a_model() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let mut heap: MinHeap<u32, u32> = MinHeap::new();
+    // (handle, priority, data) of every item in the heap
+    let mut live: Vec<(Handle, u32, u32)> = Vec::new();
+    let mut dead: Vec<Handle> = Vec::new();
+    for step in 0..3000 {
+        match rng.below(5) {
+            0 | 1 => {
+                let priority = rng.below(100);
+                live.push((heap.push(priority, step), priority, step));
+            }
+            2 if !live.is_empty() => {
+                let index = rng.index(live.len());
+                let priority = rng.below(100);
+                let old = heap.change_priority(live[index].0, priority);
+                assert_eq!(old, Ok(live[index].1));
+                live[index].1 = priority;
+            }
+            3 if !live.is_empty() => {
+                let (handle, _, data) = live.swap_remove(rng.index(live.len()));
+                assert_eq!(heap.remove(handle), Ok(data));
+                dead.push(handle);
+            }
+            _ => {
+                let top = live.iter().map(|item| item.1).min();
+                assert_eq!(heap.peek_priority().ok().copied(), top);
+                if let Ok(data) = heap.pop() {
+                    let index = live.iter().position(|item| item.2 == data).unwrap();
+                    assert_eq!(Some(live[index].1), top);
+                    dead.push(live.swap_remove(index).0);
+                }
+            }
+        }
+        assert_eq!(heap.size(), live.len());
+    }
+    for &(handle, priority, data) in &live {
+        assert_eq!(heap.get(handle), Ok(&data));
+        assert_eq!(heap.priority(handle), Ok(&priority));
+    }
+    assert!(dead.iter().all(|&handle| !heap.contains(handle)));
+}
+
+#[test]
+fn equality_order_and_hash_ignore_the_layout() {
+    fn hash_of<H: Hash>(value: &H) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let items = shuffled(0x1234_5678_9abc_def1);
+    let forward: MinHeap<u32, u32> = items.iter().map(|&item| (item, item * 2)).collect();
+    let backward: MinHeap<u32, u32> = items.iter().rev().map(|&item| (item, item * 2)).collect();
+    assert!(forward.iter().ne(backward.iter()));
+    assert_eq!(forward, backward);
+    assert_eq!(hash_of(&forward), hash_of(&backward));
+    assert_eq!(forward.cmp(&backward), Ordering::Equal);
+
+    // the same pairs, but one of them twice, is a different multiset
+    let mut longer = backward.clone();
+    longer.push(items[0], items[0] * 2);
+    assert_ne!(forward, longer);
+    // and so is one with the same priorities but another item
+    let mut changed = backward.clone();
+    let priority = *changed.peek_priority().unwrap();
+    let data = changed.pop().unwrap();
+    changed.push(priority, data + 1);
+    assert_ne!(forward, changed);
+    // sorted `(priority, item)` pairs are compared like lists
+    let small: MinHeap<u32, u32> = vec![(1, 5), (3, 0)].into_iter().collect();
+    let large: MinHeap<u32, u32> = vec![(3, 0), (2, 0)].into_iter().collect();
+    assert!(small < large);
+    assert!(MinHeap::<u32, u32>::new() < small);
+
+    // heaps can be fields of derived structs
+    #[derive(PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+    struct Queue {
+        name: &'static str,
+        jobs: MaxHeap<u32, u32>,
+    }
+    let queue = |jobs: &[(u32, u32)]| Queue {
+        name: "jobs",
+        jobs: jobs.iter().copied().collect(),
+    };
+    assert_eq!(queue(&[(1, 1), (2, 2)]), queue(&[(2, 2), (1, 1)]));
+    assert!(queue(&[(1, 1)]) < queue(&[(1, 2)]));
+    let mut queues = HashSet::new();
+    queues.insert(queue(&[(1, 1), (2, 2)]));
+    assert!(queues.contains(&queue(&[(2, 2), (1, 1)])));
+}
@@ -0,0 +1,701 @@
+//! Tests for `LinkedList`. The ownership tests check that every item handed
+//! to the list is dropped exactly once. The suite passes under Miri, which
+//! also catches leaked nodes, use-after-free and aliasing violations:
+//!
+//! ```text
+//! cargo +nightly miri test --test linked_list
+//! ```
+
+mod common;
+
+use common::{Rng, TrackedValue, Tracker};
+use data_structures::{Error, LinkedList};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+fn values(list: &LinkedList<TrackedValue>) -> Vec<u32> {
+    (0..list.size())
+        .map(|index| list.get(index).unwrap().value)
+        .collect()
+}
+
+#[test]
+fn drop_frees_every_item() {
+    let tracker = Tracker::default();
+    {
+        let mut list = LinkedList::new();
+        for value in 0..5 {
+            list.add(tracker.item(value));
+        }
+        assert_eq!(values(&list), [0, 1, 2, 3, 4]);
+        assert_eq!(tracker.drops(), 0);
+    }
+    assert_eq!(tracker.drops(), 5);
+}
+
+#[test]
+fn insert_links_items_in_order() {
+    let tracker = Tracker::default();
+    let mut list = LinkedList::new();
+    list.insert(0, tracker.item(2)).unwrap();
+    list.insert(0, tracker.item(0)).unwrap();
+    list.insert(1, tracker.item(1)).unwrap();
+    list.insert(3, tracker.item(3)).unwrap();
+    assert_eq!(values(&list), [0, 1, 2, 3]);
+
+    let rejected = list.insert(5, tracker.item(5));
+    assert_eq!(rejected, Err(Error::IndexOutOfBounds { index: 5, len: 4 }));
+    assert_eq!(tracker.drops(), 1);
+
+    drop(list);
+    assert_eq!(tracker.drops(), 5);
+}
+
+#[test]
+fn remove_hands_out_items_without_dropping_them() {
+    let tracker = Tracker::default();
+    let mut list = LinkedList::new();
+    for value in 0..6 {
+        list.add(tracker.item(value));
+    }
+
+    let first = list.remove(0).unwrap();
+    let last = list.remove(4).unwrap();
+    let middle = list.remove(1).unwrap();
+    assert_eq!((first.value, middle.value, last.value), (0, 2, 5));
+    assert_eq!(values(&list), [1, 3, 4]);
+    assert_eq!(tracker.drops(), 0);
+
+    drop((first, middle, last));
+    assert_eq!(tracker.drops(), 3);
+    assert_eq!(
+        list.remove(3).unwrap_err(),
+        Error::IndexOutOfBounds { index: 3, len: 3 }
+    );
+
+    while list.size() > 0 {
+        list.remove(list.size() - 1).unwrap();
+    }
+    assert_eq!(tracker.drops(), 6);
+}
+
+#[test]
+fn remove_item_unlinks_the_matching_node() {
+    let tracker = Tracker::default();
+    let mut list = LinkedList::new();
+    for value in 0..4 {
+        list.add(tracker.item(value));
+    }
+
+    let removed = list.remove_item(tracker.item(2)).unwrap();
+    assert_eq!(removed.value, 2);
+    assert_eq!(values(&list), [0, 1, 3]);
+    assert_eq!(
+        list.remove_item(tracker.item(7)).unwrap_err(),
+        Error::NotFound
+    );
+
+    drop(removed);
+    drop(list);
+    // four list items plus the two probes
+    assert_eq!(tracker.drops(), 6);
+}
+
+#[test]
+fn set_drops_the_replaced_item() {
+    let tracker = Tracker::default();
+    let mut list = LinkedList::new();
+    list.add(tracker.item(0));
+    list.add(tracker.item(1));
+
+    list.set(1, tracker.item(10)).unwrap();
+    assert_eq!(tracker.drops(), 1);
+    assert_eq!(values(&list), [0, 10]);
+
+    drop(list);
+    assert_eq!(tracker.drops(), 3);
+}
+
+#[test]
+fn clear_frees_nodes_and_list_stays_usable() {
+    let tracker = Tracker::default();
+    let mut list = LinkedList::new();
+    for value in 0..3 {
+        list.add(tracker.item(value));
+    }
+
+    list.clear();
+    assert_eq!(list.size(), 0);
+    assert_eq!(tracker.drops(), 3);
+    list.clear();
+
+    list.add(tracker.item(4));
+    list.insert(0, tracker.item(3)).unwrap();
+    assert_eq!(values(&list), [3, 4]);
+
+    drop(list);
+    assert_eq!(tracker.drops(), 5);
+}
+
+#[test]
+fn cursor_edits_keep_ownership_intact() {
+    let tracker = Tracker::default();
+    let mut list = LinkedList::new();
+    for value in 0..4 {
+        list.add(tracker.item(value));
+    }
+
+    let mut back = {
+        let mut cursor = list.cursor_front();
+        cursor.move_next();
+        assert_eq!(cursor.remove_current().unwrap().value, 1);
+        cursor.insert_after(tracker.item(5));
+        cursor.split_after()
+    };
+    assert_eq!(tracker.drops(), 1);
+    assert_eq!(values(&list), [0, 2]);
+    assert_eq!(values(&back), [5, 3]);
+
+    back.add(tracker.item(6));
+    drop(list);
+    assert_eq!(tracker.drops(), 3);
+    drop(back);
+    assert_eq!(tracker.drops(), 6);
+}
+
+#[test]
+fn cursor_wraps_around_through_the_ghost() {
+    let mut list = numbers(3);
+    let mut cursor = list.cursor_front();
+    assert_eq!(cursor.index(), Some(0));
+
+    // backwards from the front onto the ghost, then on to the back
+    cursor.move_prev();
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.peek_next(), Some(&mut 0));
+    assert_eq!(cursor.peek_prev(), Some(&mut 2));
+    cursor.move_prev();
+    assert_eq!(cursor.index(), Some(2));
+    assert_eq!(cursor.current(), Some(&mut 2));
+    assert_eq!(cursor.peek_next(), None);
+    assert_eq!(cursor.peek_prev(), Some(&mut 1));
+
+    // forwards from the back onto the ghost, then on to the front
+    cursor.move_next();
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.current(), None);
+    cursor.move_next();
+    assert_eq!(cursor.index(), Some(0));
+    assert_eq!(cursor.peek_prev(), None);
+    assert_eq!(cursor.peek_next(), Some(&mut 1));
+
+    // inserting on the ghost adds to the ends of the list
+    cursor.move_prev();
+    cursor.insert_before(3);
+    cursor.insert_after(-1);
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.peek_next(), Some(&mut -1));
+    assert_eq!(cursor.peek_prev(), Some(&mut 3));
+    cursor.move_prev();
+    assert_eq!(cursor.index(), Some(4));
+    assert_eq!(cursor.current(), Some(&mut 3));
+    assert!(list.iter().copied().eq(-1..4));
+
+    let mut empty: LinkedList<i32> = LinkedList::new();
+    let mut cursor = empty.cursor_back();
+    assert_eq!(cursor.index(), None);
+    cursor.move_next();
+    assert_eq!(cursor.index(), None);
+    cursor.move_prev();
+    assert_eq!(cursor.index(), None);
+    assert_eq!(cursor.peek_next(), None);
+    assert_eq!(cursor.remove_current(), None);
+    assert_eq!(cursor.split_after().size(), 0);
+    assert_eq!(cursor.split_before().size(), 0);
+}
+
+#[test]
+fn cursor_edits_match_a_vec_model() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for round in 0..20 {
+        let mut list = numbers(round % 6);
+        let mut model: Vec<i32> = (0..round % 6).collect();
+        // position of the cursor in the model, `None` for the ghost
+        let mut position = if model.is_empty() { None } else { Some(0) };
+        let mut cursor = list.cursor_front();
+        for step in 0..200 {
+            let value = 100 + step;
+            match rng.below(10) {
+                0 | 1 => {
+                    cursor.move_next();
+                    position = match position {
+                        None if model.is_empty() => None,
+                        None => Some(0),
+                        Some(index) if index + 1 < model.len() => Some(index + 1),
+                        Some(_) => None,
+                    };
+                }
+                2 | 3 => {
+                    cursor.move_prev();
+                    position = match position {
+                        None => model.len().checked_sub(1),
+                        Some(index) => index.checked_sub(1),
+                    };
+                }
+                4 => {
+                    cursor.insert_before(value);
+                    match position {
+                        None => model.push(value),
+                        Some(index) => {
+                            model.insert(index, value);
+                            position = Some(index + 1);
+                        }
+                    }
+                }
+                5 => {
+                    cursor.insert_after(value);
+                    let index = position.map_or(0, |index| index + 1);
+                    model.insert(index, value);
+                }
+                6 | 7 => {
+                    let removed = position.map(|index| model.remove(index));
+                    assert_eq!(cursor.remove_current(), removed);
+                    position = position.filter(|&index| index < model.len());
+                }
+                8 => {
+                    let tail = match position {
+                        None => model.split_off(0),
+                        Some(index) => model.split_off(index + 1),
+                    };
+                    assert!(cursor.split_after().iter().eq(tail.iter()));
+                }
+                _ => {
+                    let head: Vec<i32> = match position {
+                        None => std::mem::take(&mut model),
+                        Some(index) => {
+                            position = Some(0);
+                            model.drain(..index).collect()
+                        }
+                    };
+                    assert!(cursor.split_before().iter().eq(head.iter()));
+                }
+            }
+            let previous = match position {
+                None => model.last(),
+                Some(index) => index.checked_sub(1).map(|index| &model[index]),
+            };
+            let next = match position {
+                None => model.first(),
+                Some(index) => model.get(index + 1),
+            };
+            assert_eq!(cursor.index(), position);
+            assert_eq!(
+                cursor.current().copied(),
+                position.map(|index| model[index])
+            );
+            assert_eq!(cursor.peek_prev().copied(), previous.copied());
+            assert_eq!(cursor.peek_next().copied(), next.copied());
+        }
+        assert!(list.iter().eq(model.iter()));
+        assert!(list.iter().rev().eq(model.iter().rev()));
+        assert_eq!(list.size(), model.len());
+    }
+}
+
+fn numbers(count: i32) -> LinkedList<i32> {
+    let mut list = LinkedList::new();
+    for value in 0..count {
+        list.add(value);
+    }
+    list
+}
+
+#[test]
+fn iter_runs_backwards() {
+    let list = numbers(4);
+    let reversed: Vec<i32> = list.iter().rev().copied().collect();
+    assert_eq!(reversed, [3, 2, 1, 0]);
+}
+
+#[test]
+fn iter_front_and_back_meet_in_the_middle() {
+    let list = numbers(5);
+    let mut iter = list.iter();
+    assert_eq!(iter.len(), 5);
+    assert_eq!(iter.next(), Some(&0));
+    assert_eq!(iter.next_back(), Some(&4));
+    assert_eq!(iter.next_back(), Some(&3));
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.next(), Some(&1));
+    assert_eq!(iter.next_back(), Some(&2));
+    assert_eq!(iter.len(), 0);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn iter_alternating_ends_yields_every_item_once() {
+    for count in 0..6 {
+        let list = numbers(count);
+        let mut iter = list.iter();
+        let mut seen = Vec::new();
+        loop {
+            let item = if seen.len() % 2 == 0 {
+                iter.next()
+            } else {
+                iter.next_back()
+            };
+            match item {
+                Some(&value) => seen.push(value),
+                None => break,
+            }
+        }
+        seen.sort_unstable();
+        assert_eq!(seen, (0..count).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn iter_on_empty_list_is_exhausted() {
+    let list: LinkedList<i32> = LinkedList::new();
+    let mut iter = list.iter();
+    assert_eq!(iter.len(), 0);
+    assert_eq!(iter.next_back(), None);
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn iter_mut_from_both_ends() {
+    let mut list = numbers(4);
+    {
+        let mut iter = list.iter_mut();
+        *iter.next_back().unwrap() = 30;
+        *iter.next().unwrap() = 0;
+        for item in iter {
+            *item *= 10;
+        }
+    }
+    let values: Vec<i32> = list.iter().copied().collect();
+    assert_eq!(values, [0, 10, 20, 30]);
+}
+
+#[test]
+fn into_iter_from_both_ends_drops_the_rest() {
+    let tracker = Tracker::default();
+    let mut list = LinkedList::new();
+    for value in 0..5 {
+        list.add(tracker.item(value));
+    }
+
+    let mut iter = list.into_iter();
+    assert_eq!(iter.len(), 5);
+    assert_eq!(iter.next_back().unwrap().value, 4);
+    assert_eq!(iter.next().unwrap().value, 0);
+    assert_eq!(iter.len(), 3);
+    assert_eq!(tracker.drops(), 2);
+
+    drop(iter);
+    assert_eq!(tracker.drops(), 5);
+}
+
+/// Addresses of the items, to tell relinked nodes from copied data.
+fn addresses<T>(list: &LinkedList<T>) -> Vec<*const T> {
+    let mut addresses: Vec<_> = list.iter().map(|item| item as *const T).collect();
+    addresses.sort_unstable();
+    addresses
+}
+
+/// Checks that walking backwards visits the same items as walking forwards.
+fn assert_linked<T: PartialEq + std::fmt::Debug>(list: &LinkedList<T>) {
+    let forward: Vec<_> = list.iter().collect();
+    let mut backward: Vec<_> = list.iter().rev().collect();
+    backward.reverse();
+    assert_eq!(forward, backward);
+    assert_eq!(forward.len(), list.size());
+}
+
+#[test]
+fn insert_sorted_goes_behind_equal_items() {
+    let mut list = LinkedList::new();
+    for (key, tag) in [(3, 'a'), (1, 'b'), (3, 'c'), (2, 'd'), (0, 'e'), (3, 'f')].iter() {
+        // pairs compare by key first, so the tags show where equal keys went
+        let position = list.iter().filter(|(other, _)| other <= key).count();
+        list.insert_sorted((*key, *tag));
+        assert_eq!(list.get(position), Ok(&(*key, *tag)));
+    }
+    assert!(list.is_sorted());
+    assert_linked(&list);
+    let tags: String = list.iter().map(|(_, tag)| tag).collect();
+    assert_eq!(tags, "ebdacf");
+}
+
+#[test]
+fn sort_matches_a_stable_sort_and_reuses_the_nodes() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for size in 0..60 {
+        let items: Vec<(u32, u32)> = (0..size).map(|index| (rng.below(8), index)).collect();
+        let mut list: LinkedList<(u32, u32)> = items.iter().copied().collect();
+        let before = addresses(&list);
+
+        // sort by key only, the second half of each pair checks stability
+        list.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut expected = items.clone();
+        expected.sort_by_key(|item| item.0);
+        assert!(list.iter().eq(expected.iter()));
+        assert_linked(&list);
+        assert_eq!(addresses(&list), before);
+
+        list.sort_by(|a, b| b.cmp(a));
+        expected.sort_by(|a, b| b.cmp(a));
+        assert!(list.iter().eq(expected.iter()));
+        assert_linked(&list);
+    }
+}
+
+#[test]
+fn merge_sorted_relinks_both_lists() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for round in 0..50 {
+        let mut left: Vec<(u32, char)> = (0..round % 9).map(|_| (rng.below(10), 'l')).collect();
+        let mut right: Vec<(u32, char)> = (0..round % 13).map(|_| (rng.below(10), 'r')).collect();
+        left.sort_unstable();
+        right.sort_unstable();
+        let mut list: LinkedList<_> = left.iter().copied().collect();
+        let mut other: LinkedList<_> = right.iter().copied().collect();
+        let mut nodes = addresses(&list);
+        nodes.extend(addresses(&other));
+        nodes.sort_unstable();
+
+        list.merge_sorted(&mut other);
+        // a stable sort of the concatenation puts equal keys from the left
+        // list first, like the merge does
+        let mut expected = left.clone();
+        expected.extend(right.iter().copied());
+        expected.sort_by_key(|item| item.0);
+        assert!(list.iter().eq(expected.iter()));
+        assert!(list.is_sorted());
+        assert_linked(&list);
+        assert_eq!(addresses(&list), nodes);
+        assert_eq!(other.size(), 0);
+        assert_eq!(other.iter().next(), None);
+        other.add((0, 'o'));
+        assert_eq!(other.size(), 1);
+    }
+}
+
+/// Items with this value panic when compared.
+const FRAGILE: u32 = 99;
+
+impl PartialOrd for TrackedValue {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        assert!(
+            self.value != FRAGILE && other.value != FRAGILE,
+            "fragile item"
+        );
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+/// Checks that the list still holds every value of `expected`, in any
+/// order, with consistent links.
+fn assert_permutation(list: &LinkedList<TrackedValue>, expected: &[u32]) {
+    let mut held = values(list);
+    held.sort_unstable();
+    let mut expected = expected.to_vec();
+    expected.sort_unstable();
+    assert_eq!(held, expected);
+    let backward: Vec<u32> = list.iter().rev().map(|item| item.value).collect();
+    assert!(backward.iter().rev().eq(values(list).iter()));
+}
+
+#[test]
+fn sort_by_keeps_every_item_when_compare_panics() {
+    let mut rng = Rng(0x0123_4567_89ab_cdef);
+    for size in [2, 3, 5, 8, 13, 33].iter().copied() {
+        let items: Vec<u32> = (0..size).map(|_| rng.below(10)).collect();
+        let mut sorted: LinkedList<u32> = items.iter().copied().collect();
+        let mut needed = 0;
+        sorted.sort_by(|a, b| {
+            needed += 1;
+            a.cmp(b)
+        });
+        // panic on every comparison the sort makes, one at a time
+        for limit in 0..needed {
+            let tracker = Tracker::default();
+            let mut list = LinkedList::new();
+            for &value in &items {
+                list.add(tracker.item(value));
+            }
+            let mut comparisons = 0;
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                list.sort_by(|a, b| {
+                    assert!(comparisons < limit, "comparison limit");
+                    comparisons += 1;
+                    a.value.cmp(&b.value)
+                })
+            }));
+            assert!(result.is_err());
+            assert_permutation(&list, &items);
+            assert_eq!(tracker.drops(), 0);
+            drop(list);
+            assert_eq!(tracker.drops(), items.len());
+        }
+    }
+}
+
+#[test]
+fn merge_sorted_keeps_every_item_when_compare_panics() {
+    let tracker = Tracker::default();
+    let mut list = LinkedList::new();
+    let mut other = LinkedList::new();
+    for &value in [1, 3, 5, 7].iter() {
+        list.add(tracker.item(value));
+    }
+    for &value in [2, 4, FRAGILE, 6].iter() {
+        other.add(tracker.item(value));
+    }
+    let result = catch_unwind(AssertUnwindSafe(|| list.merge_sorted(&mut other)));
+    assert!(result.is_err());
+    assert_permutation(&list, &[1, 2, 3, 4, 5, 6, 7, FRAGILE]);
+    assert_eq!(other.size(), 0);
+    drop(list);
+    drop(other);
+    assert_eq!(tracker.drops(), 8);
+}
+
+#[test]
+fn is_sorted_min_and_max() {
+    let mut list: LinkedList<i32> = LinkedList::new();
+    assert!(list.is_sorted());
+    assert_eq!(list.min_item(), None);
+    assert_eq!(list.max_item(), None);
+    list.extend(vec![4, 1, 7, 1, 7, 3]);
+    assert!(!list.is_sorted());
+    // called on the owned list, next to `Ord::min` comparing two lists
+    assert_eq!(list.min_item(), Some(&1));
+    assert_eq!(list.max_item(), Some(&7));
+    let shorter: LinkedList<i32> = vec![4, 1].into_iter().collect();
+    assert!(list.clone().min(shorter.clone()) == shorter);
+    list.sort();
+    assert!(list.is_sorted());
+    assert!(list.iter().copied().eq([1, 1, 3, 4, 7, 7]));
+
+    let floats: LinkedList<f64> = vec![1.0, f64::NAN].into_iter().collect();
+    assert!(!floats.is_sorted());
+}
+
+#[test]
+fn dedup_drops_the_removed_items() {
+    let tracker = Tracker::default();
+    let mut list = LinkedList::new();
+    for value in [1, 1, 2, 3, 3, 3, 1, 4, 4].iter() {
+        list.add(tracker.item(*value));
+    }
+    list.dedup();
+    assert_eq!(values(&list), [1, 2, 3, 1, 4]);
+    assert_eq!(tracker.drops(), 4);
+
+    list.dedup_by(|a, b| a.value / 2 == b.value / 2);
+    assert_eq!(values(&list), [1, 2, 1, 4]);
+    assert_eq!(tracker.drops(), 5);
+    assert_eq!(list.iter().next_back().unwrap().value, 4);
+
+    let mut empty: LinkedList<TrackedValue> = LinkedList::new();
+    empty.dedup();
+    assert_eq!(empty.size(), 0);
+}
+
+#[test]
+fn push_and_pop_at_both_ends() {
+    let tracker = Tracker::default();
+    let mut list = LinkedList::new();
+    list.push_front(tracker.item(2));
+    list.push_front(tracker.item(1));
+    list.add(tracker.item(3));
+    assert_eq!(values(&list), [1, 2, 3]);
+    assert_eq!(list.pop_back().unwrap().value, 3);
+    assert_eq!(list.pop_front().unwrap().value, 1);
+    assert_eq!(list.pop_front().unwrap().value, 2);
+    assert!(list.pop_front().is_none());
+    assert!(list.pop_back().is_none());
+    assert_eq!(tracker.drops(), 3);
+    list.push_front(tracker.item(4));
+    assert_linked(&list);
+}
+
+#[test]
+fn append_moves_every_node() {
+    let mut list = numbers(3);
+    let mut other: LinkedList<i32> = (3..6).collect();
+    let mut nodes = addresses(&list);
+    nodes.extend(addresses(&other));
+    nodes.sort_unstable();
+    list.append(&mut other);
+    assert!(list.iter().copied().eq(0..6));
+    assert_eq!(addresses(&list), nodes);
+    assert_linked(&list);
+    assert_eq!(other.size(), 0);
+    assert_eq!(other.iter().next_back(), None);
+
+    // appending to or from an empty list
+    other.append(&mut list);
+    assert!(other.iter().copied().eq(0..6));
+    assert_eq!(list.size(), 0);
+    other.append(&mut list);
+    assert_eq!(other.size(), 6);
+    list.add(9);
+    assert_linked(&list);
+}
+
+#[test]
+fn split_off_and_splice_at_every_index() {
+    for size in 0..6 {
+        for index in 0..=size {
+            let mut list = numbers(size);
+            let nodes = addresses(&list);
+            let mut tail = list.split_off(index as usize).unwrap();
+            assert!(list.iter().copied().eq(0..index));
+            assert!(tail.iter().copied().eq(index..size));
+            assert_linked(&list);
+            assert_linked(&tail);
+
+            list.append(&mut tail);
+            assert!(list.iter().copied().eq(0..size));
+            assert_eq!(addresses(&list), nodes);
+
+            let mut inserted: LinkedList<i32> = vec![10, 11].into_iter().collect();
+            list.splice_at(index as usize, &mut inserted).unwrap();
+            let mut expected: Vec<i32> = (0..size).collect();
+            expected.splice(index as usize..index as usize, vec![10, 11]);
+            assert!(list.iter().eq(expected.iter()));
+            assert_linked(&list);
+            assert_eq!(inserted.size(), 0);
+        }
+    }
+    let mut list = numbers(2);
+    assert_eq!(
+        list.split_off(3).unwrap_err(),
+        Error::IndexOutOfBounds { index: 3, len: 2 }
+    );
+    assert_eq!(
+        list.splice_at(3, &mut LinkedList::new()),
+        Err(Error::IndexOutOfBounds { index: 3, len: 2 })
+    );
+}
+
+#[test]
+fn reverse_swaps_the_links() {
+    for size in 0..5 {
+        let mut list = numbers(size);
+        let nodes = addresses(&list);
+        list.reverse();
+        assert!(list.iter().copied().eq((0..size).rev()));
+        assert_linked(&list);
+        assert_eq!(addresses(&list), nodes);
+        list.push_front(-1);
+        list.add(size);
+        assert_eq!(list.get(0), Ok(&-1));
+        assert_eq!(list.get(list.size() - 1), Ok(&size));
+    }
+}
@@ -0,0 +1,216 @@
+//! Conformance scenarios every `List` implementation has to pass. Each
+//! scenario is written once against the trait and instantiated for every
+//! implementation by `list_tests!` at the bottom of the file.
+
+use data_structures::{ArenaList, ArrayList, Error, LinkedList, List};
+
+fn filled<L: List<i32> + Default>(items: &[i32]) -> L {
+    let mut list = L::default();
+    for &item in items {
+        list.add(item);
+    }
+    list
+}
+
+fn contents<L: List<i32>>(list: &L) -> Vec<i32> {
+    list.iter().copied().collect()
+}
+
+fn new_list_is_empty<L: List<i32> + Default>() {
+    let list = L::default();
+    assert_eq!(list.size(), 0);
+    assert!(list.is_empty());
+    assert_eq!(
+        list.get(0),
+        Err(Error::IndexOutOfBounds { index: 0, len: 0 })
+    );
+}
+
+fn add_appends_in_order<L: List<i32> + Default>() {
+    let list: L = filled(&[3, 1, 4, 1, 5, 9, 2, 6]);
+    assert_eq!(list.size(), 8);
+    assert!(!list.is_empty());
+    assert_eq!(contents(&list), [3, 1, 4, 1, 5, 9, 2, 6]);
+}
+
+fn get_out_of_bounds_fails<L: List<i32> + Default>() {
+    let list: L = filled(&[1, 2, 3]);
+    assert_eq!(
+        list.get(3),
+        Err(Error::IndexOutOfBounds { index: 3, len: 3 })
+    );
+}
+
+fn set_and_get_mut_replace_items<L: List<i32> + Default>() {
+    let mut list: L = filled(&[1, 2, 3]);
+    list.set(1, 20).unwrap();
+    *list.get_mut(2).unwrap() *= 10;
+    assert_eq!(contents(&list), [1, 20, 30]);
+    assert_eq!(
+        list.set(3, 0),
+        Err(Error::IndexOutOfBounds { index: 3, len: 3 })
+    );
+    assert!(list.get_mut(3).is_err());
+}
+
+fn insert_shifts_later_items<L: List<i32> + Default>() {
+    let mut list = L::default();
+    list.insert(0, 2).unwrap();
+    list.insert(0, 0).unwrap();
+    list.insert(1, 1).unwrap();
+    list.insert(3, 4).unwrap();
+    list.insert(3, 3).unwrap();
+    assert_eq!(contents(&list), [0, 1, 2, 3, 4]);
+    assert_eq!(
+        list.insert(6, 6),
+        Err(Error::IndexOutOfBounds { index: 6, len: 5 })
+    );
+    assert_eq!(list.size(), 5);
+}
+
+fn remove_returns_items<L: List<i32> + Default>() {
+    let mut list: L = filled(&[0, 1, 2, 3, 4, 5]);
+    assert_eq!(list.remove(0), Ok(0));
+    assert_eq!(list.remove(4), Ok(5));
+    assert_eq!(list.remove(1), Ok(2));
+    assert_eq!(contents(&list), [1, 3, 4]);
+    assert_eq!(
+        list.remove(3),
+        Err(Error::IndexOutOfBounds { index: 3, len: 3 })
+    );
+}
+
+fn remove_item_removes_first_match<L: List<i32> + Default>() {
+    let mut list: L = filled(&[1, 2, 3, 2]);
+    assert_eq!(list.remove_item(2), Ok(2));
+    assert_eq!(contents(&list), [1, 3, 2]);
+    assert_eq!(list.remove_item(7), Err(Error::NotFound));
+    assert_eq!(list.size(), 3);
+}
+
+fn clear_empties_the_list<L: List<i32> + Default>() {
+    let mut list: L = filled(&[1, 2, 3]);
+    list.clear();
+    assert!(list.is_empty());
+    list.add(4);
+    assert_eq!(contents(&list), [4]);
+}
+
+fn grows_and_shrinks<L: List<i32> + Default>() {
+    let mut list = L::default();
+    for i in 0..100 {
+        list.insert(i as usize / 2, i).unwrap();
+    }
+    assert_eq!(list.size(), 100);
+    let mut expected = Vec::new();
+    for i in 0..100 {
+        expected.insert(i as usize / 2, i);
+    }
+    assert_eq!(contents(&list), expected);
+    while list.size() > 1 {
+        let index = list.size() / 3;
+        assert_eq!(list.remove(index), Ok(expected.remove(index)));
+    }
+    assert_eq!(contents(&list), expected);
+}
+
+fn iter_matches_get<L: List<i32> + Default>() {
+    let list: L = filled(&[5, 6, 7]);
+    let by_index: Vec<i32> = (0..list.size()).map(|i| *list.get(i).unwrap()).collect();
+    assert_eq!(contents(&list), by_index);
+    assert_eq!(L::default().iter().next(), None);
+}
+
+fn iter_mut_updates_items<L: List<i32> + Default>() {
+    let mut list: L = filled(&[1, 2, 3]);
+    for item in list.iter_mut() {
+        *item *= 2;
+    }
+    assert_eq!(contents(&list), [2, 4, 6]);
+}
+
+fn works_with_owned_items<L: List<String> + Default>() {
+    let mut list = L::default();
+    list.add(String::from("b"));
+    list.insert(0, String::from("a")).unwrap();
+    list.get_mut(1).unwrap().push('!');
+    assert_eq!(list.get(1).map(String::as_str), Ok("b!"));
+    assert_eq!(list.remove_item(String::from("a")), Ok(String::from("a")));
+    assert_eq!(list.remove(0), Ok(String::from("b!")));
+}
+
+macro_rules! list_tests {
+    ($($name:ident: $list:ident,)*) => {
+        $(
+            mod $name {
+                use super::*;
+
+                #[test]
+                fn new_list_is_empty() {
+                    super::new_list_is_empty::<$list<i32>>();
+                }
+
+                #[test]
+                fn add_appends_in_order() {
+                    super::add_appends_in_order::<$list<i32>>();
+                }
+
+                #[test]
+                fn get_out_of_bounds_fails() {
+                    super::get_out_of_bounds_fails::<$list<i32>>();
+                }
+
+                #[test]
+                fn set_and_get_mut_replace_items() {
+                    super::set_and_get_mut_replace_items::<$list<i32>>();
+                }
+
+                #[test]
+                fn insert_shifts_later_items() {
+                    super::insert_shifts_later_items::<$list<i32>>();
+                }
+
+                #[test]
+                fn remove_returns_items() {
+                    super::remove_returns_items::<$list<i32>>();
+                }
+
+                #[test]
+                fn remove_item_removes_first_match() {
+                    super::remove_item_removes_first_match::<$list<i32>>();
+                }
+
+                #[test]
+                fn clear_empties_the_list() {
+                    super::clear_empties_the_list::<$list<i32>>();
+                }
+
+                #[test]
+                fn grows_and_shrinks() {
+                    super::grows_and_shrinks::<$list<i32>>();
+                }
+
+                #[test]
+                fn iter_matches_get() {
+                    super::iter_matches_get::<$list<i32>>();
+                }
+
+                #[test]
+                fn iter_mut_updates_items() {
+                    super::iter_mut_updates_items::<$list<i32>>();
+                }
+
+                #[test]
+                fn works_with_owned_items() {
+                    super::works_with_owned_items::<$list<String>>();
+                }
+            }
+        )*
+    };
+}
+
+list_tests! {
+    array_list: ArrayList,
+    linked_list: LinkedList,
+    arena_list: ArenaList,
+}
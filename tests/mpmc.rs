@@ -0,0 +1,162 @@
+//! Tests for `mpmc::Queue`. The stress tests run several producers and
+//! consumers at once and check that every item arrives exactly once and
+//! that items of one producer are popped in the order they were pushed.
+
+use data_structures::mpmc::Queue;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+const PRODUCERS: usize = 4;
+const CONSUMERS: usize = 4;
+const PER_PRODUCER: usize = 50_000;
+
+#[test]
+fn push_until_full_then_pop_in_order() {
+    let queue = Queue::new(3);
+    assert_eq!(queue.try_pop(), None);
+    for item in 0..3 {
+        assert_eq!(queue.try_push(item), Ok(()));
+    }
+    assert_eq!(queue.try_push(3), Err(3));
+    assert!(queue.is_full());
+    assert_eq!(queue.size(), 3);
+    for item in 0..3 {
+        assert_eq!(queue.try_pop(), Some(item));
+    }
+    assert_eq!(queue.try_pop(), None);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn positions_wrap_around() {
+    for capacity in 1..6 {
+        let queue = Queue::new(capacity);
+        for round in 0..50 {
+            for item in 0..capacity {
+                queue.try_push(round * 10 + item).unwrap();
+            }
+            assert!(queue.try_push(0).is_err());
+            assert_eq!(queue.size(), capacity);
+            for item in 0..capacity {
+                assert_eq!(queue.try_pop(), Some(round * 10 + item));
+            }
+            assert_eq!(queue.size(), 0);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "capacity must not be 0")]
+fn zero_capacity_panics() {
+    let _: Queue<i32> = Queue::new(0);
+}
+
+#[test]
+fn drop_frees_remaining_items() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    struct Counted(Arc<AtomicUsize>);
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let queue = Queue::new(3);
+    for _ in 0..5 {
+        drop(queue.try_push(Counted(Arc::clone(&drops))));
+        drop(queue.try_pop());
+    }
+    assert_eq!(drops.load(Ordering::SeqCst), 5);
+    for _ in 0..3 {
+        assert!(queue.try_push(Counted(Arc::clone(&drops))).is_ok());
+    }
+    drop(queue);
+    assert_eq!(drops.load(Ordering::SeqCst), 8);
+}
+
+/// Runs the producers and consumers and returns what every consumer popped.
+fn run(capacity: usize, blocking: bool) -> Vec<Vec<(usize, usize)>> {
+    let queue = Queue::new(capacity);
+    let popped = AtomicUsize::new(0);
+    thread::scope(|scope| {
+        for producer in 0..PRODUCERS {
+            let queue = &queue;
+            scope.spawn(move || {
+                for sequence in 0..PER_PRODUCER {
+                    let mut item = (producer, sequence);
+                    if blocking {
+                        queue.push(item);
+                    } else {
+                        while let Err(rejected) = queue.try_push(item) {
+                            item = rejected;
+                            thread::yield_now();
+                        }
+                    }
+                }
+            });
+        }
+        let consumers: Vec<_> = (0..CONSUMERS)
+            .map(|_| {
+                let (queue, popped) = (&queue, &popped);
+                scope.spawn(move || {
+                    let mut items = Vec::new();
+                    while popped.load(Ordering::SeqCst) < PRODUCERS * PER_PRODUCER {
+                        let item = if blocking {
+                            // only wait when an item is sure to come
+                            if popped.fetch_add(1, Ordering::SeqCst) >= PRODUCERS * PER_PRODUCER {
+                                break;
+                            }
+                            Some(queue.pop())
+                        } else {
+                            match queue.try_pop() {
+                                Some(item) => {
+                                    popped.fetch_add(1, Ordering::SeqCst);
+                                    Some(item)
+                                }
+                                None => {
+                                    thread::yield_now();
+                                    None
+                                }
+                            }
+                        };
+                        items.extend(item);
+                    }
+                    items
+                })
+            })
+            .collect();
+        consumers
+            .into_iter()
+            .map(|consumer| consumer.join().unwrap())
+            .collect()
+    })
+}
+
+fn check(popped: Vec<Vec<(usize, usize)>>) {
+    let mut seen = vec![vec![false; PER_PRODUCER]; PRODUCERS];
+    for items in popped {
+        let mut last = [None; PRODUCERS];
+        for (producer, sequence) in items {
+            assert!(!seen[producer][sequence], "item popped twice");
+            seen[producer][sequence] = true;
+            assert!(last[producer] < Some(sequence), "items out of order");
+            last[producer] = Some(sequence);
+        }
+    }
+    assert!(seen.iter().flatten().all(|&seen| seen), "item lost");
+}
+
+#[test]
+fn stress_try_push_and_try_pop() {
+    for &capacity in &[1, 3, 64] {
+        check(run(capacity, false));
+    }
+}
+
+#[test]
+fn stress_blocking_push_and_pop() {
+    for &capacity in &[1, 3, 64] {
+        check(run(capacity, true));
+    }
+}
@@ -0,0 +1,129 @@
+//! Tests for `SkipList`. The scenarios shared by all ordered maps live in
+//! `tests/common/ordered_map.rs` and run here on seeded maps, so every run
+//! builds the same lanes. This file adds the skip list specific checks: that
+//! the seed alone decides the lanes, and how the height grows.
+
+mod common;
+
+use common::ordered_map;
+use data_structures::SkipList;
+
+#[test]
+fn new_map_is_empty() {
+    ordered_map::new_map_is_empty(|| SkipList::with_seed(1));
+    assert_eq!(SkipList::<u32, u32>::new().height(), 0);
+}
+
+#[test]
+fn random_operations_match_btree_map() {
+    ordered_map::random_operations_match_std(|| SkipList::with_seed(1), |_| ());
+}
+
+#[test]
+fn same_seed_builds_the_same_lanes() {
+    // keys inserted in a scrambled order, some of them removed again
+    let build = |seed| {
+        let mut map = SkipList::with_seed(seed);
+        for index in 0..1000u32 {
+            map.insert(index * 7 % 1000, index);
+        }
+        for key in (0..1000).step_by(3) {
+            map.remove(&key);
+        }
+        map
+    };
+    let lanes = |map: &SkipList<u32, u32>| -> Vec<(u32, u32, usize)> {
+        map.iter()
+            .map(|(&key, &value)| (key, value, map.node_height(&key).unwrap()))
+            .collect()
+    };
+    let (first, second) = (build(42), build(42));
+    first.check_invariants().unwrap();
+    assert_eq!(first.height(), second.height());
+    assert_eq!(lanes(&first), lanes(&second));
+    assert!(lanes(&first)
+        .iter()
+        .all(|&(_, _, height)| (1..=first.height()).contains(&height)));
+    // another seed keeps the entries but not the lanes
+    let other = build(43);
+    assert!(first.iter().eq(other.iter()));
+    assert_ne!(lanes(&first), lanes(&other));
+    assert_eq!(first.node_height(&0), None);
+    // a zero seed still produces varying heights
+    assert!(build(0).height() > 1);
+}
+
+#[test]
+fn height_grows_logarithmically() {
+    let mut map = SkipList::with_seed(0x1234_5678);
+    for key in 0..4096 {
+        map.insert(key, ());
+    }
+    map.check_invariants().unwrap();
+    // 12 levels are expected; allow for an unlucky seed
+    assert!((8..=24).contains(&map.height()));
+    for key in (0..4096).rev().step_by(2) {
+        map.remove(&key);
+    }
+    map.check_invariants().unwrap();
+    assert!(map.keys().copied().eq((0..4096).step_by(2)));
+    map.clear();
+    assert_eq!(map.height(), 0);
+    map.check_invariants().unwrap();
+}
+
+#[test]
+fn borrowed_lookups_and_index() {
+    let mut map = SkipList::with_seed(3);
+    map.insert("b".to_string(), 2);
+    map.insert("a".to_string(), 1);
+    assert_eq!(map["a"], 1);
+    assert_eq!(map.get("b"), Some(&2));
+    assert!(map.contains_key("a"));
+    assert_eq!(map.get_key_value("a"), Some((&"a".to_string(), &1)));
+    assert_eq!(map.remove_entry("a"), Some(("a".to_string(), 1)));
+    assert_eq!(map.remove("a"), None);
+    assert!(!map.contains_key("a"));
+}
+
+#[test]
+fn ranges_match_btree_map() {
+    ordered_map::ranges_match_std(|| SkipList::with_seed(2));
+}
+
+#[test]
+#[should_panic(expected = "Key does not exist")]
+fn index_missing_key_panics() {
+    ordered_map::index_missing_key_panics(|| SkipList::with_seed(4));
+}
+
+#[test]
+fn mutable_iterators() {
+    ordered_map::mutable_iterators(|| SkipList::with_seed(5));
+}
+
+#[test]
+fn standard_traits() {
+    ordered_map::standard_traits(|| SkipList::with_seed(6));
+}
+
+#[test]
+fn clones_keep_the_lanes() {
+    // and grow them like the original, since the generator state is copied
+    let mut big: SkipList<u32, u32> = SkipList::with_seed(7);
+    big.extend((0..500).map(|key| (key, key)));
+    let mut copy = big.clone();
+    copy.check_invariants().unwrap();
+    for key in 500..1000 {
+        big.insert(key, key);
+        copy.insert(key, key);
+    }
+    assert_eq!(copy, big);
+    assert_eq!(copy.height(), big.height());
+    assert!((0..1000).all(|key| copy.node_height(&key) == big.node_height(&key)));
+}
+
+#[test]
+fn entries_are_dropped_once() {
+    ordered_map::entries_are_dropped_once(|| SkipList::with_seed(8));
+}
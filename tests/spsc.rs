@@ -0,0 +1,119 @@
+//! Tests for `spsc::Queue`. The stress tests pass many items between two
+//! threads through small queues, so both the full and the empty case are
+//! hit constantly.
+
+use data_structures::spsc::Queue;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+#[test]
+fn push_until_full_then_pop_in_order() {
+    let mut queue = Queue::new(3);
+    let (mut producer, mut consumer) = queue.split();
+    assert_eq!(consumer.try_pop(), None);
+    for item in 0..3 {
+        assert_eq!(producer.try_push(item), Ok(()));
+    }
+    assert_eq!(producer.try_push(3), Err(3));
+    assert_eq!(producer.size(), 3);
+    for item in 0..3 {
+        assert_eq!(consumer.try_pop(), Some(item));
+    }
+    assert_eq!(consumer.try_pop(), None);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn positions_wrap_around() {
+    let mut queue = Queue::new(2);
+    let (mut producer, mut consumer) = queue.split();
+    for item in 0..100 {
+        producer.try_push(item).unwrap();
+        if item % 2 == 1 {
+            assert_eq!(consumer.try_pop(), Some(item - 1));
+            assert_eq!(consumer.try_pop(), Some(item));
+        }
+    }
+    assert!(queue.is_empty());
+    assert!(!queue.is_full());
+}
+
+#[test]
+#[should_panic(expected = "capacity must not be 0")]
+fn zero_capacity_panics() {
+    let _: Queue<i32> = Queue::new(0);
+}
+
+#[test]
+fn drop_frees_remaining_items() {
+    let drops = Arc::new(AtomicUsize::new(0));
+    struct Counted(Arc<AtomicUsize>);
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let mut queue = Queue::new(4);
+    {
+        let (mut producer, mut consumer) = queue.split();
+        for _ in 0..4 {
+            assert!(producer.try_push(Counted(Arc::clone(&drops))).is_ok());
+        }
+        assert!(producer.try_push(Counted(Arc::clone(&drops))).is_err());
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+        drop(consumer.try_pop());
+    }
+    assert_eq!(drops.load(Ordering::SeqCst), 2);
+    drop(queue);
+    assert_eq!(drops.load(Ordering::SeqCst), 5);
+}
+
+#[test]
+fn stress_blocking_push_and_pop() {
+    const COUNT: usize = 200_000;
+    for &capacity in &[1, 2, 7, 64] {
+        let mut queue = Queue::new(capacity);
+        let (mut producer, mut consumer) = queue.split();
+        thread::scope(|scope| {
+            scope.spawn(move || {
+                for item in 0..COUNT {
+                    producer.push(item);
+                }
+            });
+            for item in 0..COUNT {
+                assert_eq!(consumer.pop(), item);
+            }
+        });
+        assert!(queue.is_empty());
+    }
+}
+
+#[test]
+fn stress_try_push_and_try_pop() {
+    const COUNT: usize = 200_000;
+    let mut queue = Queue::new(16);
+    let (mut producer, mut consumer) = queue.split();
+    thread::scope(|scope| {
+        scope.spawn(move || {
+            let mut item = 0;
+            while item < COUNT {
+                match producer.try_push(item.to_string()) {
+                    Ok(()) => item += 1,
+                    Err(_) => thread::yield_now(),
+                }
+            }
+        });
+        let mut expected = 0;
+        while expected < COUNT {
+            match consumer.try_pop() {
+                Some(item) => {
+                    assert_eq!(item, expected.to_string());
+                    expected += 1;
+                }
+                None => thread::yield_now(),
+            }
+        }
+    });
+}
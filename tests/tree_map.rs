@@ -0,0 +1,110 @@
+//! Tests for `TreeMap`. The scenarios shared by all ordered maps live in
+//! `tests/common/ordered_map.rs`; this file runs them and adds the AVL
+//! specific checks: the height bound after every step, and `floor` and
+//! `ceiling`.
+
+mod common;
+
+use common::{ordered_map, Rng};
+use data_structures::TreeMap;
+use std::collections::BTreeMap;
+
+/// AVL trees with `size` nodes are at most about 1.44 log2(size) high.
+fn max_height(size: usize) -> usize {
+    (1.45 * ((size + 2) as f64).log2()) as usize + 1
+}
+
+#[test]
+fn new_map_is_empty() {
+    ordered_map::new_map_is_empty(TreeMap::new);
+    assert_eq!(TreeMap::<u32, u32>::new().height(), 0);
+}
+
+#[test]
+fn random_operations_match_btree_map() {
+    ordered_map::random_operations_match_std(TreeMap::new, |map| {
+        assert!(map.height() <= max_height(map.size()));
+    });
+}
+
+#[test]
+fn sorted_inserts_stay_balanced() {
+    let mut map = TreeMap::new();
+    for key in 0..1000 {
+        map.insert(key, ());
+    }
+    map.check_invariants().unwrap();
+    assert!(map.height() <= max_height(1000));
+    for key in (0..1000).rev().step_by(2) {
+        map.remove(&key);
+    }
+    map.check_invariants().unwrap();
+    assert!(map.height() <= max_height(500));
+    assert!(map.keys().copied().eq((0..1000).step_by(2)));
+}
+
+#[test]
+fn ranges_match_btree_map() {
+    ordered_map::ranges_match_std(TreeMap::new);
+}
+
+#[test]
+fn floor_and_ceiling() {
+    let map: TreeMap<u32, char> = vec![(10, 'a'), (20, 'b'), (30, 'c')].into_iter().collect();
+    assert_eq!(map.floor(&5), None);
+    assert_eq!(map.floor(&10), Some((&10, &'a')));
+    assert_eq!(map.floor(&25), Some((&20, &'b')));
+    assert_eq!(map.floor(&99), Some((&30, &'c')));
+    assert_eq!(map.ceiling(&5), Some((&10, &'a')));
+    assert_eq!(map.ceiling(&20), Some((&20, &'b')));
+    assert_eq!(map.ceiling(&21), Some((&30, &'c')));
+    assert_eq!(map.ceiling(&31), None);
+
+    let mut rng = Rng(0x1234_5678_9abc_def1);
+    let mut map = TreeMap::new();
+    let mut expected = BTreeMap::new();
+    for _ in 0..300 {
+        let key = rng.below(1000);
+        map.insert(key, ());
+        expected.insert(key, ());
+    }
+    for key in 0..1001 {
+        assert_eq!(map.floor(&key), expected.range(..=key).next_back());
+        assert_eq!(map.ceiling(&key), expected.range(key..).next());
+    }
+}
+
+#[test]
+fn borrowed_lookups_and_index() {
+    let mut map = TreeMap::new();
+    map.insert("b".to_string(), 2);
+    map.insert("a".to_string(), 1);
+    assert_eq!(map["a"], 1);
+    assert_eq!(map.get("b"), Some(&2));
+    assert!(map.contains_key("a"));
+    assert_eq!(map.get_key_value("a"), Some((&"a".to_string(), &1)));
+    assert_eq!(map.floor("az"), Some((&"a".to_string(), &1)));
+    assert_eq!(map.remove_entry("a"), Some(("a".to_string(), 1)));
+    assert!(!map.contains_key("a"));
+}
+
+#[test]
+#[should_panic(expected = "Key does not exist")]
+fn index_missing_key_panics() {
+    ordered_map::index_missing_key_panics(TreeMap::new);
+}
+
+#[test]
+fn mutable_iterators() {
+    ordered_map::mutable_iterators(TreeMap::new);
+}
+
+#[test]
+fn standard_traits() {
+    ordered_map::standard_traits(TreeMap::new);
+}
+
+#[test]
+fn entries_are_dropped_once() {
+    ordered_map::entries_are_dropped_once(TreeMap::new);
+}